@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::commands::export::{export_timeline, timeline_csv, timeline_json};
+use crate::commands::import::{
+    decode_text, import_csv_data, import_json_data, inspect_csv, CsvImportOptions, PREVIEW_ROWS,
+};
+use crate::commands::markdown::timeline_to_markdown;
+use crate::commands::search::find_events;
+use crate::commands::templates::{apply_template, fetch_templates};
+use crate::commands::timelines::fetch_timelines;
+use crate::db;
+use crate::error::{AppError, AppResult};
+use crate::export::{pdf::generate_pdf, png::generate_png, svg::generate_svg};
+
+const USAGE: &str = "\
+Usage: chronomap-cli --db <chronomap.db> <command> [arguments]
+
+Commands:
+  list                                  List timelines (id, last update, title)
+  templates                             List templates (id, name)
+  search <timeline-id> <query>          Search a timeline's events
+  import json <file> [--dry-run]        Import a JSON export as a new timeline
+  import csv <timeline-id> <file>       Import CSV rows into a timeline
+         [--map field=Header]...        Column mapping; suggested from the headers when omitted
+  export <timeline-id> <format>         Export as json, csv, md, svg, png or pdf
+         [--output <file>]              Write to a file instead of standard output
+  template <template-id> <title>        Create a timeline from a template
+
+Options:
+  --options <json>                      Export or CSV import options, as the app sends them
+  -h, --help                            Show this help";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+    Svg,
+    Png,
+    Pdf,
+}
+
+impl ExportFormat {
+    fn parse(name: &str) -> AppResult<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "md" | "markdown" => Ok(Self::Markdown),
+            "svg" => Ok(Self::Svg),
+            "png" => Ok(Self::Png),
+            "pdf" => Ok(Self::Pdf),
+            other => Err(AppError::Validation(format!("Unknown export format '{other}'"))),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Help,
+    List,
+    Templates,
+    Search { timeline_id: String, query: String },
+    ImportJson { file: PathBuf, dry_run: bool },
+    ImportCsv { timeline_id: String, file: PathBuf, mapping: HashMap<String, String> },
+    Export { timeline_id: String, format: ExportFormat, output: Option<PathBuf> },
+    Template { template_id: String, title: String },
+}
+
+#[derive(Debug, PartialEq)]
+struct Invocation {
+    db: Option<PathBuf>,
+    command: Command,
+    /// Raw JSON for the export or CSV import options struct.
+    options: Option<String>,
+}
+
+fn usage_error(message: impl Into<String>) -> AppError {
+    AppError::Validation(message.into())
+}
+
+fn parse_args(args: &[String]) -> AppResult<Invocation> {
+    let mut db = None;
+    let mut options = None;
+    let mut output = None;
+    let mut dry_run = false;
+    let mut mapping = HashMap::new();
+    let mut positional = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next().cloned().ok_or_else(|| usage_error(format!("{flag} needs a value")))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => {
+                return Ok(Invocation { db, command: Command::Help, options });
+            }
+            "--db" => db = Some(PathBuf::from(value(arg)?)),
+            "--options" => options = Some(value(arg)?),
+            "-o" | "--output" => output = Some(PathBuf::from(value(arg)?)),
+            "--dry-run" => dry_run = true,
+            "--map" => {
+                let pair = value(arg)?;
+                let (field, header) = pair
+                    .split_once('=')
+                    .ok_or_else(|| usage_error(format!("--map expects field=Header, got '{pair}'")))?;
+                mapping.insert(field.to_string(), header.to_string());
+            }
+            flag if flag.starts_with("--") => {
+                return Err(usage_error(format!("Unknown option '{flag}'")));
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let positional: Vec<&str> = positional.iter().map(String::as_str).collect();
+    let command = match positional.as_slice() {
+        [] => Command::Help,
+        ["list"] => Command::List,
+        ["templates"] => Command::Templates,
+        ["search", timeline_id, query @ ..] if !query.is_empty() => Command::Search {
+            timeline_id: timeline_id.to_string(),
+            query: query.join(" "),
+        },
+        ["import", "json", file] => Command::ImportJson { file: PathBuf::from(file), dry_run },
+        ["import", "csv", timeline_id, file] => Command::ImportCsv {
+            timeline_id: timeline_id.to_string(),
+            file: PathBuf::from(file),
+            mapping,
+        },
+        ["export", timeline_id, format] => Command::Export {
+            timeline_id: timeline_id.to_string(),
+            format: ExportFormat::parse(format)?,
+            output,
+        },
+        ["template", template_id, title] => Command::Template {
+            template_id: template_id.to_string(),
+            title: title.to_string(),
+        },
+        _ => return Err(usage_error(format!("Unrecognized command '{}'", positional.join(" ")))),
+    };
+
+    Ok(Invocation { db, command, options })
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> AppError {
+    AppError::Internal(format!("{}: {e}", path.display()))
+}
+
+fn read_file(path: &Path) -> AppResult<Vec<u8>> {
+    std::fs::read(path).map_err(|e| io_error(path, e))
+}
+
+/// Options the app would send as JSON; defaults when none were given.
+fn parse_options<T: DeserializeOwned + Default>(options: Option<&str>) -> AppResult<T> {
+    match options {
+        Some(json) => serde_json::from_str(json)
+            .map_err(|e| AppError::Validation(format!("Invalid --options: {e}"))),
+        None => Ok(T::default()),
+    }
+}
+
+fn write_json(out: &mut dyn Write, value: &impl Serialize) -> AppResult<()> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| AppError::Internal(format!("JSON serialization error: {e}")))?;
+    writeln!(out, "{json}").map_err(|e| AppError::Internal(e.to_string()))
+}
+
+fn export(conn: &Connection, timeline_id: &str, format: ExportFormat, options: Option<&str>) -> AppResult<Vec<u8>> {
+    Ok(match format {
+        ExportFormat::Json => timeline_json(conn, timeline_id)?.into_bytes(),
+        ExportFormat::Csv => timeline_csv(conn, timeline_id)?.into_bytes(),
+        ExportFormat::Markdown => {
+            let exported = export_timeline(conn, timeline_id)?;
+            timeline_to_markdown(&exported, &parse_options(options)?).into_bytes()
+        }
+        ExportFormat::Svg => generate_svg(conn, timeline_id, &parse_options(options)?)?.into_bytes(),
+        ExportFormat::Png => generate_png(conn, timeline_id, &parse_options(options)?)?,
+        ExportFormat::Pdf => generate_pdf(conn, timeline_id, &parse_options(options)?)?,
+    })
+}
+
+fn execute(conn: &Connection, invocation: &Invocation, out: &mut dyn Write) -> AppResult<()> {
+    let options = invocation.options.as_deref();
+    let write_err = |e: std::io::Error| AppError::Internal(e.to_string());
+    match &invocation.command {
+        Command::Help => writeln!(out, "{USAGE}").map_err(write_err),
+        Command::List => {
+            for timeline in fetch_timelines(conn)? {
+                writeln!(out, "{}\t{}\t{}", timeline.id, timeline.updated_at, timeline.title)
+                    .map_err(write_err)?;
+            }
+            Ok(())
+        }
+        Command::Templates => {
+            for template in fetch_templates(conn)? {
+                writeln!(out, "{}\t{}", template.id, template.name).map_err(write_err)?;
+            }
+            Ok(())
+        }
+        Command::Search { timeline_id, query } => {
+            for result in find_events(conn, timeline_id, query)? {
+                writeln!(out, "{}\t{}\t{}", result.event_id, result.start_date, result.title)
+                    .map_err(write_err)?;
+            }
+            Ok(())
+        }
+        Command::ImportJson { file, dry_run } => {
+            let data = decode_text(&read_file(file)?);
+            write_json(out, &import_json_data(conn, &data, *dry_run)?)
+        }
+        Command::ImportCsv { timeline_id, file, mapping } => {
+            let data = decode_text(&read_file(file)?);
+            let csv_options: CsvImportOptions = parse_options(options)?;
+            let mapping = if mapping.is_empty() {
+                inspect_csv(&data, &csv_options, PREVIEW_ROWS)?.suggested_mapping
+            } else {
+                mapping.clone()
+            };
+            write_json(out, &import_csv_data(conn, timeline_id, &data, &mapping, &csv_options)?)
+        }
+        Command::Export { timeline_id, format, output } => {
+            let bytes = export(conn, timeline_id, *format, options)?;
+            match output {
+                Some(path) => std::fs::write(path, bytes).map_err(|e| io_error(path, e)),
+                None => out.write_all(&bytes).map_err(write_err),
+            }
+        }
+        Command::Template { template_id, title } => {
+            let timeline_id = apply_template(conn, template_id, title)?;
+            writeln!(out, "{timeline_id}").map_err(write_err)
+        }
+    }
+}
+
+/// Entry point of the `chronomap-cli` binary, which runs one command against
+/// the database file given with `--db`.
+pub fn run(args: impl IntoIterator<Item = String>) -> ExitCode {
+    let args: Vec<String> = args.into_iter().collect();
+    let invocation = match parse_args(&args) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = (|| {
+        let conn = match (&invocation.command, &invocation.db) {
+            (Command::Help, _) => Connection::open_in_memory()?,
+            (_, Some(path)) if path.is_file() => db::init_db(path)?,
+            (_, Some(path)) => {
+                return Err(AppError::NotFound(format!("No database at {}", path.display())));
+            }
+            (_, None) => return Err(usage_error("--db <chronomap.db> is required")),
+        };
+        let mut out = Vec::new();
+        execute(&conn, &invocation, &mut out)?;
+        Ok(out)
+    })();
+
+    match result {
+        Ok(out) => {
+            // A closed pipe (e.g. `| head`) is not an error for the caller
+            match std::io::stdout().lock().write_all(&out) {
+                Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+                    eprintln!("{e}");
+                    ExitCode::FAILURE
+                }
+                _ => ExitCode::SUCCESS,
+            }
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_test_db;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    fn run_in(conn: &Connection, line: &str) -> AppResult<String> {
+        let mut out = Vec::new();
+        execute(conn, &parse_args(&args(line))?, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let invocation = parse_args(&args("--db /tmp/c.db export tl pdf -o /tmp/t.pdf")).unwrap();
+        assert_eq!(invocation.db, Some(PathBuf::from("/tmp/c.db")));
+        assert_eq!(
+            invocation.command,
+            Command::Export {
+                timeline_id: "tl".to_string(),
+                format: ExportFormat::Pdf,
+                output: Some(PathBuf::from("/tmp/t.pdf")),
+            }
+        );
+
+        let invocation = parse_args(&args("import csv tl data.csv --map title=Name")).unwrap();
+        let Command::ImportCsv { mapping, .. } = invocation.command else { panic!() };
+        assert_eq!(mapping.get("title").map(String::as_str), Some("Name"));
+
+        assert!(parse_args(&args("export tl docx")).is_err());
+        assert!(parse_args(&args("search tl")).is_err());
+        assert!(parse_args(&args("list --map title")).is_err());
+    }
+
+    #[test]
+    fn test_execute_commands() {
+        let conn = init_test_db().unwrap();
+        let dir = std::env::temp_dir().join(format!("chronomap-cli-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let template_id = fetch_templates(&conn).unwrap()[0].id.clone();
+        let timeline_id = run_in(&conn, &format!("template {template_id} Nightly")).unwrap();
+        let timeline_id = timeline_id.trim();
+        assert!(run_in(&conn, "list").unwrap().contains(&format!("{timeline_id}\t")));
+
+        let csv_path = dir.join("events.csv");
+        std::fs::write(&csv_path, "Name,Year\nMoon landing,1969\nBerlin Wall falls,1989\n").unwrap();
+        let report = run_in(&conn, &format!("import csv {timeline_id} {}", csv_path.display())).unwrap();
+        assert!(report.contains("\"eventsCreated\": 2"), "{report}");
+
+        let found = run_in(&conn, &format!("search {timeline_id} moon")).unwrap();
+        assert_eq!(found.lines().count(), 1);
+        assert!(found.contains("Moon landing"));
+
+        let csv = run_in(&conn, &format!("export {timeline_id} csv")).unwrap();
+        assert!(csv.contains("Berlin Wall falls,1989"));
+
+        let json_path = dir.join("timeline.json");
+        run_in(&conn, &format!("export {timeline_id} json -o {}", json_path.display())).unwrap();
+        let report = run_in(&conn, &format!("import json {} --dry-run", json_path.display())).unwrap();
+        assert!(report.contains("\"eventsCreated\": 2"), "{report}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
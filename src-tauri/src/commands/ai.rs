@@ -1,10 +1,11 @@
 use std::sync::Mutex;
 
-use chrono::NaiveDate;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "desktop")]
 use tauri::State;
 
+use crate::db::dates::HistoricalDate;
 use crate::error::{AppError, AppResult};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -119,41 +120,7 @@ fn normalize_generated_event(event: AiGeneratedEvent) -> Option<AiGeneratedEvent
 }
 
 fn normalize_date(raw: &str) -> Option<String> {
-    let trimmed = raw.trim();
-    if trimmed.is_empty() {
-        return None;
-    }
-
-    if trimmed.len() == 4 && trimmed.chars().all(|c| c.is_ascii_digit()) {
-        return Some(trimmed.to_string());
-    }
-
-    if trimmed.len() == 7
-        && trimmed.as_bytes().get(4) == Some(&b'-')
-        && trimmed.chars().enumerate().all(|(i, ch)| i == 4 || ch.is_ascii_digit())
-    {
-        return Some(trimmed.to_string());
-    }
-
-    if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
-        return Some(date.format("%Y-%m-%d").to_string());
-    }
-
-    for fmt in [
-        "%B %d, %Y",
-        "%B %-d, %Y",
-        "%b %d, %Y",
-        "%b %-d, %Y",
-        "%d %B %Y",
-        "%-d %B %Y",
-        "%Y/%m/%d",
-    ] {
-        if let Ok(date) = NaiveDate::parse_from_str(trimmed, fmt) {
-            return Some(date.format("%Y-%m-%d").to_string());
-        }
-    }
-
-    None
+    HistoricalDate::parse(raw).ok().map(|date| date.to_string())
 }
 
 fn normalize_event_type(raw: Option<&str>) -> String {
@@ -199,6 +166,7 @@ fn normalize_confidence(raw: Option<f64>) -> Option<f64> {
     Some(normalized.clamp(0.0, 1.0))
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn ai_check_connection(db: State<'_, Mutex<Connection>>) -> AppResult<Vec<AiModel>> {
     let (host, _) = {
@@ -272,6 +240,7 @@ fn structured_event_system(extra_rules: &str) -> String {
     )
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn ai_research_topic(
     db: State<'_, Mutex<Connection>>,
@@ -299,6 +268,7 @@ pub async fn ai_research_topic(
     Ok(parse_events_from_response(&response))
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn ai_fill_gaps(
     db: State<'_, Mutex<Connection>>,
@@ -325,6 +295,7 @@ pub async fn ai_fill_gaps(
     Ok(parse_events_from_response(&response))
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn ai_generate_description(
     db: State<'_, Mutex<Connection>>,
@@ -348,6 +319,7 @@ pub async fn ai_generate_description(
     generate(&host, &model, &prompt, &system, 0.4).await
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn ai_suggest_connections(
     db: State<'_, Mutex<Connection>>,
@@ -366,6 +338,7 @@ pub async fn ai_suggest_connections(
     generate(&host, &model, &prompt, &system, 0.2).await
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn ai_fact_check(
     db: State<'_, Mutex<Connection>>,
@@ -385,6 +358,7 @@ pub async fn ai_fact_check(
     generate(&host, &model, &prompt, &system, 0.3).await
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn ai_chat(
     db: State<'_, Mutex<Connection>>,
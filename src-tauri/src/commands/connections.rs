@@ -1,8 +1,10 @@
 use std::sync::Mutex;
 
 use rusqlite::Connection;
+#[cfg(feature = "desktop")]
 use tauri::State;
 
+use crate::db::journal::{self, Snapshot};
 use crate::db::models::Connection as ConnModel;
 use crate::error::{AppError, AppResult};
 
@@ -39,6 +41,7 @@ fn row_to_connection(row: &rusqlite::Row<'_>) -> rusqlite::Result<ConnModel> {
     })
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn create_connection(
     db: State<'_, Mutex<Connection>>,
@@ -49,19 +52,25 @@ pub fn create_connection(
     let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
     let conn_type = input.connection_type.unwrap_or_else(|| "related".to_string());
 
-    conn.execute(
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
         "INSERT INTO connections (id, timeline_id, source_event_id, target_event_id, connection_type, label, color, created_at)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
         rusqlite::params![id, input.timeline_id, input.source_event_id, input.target_event_id, conn_type, input.label, input.color, now],
     )?;
+    let after = Snapshot::connections(&tx, std::slice::from_ref(&id))?;
+    journal::record(&tx, "Create connection", Snapshot::default(), after)?;
 
-    conn.query_row(
+    let connection = tx.query_row(
         "SELECT id, timeline_id, source_event_id, target_event_id, connection_type, label, color, created_at FROM connections WHERE id = ?1",
         [&id],
         row_to_connection,
-    ).map_err(AppError::Database)
+    )?;
+    tx.commit()?;
+    Ok(connection)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn list_connections(
     db: State<'_, Mutex<Connection>>,
@@ -77,40 +86,53 @@ pub fn list_connections(
     Ok(results)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_connection(
     db: State<'_, Mutex<Connection>>,
     input: UpdateConnection,
 ) -> AppResult<ConnModel> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let tx = conn.unchecked_transaction()?;
+    let ids = std::slice::from_ref(&input.id);
+    let before = Snapshot::connections(&tx, ids)?;
 
     if let Some(ref ct) = input.connection_type {
-        conn.execute("UPDATE connections SET connection_type = ?1 WHERE id = ?2", rusqlite::params![ct, input.id])?;
+        tx.execute("UPDATE connections SET connection_type = ?1 WHERE id = ?2", rusqlite::params![ct, input.id])?;
     }
     if let Some(ref label) = input.label {
-        conn.execute("UPDATE connections SET label = ?1 WHERE id = ?2", rusqlite::params![label, input.id])?;
+        tx.execute("UPDATE connections SET label = ?1 WHERE id = ?2", rusqlite::params![label, input.id])?;
     }
     if let Some(ref color) = input.color {
-        conn.execute("UPDATE connections SET color = ?1 WHERE id = ?2", rusqlite::params![color, input.id])?;
+        tx.execute("UPDATE connections SET color = ?1 WHERE id = ?2", rusqlite::params![color, input.id])?;
     }
+    journal::record(&tx, "Edit connection", before, Snapshot::connections(&tx, ids)?)?;
 
-    conn.query_row(
+    let connection = tx.query_row(
         "SELECT id, timeline_id, source_event_id, target_event_id, connection_type, label, color, created_at FROM connections WHERE id = ?1",
         [&input.id],
         row_to_connection,
     ).map_err(|e| match e {
         rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(format!("Connection {} not found", input.id)),
         other => AppError::Database(other),
-    })
+    })?;
+    tx.commit()?;
+    Ok(connection)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn delete_connection(db: State<'_, Mutex<Connection>>, id: String) -> AppResult<()> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-    let changes = conn.execute("DELETE FROM connections WHERE id = ?1", [&id])?;
+    let tx = conn.unchecked_transaction()?;
+    let ids = std::slice::from_ref(&id);
+    let before = Snapshot::connections(&tx, ids)?;
+    let changes = tx.execute("DELETE FROM connections WHERE id = ?1", [&id])?;
     if changes == 0 {
         return Err(AppError::NotFound(format!("Connection {id} not found")));
     }
+    journal::record(&tx, "Delete connection", before, Snapshot::connections(&tx, ids)?)?;
+    tx.commit()?;
     Ok(())
 }
 
@@ -1,9 +1,13 @@
 use std::sync::Mutex;
 
 use rusqlite::Connection;
+#[cfg(feature = "desktop")]
 use tauri::State;
 
-use crate::db::models::{CreateEvent, Event, UpdateEvent};
+use super::revisions;
+use crate::db::dates::HistoricalDate;
+use crate::db::journal::{self, Snapshot};
+use crate::db::models::{CreateEvent, Event, UndatedEvent, UpdateEvent};
 use crate::error::{AppError, AppResult};
 
 fn row_to_event(row: &rusqlite::Row<'_>) -> rusqlite::Result<Event> {
@@ -30,8 +34,33 @@ fn row_to_event(row: &rusqlite::Row<'_>) -> rusqlite::Result<Event> {
     })
 }
 
+/// Decode an event row, or return the row's stored start date when it cannot
+/// be read (legacy dates `backfill_sort_keys` left as written).
+fn row_to_dated_event(row: &rusqlite::Row<'_>) -> rusqlite::Result<Result<Event, String>> {
+    let raw: String = row.get("start_date")?;
+    match HistoricalDate::parse(&raw) {
+        Ok(_) => row_to_event(row).map(Ok),
+        Err(_) => Ok(Err(raw)),
+    }
+}
+
 const EVENT_COLUMNS: &str = "id, timeline_id, track_id, title, description, start_date, end_date, event_type, importance, color, icon, image_path, external_link, tags, source, ai_generated, ai_confidence, created_at, updated_at";
 
+pub fn fetch_event(conn: &Connection, id: &str) -> AppResult<Event> {
+    let query = format!("SELECT {EVENT_COLUMNS} FROM events WHERE id = ?1");
+    conn.query_row(&query, [id], row_to_dated_event)
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                AppError::NotFound(format!("Event {id} not found"))
+            }
+            other => AppError::Database(other),
+        })?
+        .map_err(|raw| {
+            AppError::Validation(format!("Event {id} has an unreadable start date '{raw}'; set a new start date"))
+        })
+}
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn create_event(db: State<'_, Mutex<Connection>>, input: CreateEvent) -> AppResult<Event> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
@@ -45,60 +74,98 @@ pub fn create_event(db: State<'_, Mutex<Connection>>, input: CreateEvent) -> App
         return Err(AppError::Validation("Title must be 500 characters or fewer".to_string()));
     }
 
+    let start_date = HistoricalDate::parse(&input.start_date)?;
+    let end_date = HistoricalDate::parse_optional(input.end_date.as_deref())?;
     let event_type = input.event_type.unwrap_or_else(|| "point".to_string());
     let importance = input.importance.unwrap_or(3);
     let description = input.description.unwrap_or_default();
     let tags = input.tags.unwrap_or_default();
     let ai_generated = input.ai_generated.unwrap_or(false);
 
-    conn.execute(
-        "INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, event_type, importance, color, icon, tags, source, ai_generated, ai_confidence, created_at, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, event_type, importance, color, icon, tags, source, ai_generated, ai_confidence, created_at, updated_at, start_sort_key, end_sort_key)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
         rusqlite::params![
             id, input.timeline_id, input.track_id, input.title, description,
-            input.start_date, input.end_date, event_type, importance,
+            start_date, end_date, event_type, importance,
             input.color, input.icon, tags, input.source,
-            ai_generated, input.ai_confidence, now, now
+            ai_generated, input.ai_confidence, now, now,
+            start_date.sort_key(), end_date.map(|d| d.sort_key())
         ],
     )?;
+    let after = Snapshot::events(&tx, std::slice::from_ref(&id))?;
+    journal::record(&tx, "Create event", Snapshot::default(), after)?;
 
     let query = format!("SELECT {EVENT_COLUMNS} FROM events WHERE id = ?1");
-    conn.query_row(&query, [&id], row_to_event)
-        .map_err(AppError::Database)
+    let event = tx.query_row(&query, [&id], row_to_event)?;
+    tx.commit()?;
+    Ok(event)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn get_event(db: State<'_, Mutex<Connection>>, id: String) -> AppResult<Event> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-    let query = format!("SELECT {EVENT_COLUMNS} FROM events WHERE id = ?1");
+    fetch_event(&conn, &id)
+}
 
-    conn.query_row(&query, [&id], row_to_event)
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => {
-                AppError::NotFound(format!("Event {id} not found"))
-            }
-            other => AppError::Database(other),
-        })
+/// All events of a timeline in chronological order. Events whose start date
+/// cannot be read are listed by `fetch_undated_events` instead.
+pub fn fetch_timeline_events(conn: &Connection, timeline_id: &str) -> AppResult<Vec<Event>> {
+    let query = format!(
+        "SELECT {EVENT_COLUMNS} FROM events WHERE timeline_id = ?1 ORDER BY start_sort_key, start_date"
+    );
+    let mut stmt = conn.prepare(&query)?;
+
+    let rows = stmt
+        .query_map([timeline_id], row_to_dated_event)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows.into_iter().filter_map(Result::ok).collect())
 }
 
+/// Events of a timeline whose stored start date cannot be read, so the user
+/// can give them a new one.
+pub fn fetch_undated_events(conn: &Connection, timeline_id: &str) -> AppResult<Vec<UndatedEvent>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, track_id, title, start_date FROM events WHERE timeline_id = ?1 ORDER BY title",
+    )?;
+    let rows = stmt
+        .query_map([timeline_id], |row| {
+            Ok(UndatedEvent {
+                id: row.get(0)?,
+                track_id: row.get(1)?,
+                title: row.get(2)?,
+                start_date: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(rows.into_iter().filter(|e| HistoricalDate::parse(&e.start_date).is_err()).collect())
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn list_undated_events(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+) -> AppResult<Vec<UndatedEvent>> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    fetch_undated_events(&conn, &timeline_id)
+}
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn list_events(
     db: State<'_, Mutex<Connection>>,
     timeline_id: String,
 ) -> AppResult<Vec<Event>> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-    let query = format!(
-        "SELECT {EVENT_COLUMNS} FROM events WHERE timeline_id = ?1 ORDER BY start_date"
-    );
-    let mut stmt = conn.prepare(&query)?;
-
-    let events = stmt
-        .query_map([&timeline_id], row_to_event)?
-        .collect::<Result<Vec<_>, _>>()?;
-
-    Ok(events)
+    fetch_timeline_events(&conn, &timeline_id)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_event(db: State<'_, Mutex<Connection>>, input: UpdateEvent) -> AppResult<Event> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
@@ -122,8 +189,21 @@ pub fn update_event(db: State<'_, Mutex<Connection>>, input: UpdateEvent) -> App
     maybe_set!(track_id, "track_id");
     maybe_set!(title, "title");
     maybe_set!(description, "description");
-    maybe_set!(start_date, "start_date");
-    maybe_set!(end_date, "end_date");
+    if let Some(ref raw) = input.start_date {
+        let start_date = HistoricalDate::parse(raw)?;
+        sets.push(format!("start_date = ?{param_idx}, start_sort_key = ?{}", param_idx + 1));
+        params_list.push(Box::new(start_date));
+        params_list.push(Box::new(start_date.sort_key()));
+        param_idx += 2;
+    }
+    if let Some(ref raw) = input.end_date {
+        // An empty end date clears it.
+        let end_date = HistoricalDate::parse_optional(Some(raw))?;
+        sets.push(format!("end_date = ?{param_idx}, end_sort_key = ?{}", param_idx + 1));
+        params_list.push(Box::new(end_date));
+        params_list.push(Box::new(end_date.map(|d| d.sort_key())));
+        param_idx += 2;
+    }
     maybe_set!(event_type, "event_type");
     maybe_set!(color, "color");
     maybe_set!(icon, "icon");
@@ -144,35 +224,47 @@ pub fn update_event(db: State<'_, Mutex<Connection>>, input: UpdateEvent) -> App
     params_list.push(Box::new(input.id.clone()));
 
     let params_refs: Vec<&dyn rusqlite::types::ToSql> = params_list.iter().map(|b| b.as_ref()).collect();
-    conn.execute(&sql, params_refs.as_slice())?;
-
-    let query = format!("SELECT {EVENT_COLUMNS} FROM events WHERE id = ?1");
-    conn.query_row(&query, [&input.id], row_to_event)
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => {
-                AppError::NotFound(format!("Event {} not found", input.id))
-            }
-            other => AppError::Database(other),
-        })
+    let tx = conn.unchecked_transaction()?;
+    let ids = std::slice::from_ref(&input.id);
+    let before = Snapshot::events(&tx, ids)?;
+    revisions::record_revisions(&tx, ids)?;
+    tx.execute(&sql, params_refs.as_slice())?;
+    journal::record(&tx, "Edit event", before, Snapshot::events(&tx, ids)?)?;
+
+    let event = fetch_event(&tx, &input.id)?;
+    tx.commit()?;
+    Ok(event)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn delete_event(db: State<'_, Mutex<Connection>>, id: String) -> AppResult<()> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-    let changes = conn.execute("DELETE FROM events WHERE id = ?1", [&id])?;
+    let tx = conn.unchecked_transaction()?;
+    let ids = std::slice::from_ref(&id);
+    let before = Snapshot::events(&tx, ids)?;
+    let changes = tx.execute("DELETE FROM events WHERE id = ?1", [&id])?;
     if changes == 0 {
         return Err(AppError::NotFound(format!("Event {id} not found")));
     }
+    journal::record(&tx, "Delete event", before, Snapshot::events(&tx, ids)?)?;
+    tx.commit()?;
     Ok(())
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn bulk_delete_events(db: State<'_, Mutex<Connection>>, ids: Vec<String>) -> AppResult<u32> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
     let placeholders: Vec<String> = ids.iter().enumerate().map(|(i, _)| format!("?{}", i + 1)).collect();
     let sql = format!("DELETE FROM events WHERE id IN ({})", placeholders.join(", "));
     let params: Vec<&dyn rusqlite::types::ToSql> = ids.iter().map(|id| id as &dyn rusqlite::types::ToSql).collect();
-    let changes = conn.execute(&sql, params.as_slice())?;
+    let tx = conn.unchecked_transaction()?;
+    let before = Snapshot::events(&tx, &ids)?;
+    let changes = tx.execute(&sql, params.as_slice())?;
+    let label = format!("Delete {changes} events");
+    journal::record(&tx, &label, before, Snapshot::events(&tx, &ids)?)?;
+    tx.commit()?;
     Ok(changes as u32)
 }
 
@@ -186,6 +278,7 @@ pub struct BulkUpdateInput {
     pub tags: Option<String>,
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn bulk_update_events(db: State<'_, Mutex<Connection>>, input: BulkUpdateInput) -> AppResult<u32> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
@@ -229,7 +322,13 @@ pub fn bulk_update_events(db: State<'_, Mutex<Connection>>, input: BulkUpdateInp
     );
 
     let params_refs: Vec<&dyn rusqlite::types::ToSql> = params_list.iter().map(|b| b.as_ref()).collect();
-    let changes = conn.execute(&sql, params_refs.as_slice())?;
+    let tx = conn.unchecked_transaction()?;
+    let before = Snapshot::events(&tx, &input.ids)?;
+    revisions::record_revisions(&tx, &input.ids)?;
+    let changes = tx.execute(&sql, params_refs.as_slice())?;
+    let label = format!("Update {changes} events");
+    journal::record(&tx, &label, before, Snapshot::events(&tx, &input.ids)?)?;
+    tx.commit()?;
     Ok(changes as u32)
 }
 
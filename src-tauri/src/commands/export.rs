@@ -1,17 +1,43 @@
+use std::collections::HashMap;
+#[cfg(feature = "desktop")]
 use std::sync::Mutex;
 
+#[cfg(feature = "desktop")]
+use chrono::{Datelike, Timelike};
 use rusqlite::Connection;
 use serde::Serialize;
+#[cfg(feature = "desktop")]
 use tauri::State;
 
+#[cfg(feature = "desktop")]
+use super::events::fetch_timeline_events;
+#[cfg(feature = "desktop")]
+use super::ical::events_to_ics;
+#[cfg(feature = "desktop")]
+use super::markdown::{timeline_to_markdown, MarkdownExportOptions};
+#[cfg(feature = "desktop")]
+use crate::db::dates::{DatePrecision, HistoricalDate};
 use crate::error::{AppError, AppResult};
+#[cfg(feature = "desktop")]
+use crate::export::layout::LayoutOptions;
+#[cfg(feature = "desktop")]
+use crate::export::pdf::PdfOptions;
+#[cfg(feature = "desktop")]
+use crate::export::png::PngOptions;
+
+/// Current version of the JSON export format. Version 2 carries every column
+/// and stable ids; version 1 files (titles only) are still accepted on import.
+pub const EXPORT_VERSION: u32 = 2;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportedTimeline {
     pub version: u32,
+    pub id: String,
     pub title: String,
     pub description: String,
+    pub created_at: String,
+    pub updated_at: String,
     pub tracks: Vec<ExportedTrack>,
     pub events: Vec<ExportedEvent>,
     pub connections: Vec<ExportedConnection>,
@@ -20,84 +46,91 @@ pub struct ExportedTimeline {
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportedTrack {
+    pub id: String,
     pub name: String,
     pub color: String,
+    pub sort_order: i32,
+    pub visible: bool,
+    pub created_at: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportedEvent {
+    pub id: String,
     pub title: String,
     pub description: String,
     pub start_date: String,
     pub end_date: Option<String>,
     pub event_type: String,
     pub importance: i32,
+    pub track_id: String,
+    /// Kept alongside `track_id` so v1 readers can still place the event.
     pub track_name: String,
     pub color: Option<String>,
+    pub icon: Option<String>,
+    pub image_path: Option<String>,
+    pub external_link: Option<String>,
     pub tags: String,
     pub source: Option<String>,
+    pub ai_generated: bool,
+    pub ai_confidence: Option<f64>,
+    pub created_at: String,
+    pub updated_at: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportedConnection {
+    pub id: String,
+    pub source_id: String,
+    pub target_id: String,
+    /// Titles are kept alongside the ids so v1 readers can still resolve them.
     pub source_title: String,
     pub target_title: String,
     pub connection_type: String,
     pub label: Option<String>,
+    pub color: Option<String>,
+    pub created_at: String,
 }
 
-#[tauri::command]
-pub fn export_json(
-    db: State<'_, Mutex<Connection>>,
-    timeline_id: String,
-) -> AppResult<String> {
-    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-
-    let (title, description): (String, String) = conn.query_row(
-        "SELECT title, description FROM timelines WHERE id = ?1",
-        [&timeline_id],
-        |row| Ok((row.get(0)?, row.get(1)?)),
+/// Read a whole timeline into the current export format.
+pub fn export_timeline(conn: &Connection, timeline_id: &str) -> AppResult<ExportedTimeline> {
+    let (title, description, created_at, updated_at): (String, String, String, String) = conn.query_row(
+        "SELECT title, description, created_at, updated_at FROM timelines WHERE id = ?1",
+        [timeline_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
     ).map_err(|_| AppError::NotFound(format!("Timeline {timeline_id} not found")))?;
 
     let mut track_stmt = conn.prepare(
-        "SELECT id, name, color FROM tracks WHERE timeline_id = ?1 ORDER BY sort_order",
+        "SELECT id, name, color, sort_order, visible, created_at FROM tracks WHERE timeline_id = ?1 ORDER BY sort_order",
     )?;
-    let tracks_raw: Vec<(String, String, String)> = track_stmt
-        .query_map([&timeline_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+    let tracks: Vec<ExportedTrack> = track_stmt
+        .query_map([timeline_id], |row| {
+            Ok(ExportedTrack {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+                sort_order: row.get(3)?,
+                visible: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?
         .collect::<Result<Vec<_>, _>>()?;
 
-    let track_name_map: std::collections::HashMap<String, String> = tracks_raw
-        .iter()
-        .map(|(id, name, _)| (id.clone(), name.clone()))
-        .collect();
-
-    let tracks: Vec<ExportedTrack> = tracks_raw
+    let track_name_map: HashMap<&str, &str> = tracks
         .iter()
-        .map(|(_, name, color)| ExportedTrack { name: name.clone(), color: color.clone() })
+        .map(|t| (t.id.as_str(), t.name.as_str()))
         .collect();
 
-    struct EventRow {
-        id: String,
-        title: String,
-        description: String,
-        start_date: String,
-        end_date: Option<String>,
-        event_type: String,
-        importance: i32,
-        track_id: String,
-        color: Option<String>,
-        tags: String,
-        source: Option<String>,
-    }
-
     let mut event_stmt = conn.prepare(
-        "SELECT id, title, description, start_date, end_date, event_type, importance, track_id, color, tags, source FROM events WHERE timeline_id = ?1 ORDER BY start_date",
+        "SELECT id, title, description, start_date, end_date, event_type, importance, track_id, color, icon, image_path, external_link, tags, source, ai_generated, ai_confidence, created_at, updated_at
+         FROM events WHERE timeline_id = ?1 ORDER BY start_sort_key, start_date",
     )?;
-    let events_raw: Vec<EventRow> = event_stmt
-        .query_map([&timeline_id], |row| {
-            Ok(EventRow {
+    let events: Vec<ExportedEvent> = event_stmt
+        .query_map([timeline_id], |row| {
+            let track_id: String = row.get(7)?;
+            Ok(ExportedEvent {
                 id: row.get(0)?,
                 title: row.get(1)?,
                 description: row.get(2)?,
@@ -105,87 +138,252 @@ pub fn export_json(
                 end_date: row.get(4)?,
                 event_type: row.get(5)?,
                 importance: row.get(6)?,
-                track_id: row.get(7)?,
+                track_name: track_name_map.get(track_id.as_str()).map(|n| n.to_string()).unwrap_or_default(),
+                track_id,
                 color: row.get(8)?,
-                tags: row.get(9)?,
-                source: row.get(10)?,
+                icon: row.get(9)?,
+                image_path: row.get(10)?,
+                external_link: row.get(11)?,
+                tags: row.get(12)?,
+                source: row.get(13)?,
+                ai_generated: row.get(14)?,
+                ai_confidence: row.get(15)?,
+                created_at: row.get(16)?,
+                updated_at: row.get(17)?,
             })
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
-    let event_title_map: std::collections::HashMap<String, String> = events_raw
-        .iter()
-        .map(|e| (e.id.clone(), e.title.clone()))
-        .collect();
-
-    let events: Vec<ExportedEvent> = events_raw
+    let event_title_map: HashMap<&str, &str> = events
         .iter()
-        .map(|e| ExportedEvent {
-            title: e.title.clone(),
-            description: e.description.clone(),
-            start_date: e.start_date.clone(),
-            end_date: e.end_date.clone(),
-            event_type: e.event_type.clone(),
-            importance: e.importance,
-            track_name: track_name_map.get(&e.track_id).cloned().unwrap_or_default(),
-            color: e.color.clone(),
-            tags: e.tags.clone(),
-            source: e.source.clone(),
-        })
+        .map(|e| (e.id.as_str(), e.title.as_str()))
         .collect();
 
     let mut conn_stmt = conn.prepare(
-        "SELECT source_event_id, target_event_id, connection_type, label FROM connections WHERE timeline_id = ?1",
+        "SELECT id, source_event_id, target_event_id, connection_type, label, color, created_at FROM connections WHERE timeline_id = ?1 ORDER BY created_at, id",
     )?;
     let connections: Vec<ExportedConnection> = conn_stmt
-        .query_map([&timeline_id], |row| {
-            let source_id: String = row.get(0)?;
-            let target_id: String = row.get(1)?;
-            Ok((source_id, target_id, row.get::<_, String>(2)?, row.get::<_, Option<String>>(3)?))
+        .query_map([timeline_id], |row| {
+            let source_id: String = row.get(1)?;
+            let target_id: String = row.get(2)?;
+            Ok(ExportedConnection {
+                id: row.get(0)?,
+                source_title: event_title_map.get(source_id.as_str()).map(|t| t.to_string()).unwrap_or_default(),
+                target_title: event_title_map.get(target_id.as_str()).map(|t| t.to_string()).unwrap_or_default(),
+                source_id,
+                target_id,
+                connection_type: row.get(3)?,
+                label: row.get(4)?,
+                color: row.get(5)?,
+                created_at: row.get(6)?,
+            })
         })?
-        .filter_map(|r| r.ok())
-        .filter_map(|(source_id, target_id, conn_type, label)| {
-            let source_title = event_title_map.get(&source_id)?.clone();
-            let target_title = event_title_map.get(&target_id)?.clone();
-            Some(ExportedConnection { source_title, target_title, connection_type: conn_type, label })
-        })
-        .collect();
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let exported = ExportedTimeline {
-        version: 1,
+    Ok(ExportedTimeline {
+        version: EXPORT_VERSION,
+        id: timeline_id.to_string(),
         title,
         description,
+        created_at,
+        updated_at,
         tracks,
         events,
         connections,
-    };
+    })
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn export_json(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+) -> AppResult<String> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    timeline_json(&conn, &timeline_id)
+}
+
+/// The timeline as the pretty-printed JSON document `import_json` reads back.
+pub fn timeline_json(conn: &Connection, timeline_id: &str) -> AppResult<String> {
+    let exported = export_timeline(conn, timeline_id)?;
 
     serde_json::to_string_pretty(&exported)
         .map_err(|e| AppError::Internal(format!("JSON serialization error: {e}")))
 }
 
+/// A TimelineJS (Knight Lab) JSON document, as loaded by its web embed.
+#[cfg(feature = "desktop")]
+#[derive(Debug, Serialize)]
+struct TimelineJsExport {
+    title: TimelineJsSlide,
+    events: Vec<TimelineJsSlide>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    eras: Vec<TimelineJsSlide>,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Default, Serialize)]
+struct TimelineJsSlide {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_date: Option<TimelineJsDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_date: Option<TimelineJsDate>,
+    text: TimelineJsText,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    media: Option<TimelineJsMedia>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unique_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    background: Option<TimelineJsBackground>,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Serialize)]
+struct TimelineJsDate {
+    year: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    month: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    day: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hour: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minute: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    second: Option<u32>,
+    /// Shown instead of the parts for dates TimelineJS cannot express (circa, decades).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_date: Option<String>,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Default, Serialize)]
+struct TimelineJsText {
+    headline: String,
+    text: String,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Serialize)]
+struct TimelineJsMedia {
+    url: String,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Serialize)]
+struct TimelineJsBackground {
+    color: String,
+}
+
+/// Escape plain text for TimelineJS' HTML fields, keeping line breaks.
+#[cfg(feature = "desktop")]
+fn html_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+/// Date parts down to the stored precision. TimelineJS writes years BCE as
+/// negative numbers (500 BCE is -500).
+#[cfg(feature = "desktop")]
+fn timelinejs_date(raw: &str) -> Option<TimelineJsDate> {
+    let date = HistoricalDate::parse(raw).ok()?;
+    let start = date.start();
+    let precision = date.precision();
+    let part = |min: DatePrecision, value: u32| (precision >= min).then_some(value);
+    Some(TimelineJsDate {
+        year: if date.is_bce() { date.year() - 1 } else { date.year() },
+        month: part(DatePrecision::Month, start.month()),
+        day: part(DatePrecision::Day, start.day()),
+        hour: part(DatePrecision::Time, start.hour()),
+        minute: part(DatePrecision::Time, start.minute()),
+        second: part(DatePrecision::Time, start.second()),
+        display_date: (date.is_circa() || precision < DatePrecision::Year).then(|| date.to_string()),
+    })
+}
+
+/// Convert an export into TimelineJS' format: tracks become groups, era events
+/// with an end date become eras, and images or links become media.
+#[cfg(feature = "desktop")]
+fn timeline_to_timelinejs(exported: &ExportedTimeline) -> TimelineJsExport {
+    let mut events = Vec::new();
+    let mut eras = Vec::new();
+    for event in &exported.events {
+        let slide = TimelineJsSlide {
+            start_date: timelinejs_date(&event.start_date),
+            end_date: event.end_date.as_deref().and_then(timelinejs_date),
+            text: TimelineJsText { headline: html_text(&event.title), text: html_text(&event.description) },
+            media: event
+                .image_path
+                .as_ref()
+                .or(event.external_link.as_ref())
+                .map(|url| TimelineJsMedia { url: url.clone() }),
+            group: Some(event.track_name.clone()).filter(|n| !n.is_empty()),
+            unique_id: Some(event.id.clone()),
+            background: event.color.as_ref().map(|color| TimelineJsBackground { color: color.clone() }),
+        };
+        // Eras only carry dates and text in TimelineJS.
+        if event.event_type == "era" && slide.end_date.is_some() {
+            eras.push(TimelineJsSlide { media: None, group: None, unique_id: None, background: None, ..slide });
+        } else {
+            events.push(slide);
+        }
+    }
+
+    TimelineJsExport {
+        title: TimelineJsSlide {
+            text: TimelineJsText { headline: html_text(&exported.title), text: html_text(&exported.description) },
+            ..Default::default()
+        },
+        events,
+        eras,
+    }
+}
+
+/// Export a timeline as a TimelineJS JSON document for existing web embeds.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn export_timelinejs(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+) -> AppResult<String> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let exported = export_timeline(&conn, &timeline_id)?;
+
+    serde_json::to_string_pretty(&timeline_to_timelinejs(&exported))
+        .map_err(|e| AppError::Internal(format!("JSON serialization error: {e}")))
+}
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn export_csv(
     db: State<'_, Mutex<Connection>>,
     timeline_id: String,
 ) -> AppResult<String> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    timeline_csv(&conn, &timeline_id)
+}
 
-    let track_names: std::collections::HashMap<String, String> = {
+/// The timeline's events as CSV, one row per event in date order.
+pub fn timeline_csv(conn: &Connection, timeline_id: &str) -> AppResult<String> {
+    let track_names: HashMap<String, String> = {
         let mut stmt = conn.prepare("SELECT id, name FROM tracks WHERE timeline_id = ?1")?;
-        let results: Vec<(String, String)> = stmt.query_map([&timeline_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        let results: Vec<(String, String)> = stmt.query_map([timeline_id], |row| Ok((row.get(0)?, row.get(1)?)))?
             .filter_map(|r| r.ok())
             .collect();
         results.into_iter().collect()
     };
 
     let mut stmt = conn.prepare(
-        "SELECT title, start_date, end_date, event_type, importance, track_id, description, tags FROM events WHERE timeline_id = ?1 ORDER BY start_date",
+        "SELECT title, start_date, end_date, event_type, importance, track_id, description, tags FROM events WHERE timeline_id = ?1 ORDER BY start_sort_key, start_date",
     )?;
 
     let mut csv_out = String::from("title,start_date,end_date,event_type,importance,track,description,tags\n");
 
-    let rows = stmt.query_map([&timeline_id], |row| {
+    let rows = stmt.query_map([timeline_id], |row| {
         Ok((
             row.get::<_, String>(0)?,
             row.get::<_, String>(1)?,
@@ -224,61 +422,51 @@ pub fn export_csv(
     Ok(csv_out)
 }
 
+/// Export a timeline as an iCalendar (.ics) file for calendar apps.
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub fn export_markdown(
+pub fn export_ics(
     db: State<'_, Mutex<Connection>>,
     timeline_id: String,
 ) -> AppResult<String> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    timeline_ics(&conn, &timeline_id)
+}
 
-    let title: String = conn.query_row(
-        "SELECT title FROM timelines WHERE id = ?1",
-        [&timeline_id],
-        |row| row.get(0),
-    ).map_err(|_| AppError::NotFound("Timeline not found".to_string()))?;
-
-    let mut stmt = conn.prepare(
-        "SELECT title, start_date, end_date, description, event_type FROM events WHERE timeline_id = ?1 ORDER BY start_date",
-    )?;
-
-    let mut md = format!("# {title}\n\n");
-    let mut current_year = String::new();
-
-    let rows = stmt.query_map([&timeline_id], |row| {
-        Ok((
-            row.get::<_, String>(0)?,
-            row.get::<_, String>(1)?,
-            row.get::<_, Option<String>>(2)?,
-            row.get::<_, String>(3)?,
-            row.get::<_, String>(4)?,
-        ))
-    })?;
-
-    for row in rows {
-        let (ev_title, start, end, desc, _etype) = row?;
-        let year = start.get(..4).unwrap_or("").to_string();
-        if year != current_year {
-            current_year = year.clone();
-            md.push_str(&format!("\n## {year}\n\n"));
-        }
-
-        md.push_str(&format!("### {ev_title}\n\n"));
-        md.push_str(&format!("**Date:** {start}"));
-        if let Some(ref e) = end {
-            md.push_str(&format!(" — {e}"));
-        }
-        md.push_str("\n\n");
-
-        if !desc.is_empty() {
-            md.push_str(&desc);
-            md.push_str("\n\n");
-        }
-    }
+/// The timeline's events as an iCalendar document.
+#[cfg(feature = "desktop")]
+pub fn timeline_ics(conn: &Connection, timeline_id: &str) -> AppResult<String> {
+    let title: String = conn
+        .query_row(
+            "SELECT title FROM timelines WHERE id = ?1",
+            [timeline_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                AppError::NotFound(format!("Timeline {timeline_id} not found"))
+            }
+            other => AppError::Database(other),
+        })?;
+    let events = fetch_timeline_events(conn, timeline_id)?;
+    Ok(events_to_ics(&title, &events))
+}
 
-    Ok(md)
+/// Export a timeline as Markdown, grouped by period or track.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn export_markdown(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+    options: Option<MarkdownExportOptions>,
+) -> AppResult<String> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let exported = export_timeline(&conn, &timeline_id)?;
+    Ok(timeline_to_markdown(&exported, &options.unwrap_or_default()))
 }
 
-fn validate_file_path(path: &str) -> AppResult<()> {
+#[cfg(feature = "desktop")]
+pub fn validate_file_path(path: &str) -> AppResult<()> {
     let p = std::path::Path::new(path);
 
     // Block obviously dangerous paths
@@ -294,6 +482,7 @@ fn validate_file_path(path: &str) -> AppResult<()> {
     Ok(())
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn save_file(path: String, content: String) -> AppResult<()> {
     validate_file_path(&path)?;
@@ -301,13 +490,16 @@ pub fn save_file(path: String, content: String) -> AppResult<()> {
         .map_err(|e| AppError::Internal(format!("Failed to write file: {e}")))
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn read_file(path: String) -> AppResult<String> {
     validate_file_path(&path)?;
-    std::fs::read_to_string(&path)
+    std::fs::read(&path)
+        .map(|bytes| super::import::decode_text(&bytes))
         .map_err(|e| AppError::Internal(format!("Failed to read file: {e}")))
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn show_save_dialog(
     default_path: String,
@@ -323,6 +515,7 @@ pub async fn show_save_dialog(
     Ok(dialog.map(|f| f.path().to_string_lossy().to_string()))
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub async fn show_open_dialog(
     filter_name: String,
@@ -336,22 +529,54 @@ pub async fn show_open_dialog(
     Ok(dialog.map(|f| f.path().to_string_lossy().to_string()))
 }
 
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub async fn show_folder_dialog() -> AppResult<Option<String>> {
+    let dialog = rfd::AsyncFileDialog::new().pick_folder().await;
+    Ok(dialog.map(|f| f.path().to_string_lossy().to_string()))
+}
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn export_svg(
     db: State<'_, Mutex<Connection>>,
     timeline_id: String,
+    options: Option<LayoutOptions>,
+) -> AppResult<String> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    crate::export::svg::generate_svg(&conn, &timeline_id, &options.unwrap_or_default())
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn export_html(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
 ) -> AppResult<String> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-    crate::export::svg::generate_svg(&conn, &timeline_id)
+    crate::export::html::generate_html(&conn, &timeline_id)
 }
 
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn export_png(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+    options: Option<PngOptions>,
+) -> AppResult<Vec<u8>> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    crate::export::png::generate_png(&conn, &timeline_id, &options.unwrap_or_default())
+}
+
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn export_pdf(
     db: State<'_, Mutex<Connection>>,
     timeline_id: String,
+    options: Option<PdfOptions>,
 ) -> AppResult<Vec<u8>> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-    crate::export::pdf::generate_pdf(&conn, &timeline_id)
+    crate::export::pdf::generate_pdf(&conn, &timeline_id, &options.unwrap_or_default())
 }
 
 #[cfg(test)]
@@ -457,6 +682,39 @@ mod tests {
         assert_eq!(conn_count, 1);
     }
 
+    #[test]
+    fn test_export_json_round_trip() {
+        use super::super::import::{import_timeline, ImportedTimeline};
+        use super::export_timeline;
+
+        let conn = init_test_db().unwrap();
+        let tl_id = setup_export_data(&conn);
+        conn.execute(
+            "UPDATE events SET icon = 'flag', color = '#000000', ai_generated = 1, ai_confidence = 0.5 WHERE title = 'Revolution'",
+            [],
+        )
+        .unwrap();
+        let exported = export_timeline(&conn, &tl_id).unwrap();
+        let json = serde_json::to_string(&exported).unwrap();
+
+        // Into a fresh database every id is kept
+        let other = init_test_db().unwrap();
+        let imported: ImportedTimeline = serde_json::from_str(&json).unwrap();
+        let report = import_timeline(&other, &imported).unwrap();
+        assert_eq!(report.timeline_id.as_deref(), Some(tl_id.as_str()));
+        assert_eq!(report.ids_reassigned, 0);
+        let round_tripped = export_timeline(&other, &tl_id).unwrap();
+        assert_eq!(serde_json::to_value(&round_tripped).unwrap(), serde_json::to_value(&exported).unwrap());
+
+        // Into the same database everything gets new ids, connections still resolve
+        let report = import_timeline(&conn, &imported).unwrap();
+        assert_eq!(report.ids_reassigned, 6);
+        assert_eq!(report.connections_created, 1);
+        let copy = export_timeline(&conn, report.timeline_id.as_deref().unwrap()).unwrap();
+        assert_eq!(copy.connections[0].source_title, "Revolution");
+        assert_eq!(copy.events.iter().find(|e| e.title == "Revolution").unwrap().icon.as_deref(), Some("flag"));
+    }
+
     #[test]
     fn test_export_json_event_track_resolution() {
         let conn = init_test_db().unwrap();
@@ -544,6 +802,17 @@ mod tests {
         assert!(csv_out.contains("Science"));
     }
 
+    #[cfg(feature = "desktop")]
+    #[test]
+    fn test_export_ics_missing_timeline() {
+        let conn = init_test_db().unwrap();
+        let tl_id = setup_export_data(&conn);
+        assert!(super::timeline_ics(&conn, &tl_id).unwrap().contains("BEGIN:VCALENDAR"));
+
+        let err = super::timeline_ics(&conn, "missing").unwrap_err();
+        assert!(matches!(err, crate::error::AppError::NotFound(_)));
+    }
+
     #[test]
     fn test_export_markdown_structure() {
         let conn = init_test_db().unwrap();
@@ -601,4 +870,58 @@ mod tests {
         assert!(md.contains("The great revolution"));
         assert!(md.contains("Theory of relativity"));
     }
+
+    #[cfg(feature = "desktop")]
+    #[test]
+    fn test_export_timelinejs_round_trip() {
+        use super::super::import::timelinejs_to_timeline;
+        use super::{export_timeline, timeline_to_timelinejs};
+
+        let conn = init_test_db().unwrap();
+        let tl_id = setup_export_data(&conn);
+        let track_id: String = conn
+            .query_row("SELECT id FROM tracks WHERE timeline_id = ?1 AND name = 'Politics'", [&tl_id], |row| row.get(0))
+            .unwrap();
+        conn.execute(
+            "INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, event_type)
+             VALUES ('era-1', ?1, ?2, 'Age of Revolutions', 'Kings & <crowns>', 'c. 1770', '1850s', 'era')",
+            params![tl_id, track_id],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO events (id, timeline_id, track_id, title, start_date, event_type, external_link)
+             VALUES ('bce', ?1, ?2, 'Marathon', '490 BCE', 'point', 'https://example.org')",
+            params![tl_id, track_id],
+        )
+        .unwrap();
+
+        let exported = export_timeline(&conn, &tl_id).unwrap();
+        let json = serde_json::to_value(timeline_to_timelinejs(&exported)).unwrap();
+        assert_eq!(json["title"]["text"]["headline"], "Export Test");
+        assert_eq!(json["events"].as_array().unwrap().len(), 3);
+        assert_eq!(json["eras"][0]["text"]["text"], "Kings &amp; &lt;crowns&gt;");
+        assert_eq!(json["eras"][0]["start_date"]["display_date"], "c. 1770");
+        let marathon = json["events"].as_array().unwrap().iter().find(|e| e["text"]["headline"] == "Marathon").unwrap();
+        assert_eq!(marathon["start_date"]["year"], -490);
+        assert_eq!(marathon["media"]["url"], "https://example.org");
+        assert_eq!(marathon["group"], "Politics");
+
+        let imported = timelinejs_to_timeline(&json.to_string()).unwrap();
+        let mut dates: Vec<(String, String, Option<String>)> = imported
+            .events
+            .iter()
+            .map(|e| (e.title.clone(), e.start_date.clone(), e.end_date.clone()))
+            .collect();
+        dates.sort();
+        assert_eq!(
+            dates,
+            vec![
+                ("Age of Revolutions".to_string(), "c. 1770".to_string(), Some("1850s".to_string())),
+                ("Marathon".to_string(), "490 BCE".to_string(), None),
+                ("Relativity".to_string(), "1905-06-30".to_string(), Some("1905-09-26".to_string())),
+                ("Revolution".to_string(), "1789-07-14".to_string(), None),
+            ]
+        );
+        assert_eq!(imported.events[3].description.as_deref(), Some("Kings & <crowns>"));
+    }
 }
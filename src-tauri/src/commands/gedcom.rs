@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+use serde::Deserialize;
+#[cfg(feature = "desktop")]
+use tauri::State;
+
+use super::import::{import_in_transaction, ImportReport, ImportedEvent, ImportedTimeline, ImportedTrack};
+use crate::db::dates::HistoricalDate;
+use crate::error::{AppError, AppResult};
+
+/// GEDCOM event tags that are imported, with their label and category track.
+const LIFE_EVENTS: [(&str, &str, &str); 6] = [
+    ("BIRT", "Birth", "Births"),
+    ("DEAT", "Death", "Deaths"),
+    ("MARR", "Marriage", "Marriages"),
+    ("RESI", "Residence", "Residences"),
+    ("EDUC", "Education", "Education"),
+    ("OCCU", "Occupation", "Occupations"),
+];
+
+/// How imported life events are split into tracks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GedcomGrouping {
+    /// One track per individual; a marriage goes on the first spouse's track.
+    #[default]
+    Person,
+    /// One track per kind of event (Births, Deaths, Marriages, ...).
+    Category,
+}
+
+/// A GEDCOM line with its subordinate lines.
+#[derive(Debug, Default)]
+struct Record {
+    xref: Option<String>,
+    tag: String,
+    value: String,
+    children: Vec<Record>,
+}
+
+impl Record {
+    fn child(&self, tag: &str) -> Option<&Record> {
+        self.children.iter().find(|c| c.tag == tag)
+    }
+
+    fn child_value(&self, tag: &str) -> Option<&str> {
+        self.child(tag).map(|c| c.value.trim()).filter(|v| !v.is_empty())
+    }
+
+    /// The value with its CONT (new line) and CONC (same line) continuations.
+    fn text(&self) -> String {
+        let mut text = self.value.clone();
+        for child in &self.children {
+            match child.tag.as_str() {
+                "CONT" => {
+                    text.push('\n');
+                    text.push_str(&child.value);
+                }
+                "CONC" => text.push_str(&child.value),
+                _ => {}
+            }
+        }
+        text.trim().to_string()
+    }
+}
+
+/// Build the record tree from "LEVEL [@XREF@] TAG [VALUE]" lines.
+fn parse_records(data: &str) -> AppResult<Vec<Record>> {
+    fn close(stack: &mut Vec<Record>, roots: &mut Vec<Record>) {
+        if let Some(done) = stack.pop() {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        }
+    }
+
+    let mut roots = Vec::new();
+    let mut stack: Vec<Record> = Vec::new();
+    for (n, line) in data.lines().enumerate() {
+        let line = line.trim_start_matches('\u{feff}').trim_start();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = || AppError::Validation(format!("Line {}: not a GEDCOM line", n + 1));
+        let (level, rest) = line.split_once(' ').ok_or_else(invalid)?;
+        let level: usize = level.parse().map_err(|_| invalid())?;
+        if level > stack.len() {
+            return Err(invalid());
+        }
+        let (xref, rest) = match rest.strip_prefix('@') {
+            Some(tail) => {
+                let (xref, rest) = tail.split_once(' ').unwrap_or((tail, ""));
+                (Some(xref.trim_end_matches('@').to_string()), rest)
+            }
+            None => (None, rest),
+        };
+        let (tag, value) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        while stack.len() > level {
+            close(&mut stack, &mut roots);
+        }
+        stack.push(Record {
+            xref,
+            tag: tag.trim().to_ascii_uppercase(),
+            value: value.to_string(),
+            children: Vec::new(),
+        });
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+    Ok(roots)
+}
+
+/// "John /Smith/" -> "John Smith"
+fn person_name(individual: &Record) -> String {
+    let name = individual
+        .child_value("NAME")
+        .map(|n| n.replace('/', " ").split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|n| !n.is_empty());
+    name.unwrap_or_else(|| format!("Unknown ({})", individual.xref.as_deref().unwrap_or("?")))
+}
+
+/// A GEDCOM date converted to canonical dates. Qualifiers with no equivalent
+/// in a date ("BEF 1850") are kept as a note for the description.
+#[derive(Debug, PartialEq)]
+struct GedcomDate {
+    start: String,
+    end: Option<String>,
+    note: Option<String>,
+}
+
+/// A single GEDCOM date ("12 JAN 1850", "JAN 1850", "1750/51", "44 B.C.").
+fn simple_date(text: &str) -> Option<HistoricalDate> {
+    // Dual years (Julian/Gregorian new year) use the first year.
+    let text: Vec<&str> = text.split_whitespace().map(|t| t.split('/').next().unwrap_or(t)).collect();
+    HistoricalDate::parse(&text.join(" ")).ok()
+}
+
+/// Parse a GEDCOM 5.5.1 date value: exact dates, ABT/EST/CAL (circa),
+/// BEF/AFT, BET...AND, FROM...TO and INT with a phrase. Calendar escapes
+/// such as @#DJULIAN@ are dropped; the date is read as written.
+fn parse_gedcom_date(value: &str) -> Option<GedcomDate> {
+    let mut text = value.to_string();
+    while let Some(start) = text.find("@#") {
+        let end = text[start + 2..].find('@').map_or(text.len(), |i| start + 3 + i);
+        text.replace_range(start..end, "");
+    }
+    let text = text.split('(').next().unwrap_or("").trim().to_string();
+    let (keyword, rest) = text.split_once(' ').unwrap_or((&text, ""));
+    let rest = rest.trim();
+    let single = |start: HistoricalDate, note: Option<String>| GedcomDate { start: start.to_string(), end: None, note };
+    // "BET x AND y" only says the date lies somewhere in between, so it
+    // stays a single circa date; "FROM x [TO y]" is a real span.
+    let split = |separator: &str| match rest.to_ascii_uppercase().find(separator) {
+        Some(i) => (rest[..i].trim(), Some(rest[i + separator.len()..].trim())),
+        None => (rest, None),
+    };
+    let between = || -> Option<GedcomDate> {
+        let (from, to) = split(" AND ");
+        let to = to?;
+        simple_date(to)?;
+        simple_date(from).map(|d| single(d.with_circa(true), Some(format!("between {from} and {to}"))))
+    };
+    let span = || -> Option<GedcomDate> {
+        let (from, to) = split(" TO ");
+        let end = match to {
+            Some(to) => Some(simple_date(to)?.to_string()),
+            None => None,
+        };
+        Some(GedcomDate { start: simple_date(from)?.to_string(), end, note: None })
+    };
+
+    match keyword.to_ascii_uppercase().as_str() {
+        "ABT" | "EST" | "CAL" => simple_date(rest).map(|d| single(d.with_circa(true), None)),
+        "BEF" => simple_date(rest).map(|d| single(d, Some(format!("before {rest}")))),
+        "AFT" => simple_date(rest).map(|d| single(d, Some(format!("after {rest}")))),
+        "TO" => simple_date(rest).map(|d| single(d, Some(format!("until {rest}")))),
+        "INT" => simple_date(rest).map(|d| single(d, None)),
+        "BET" => between(),
+        "FROM" => span(),
+        _ => simple_date(&text).map(|d| single(d, None)),
+    }
+}
+
+/// Convert GEDCOM individuals and families into a timeline. Each supported
+/// life event becomes one event; events without a date are passed through so
+/// the import report lists them.
+fn gedcom_to_timeline(title: &str, data: &str, grouping: GedcomGrouping) -> AppResult<ImportedTimeline> {
+    let records = parse_records(data)?;
+    if records.first().is_none_or(|r| r.tag != "HEAD") {
+        return Err(AppError::Validation("Not a GEDCOM file (missing HEAD record)".to_string()));
+    }
+
+    let individuals: Vec<&Record> = records.iter().filter(|r| r.tag == "INDI").collect();
+    let mut names: HashMap<&str, String> = HashMap::new();
+    for individual in &individuals {
+        let mut name = person_name(individual);
+        if names.values().any(|n| n == &name) {
+            name = format!("{name} ({})", individual.xref.as_deref().unwrap_or("?"));
+        }
+        names.insert(individual.xref.as_deref().unwrap_or_default(), name);
+    }
+
+    let mut tracks: Vec<ImportedTrack> = Vec::new();
+    let mut events = Vec::new();
+    let mut add_event = |event: &Record, label: &str, category: &str, who: String, track: Option<String>| {
+        let detail = match event.tag.as_str() {
+            "EDUC" | "OCCU" => Some(event.text()).filter(|t| !t.is_empty()),
+            "RESI" => event.child_value("PLAC").map(str::to_string),
+            _ => None,
+        };
+        let title = match detail {
+            Some(detail) => format!("{label} of {who}: {detail}"),
+            None => format!("{label} of {who}"),
+        };
+
+        let raw_date = event.child_value("DATE").unwrap_or_default();
+        let date = parse_gedcom_date(raw_date);
+        let mut description = Vec::new();
+        if let Some(note) = date.as_ref().and_then(|d| d.note.as_ref()) {
+            description.push(format!("Date: {note}"));
+        }
+        if let Some(place) = event.child_value("PLAC") {
+            description.push(format!("Place: {place}"));
+        }
+        if let Some(note) = event.child("NOTE").map(Record::text).filter(|n| !n.is_empty()) {
+            description.push(note);
+        }
+
+        let track_name = match grouping {
+            GedcomGrouping::Person => track,
+            GedcomGrouping::Category => Some(category.to_string()),
+        };
+        if let Some(ref name) = track_name {
+            if !tracks.iter().any(|t| &t.name == name) {
+                tracks.push(ImportedTrack { name: name.clone(), ..Default::default() });
+            }
+        }
+        let end_date = date.as_ref().and_then(|d| d.end.clone());
+        events.push(ImportedEvent {
+            title,
+            description: (!description.is_empty()).then(|| description.join("\n")),
+            // Unparseable dates are passed through so the import report names them.
+            start_date: date.map_or_else(|| raw_date.to_string(), |d| d.start),
+            event_type: Some(if end_date.is_some() { "range" } else { "point" }.to_string()),
+            end_date,
+            track_name,
+            tags: Some(label.to_lowercase()),
+            source: Some("GEDCOM".to_string()),
+            ..Default::default()
+        });
+    };
+
+    for individual in &individuals {
+        let name = &names[individual.xref.as_deref().unwrap_or_default()];
+        for event in &individual.children {
+            if let Some(&(_, label, category)) = LIFE_EVENTS.iter().find(|(tag, ..)| *tag == event.tag && *tag != "MARR") {
+                add_event(event, label, category, name.clone(), Some(name.clone()));
+            }
+        }
+    }
+
+    for family in records.iter().filter(|r| r.tag == "FAM") {
+        let spouses: Vec<&String> = ["HUSB", "WIFE"]
+            .iter()
+            .filter_map(|tag| family.child_value(tag))
+            .filter_map(|xref| names.get(xref.trim_matches('@')))
+            .collect();
+        let who = match spouses.as_slice() {
+            [] => format!("family {}", family.xref.as_deref().unwrap_or("?")),
+            [one] => one.to_string(),
+            [first, second, ..] => format!("{first} and {second}"),
+        };
+        for event in family.children.iter().filter(|c| c.tag == "MARR") {
+            add_event(event, "Marriage", "Marriages", who.clone(), spouses.first().map(|s| s.to_string()));
+        }
+    }
+
+    Ok(ImportedTimeline {
+        title: title.to_string(),
+        tracks,
+        events,
+        ..Default::default()
+    })
+}
+
+/// Import a GEDCOM 5.5.1 family tree as a new timeline of births, deaths,
+/// marriages, residences, education and occupations. With `dry_run`
+/// everything is validated and counted, then rolled back.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn import_gedcom(
+    db: State<'_, Mutex<Connection>>,
+    title: String,
+    data: String,
+    grouping: Option<GedcomGrouping>,
+    dry_run: Option<bool>,
+) -> AppResult<ImportReport> {
+    let imported = gedcom_to_timeline(&title, &data, grouping.unwrap_or_default())?;
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    import_in_transaction(&conn, &imported, dry_run.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gedcom_to_timeline, parse_gedcom_date, GedcomDate, GedcomGrouping};
+    use crate::commands::import::import_timeline;
+    use crate::db::init_test_db;
+
+    const FAMILY: &str = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 CHAR UTF-8\n\
+0 @I1@ INDI\n1 NAME John /Smith/\n1 BIRT\n2 DATE ABT 1820\n2 PLAC Boston, Massachusetts\n\
+1 OCCU Blacksmith\n2 DATE FROM 1845 TO 1870\n1 DEAT\n2 DATE BEF 3 MAR 1881\n\
+2 NOTE Buried in the\n3 CONC  family plot\n3 CONT next to his father.\n\
+0 @I2@ INDI\n1 NAME Mary /Jones/\n1 BIRT\n2 DATE 12 JAN 1825\n1 RESI\n2 PLAC Salem\n2 DATE BET 1850 AND 1860\n\
+1 EDUC\n2 PLAC Salem Academy\n\
+0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I2@\n1 MARR\n2 DATE @#DGREGORIAN@ 5 JUN 1848\n\
+0 TRLR\n";
+
+    #[test]
+    fn test_gedcom_dates() {
+        let parse = |value: &str| parse_gedcom_date(value).unwrap();
+        let date = |start: &str, end: Option<&str>, note: Option<&str>| GedcomDate {
+            start: start.to_string(),
+            end: end.map(str::to_string),
+            note: note.map(str::to_string),
+        };
+        assert_eq!(parse("12 JAN 1850"), date("1850-01-12", None, None));
+        assert_eq!(parse("ABT JAN 1850"), date("c. 1850-01", None, None));
+        assert_eq!(parse("EST 1750/51"), date("c. 1750", None, None));
+        assert_eq!(parse("AFT 1850"), date("1850", None, Some("after 1850")));
+        assert_eq!(parse("BET 1850 AND 1860"), date("c. 1850", None, Some("between 1850 and 1860")));
+        assert_eq!(parse("FROM 1 MAR 1850 TO 1855"), date("1850-03-01", Some("1855"), None));
+        assert_eq!(parse("FROM 1850"), date("1850", None, None));
+        assert_eq!(parse("INT 1850 (during the gold rush)"), date("1850", None, None));
+        assert_eq!(parse("44 B.C."), date("44 BCE", None, None));
+        assert!(parse_gedcom_date("(unknown)").is_none());
+        assert!(parse_gedcom_date("BET 1850").is_none());
+    }
+
+    #[test]
+    fn test_gedcom_import() {
+        let by_person = gedcom_to_timeline("Smiths", FAMILY, GedcomGrouping::Person).unwrap();
+        let track_names: Vec<&str> = by_person.tracks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(track_names, vec!["John Smith", "Mary Jones"]);
+        let titles: Vec<&str> = by_person.events.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Birth of John Smith",
+                "Occupation of John Smith: Blacksmith",
+                "Death of John Smith",
+                "Birth of Mary Jones",
+                "Residence of Mary Jones: Salem",
+                "Education of Mary Jones",
+                "Marriage of John Smith and Mary Jones",
+            ]
+        );
+        let death = &by_person.events[2];
+        assert_eq!(death.start_date, "1881-03-03");
+        assert_eq!(
+            death.description.as_deref(),
+            Some("Date: before 3 MAR 1881\nBuried in the family plot\nnext to his father.")
+        );
+        assert_eq!(by_person.events[6].track_name.as_deref(), Some("John Smith"));
+        assert_eq!(by_person.events[1].end_date.as_deref(), Some("1870"));
+        let residence = &by_person.events[4];
+        assert_eq!(residence.start_date, "c. 1850");
+        assert_eq!(residence.end_date, None);
+        assert_eq!(residence.event_type.as_deref(), Some("point"));
+        assert_eq!(residence.description.as_deref(), Some("Date: between 1850 and 1860\nPlace: Salem"));
+
+        let by_category = gedcom_to_timeline("Smiths", FAMILY, GedcomGrouping::Category).unwrap();
+        let track_names: Vec<&str> = by_category.tracks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(track_names, vec!["Births", "Occupations", "Deaths", "Residences", "Education", "Marriages"]);
+
+        let conn = init_test_db().unwrap();
+        let report = import_timeline(&conn, &by_category).unwrap();
+        assert_eq!(report.events_created, 6);
+        assert_eq!(report.skipped[0].title, "Education of Mary Jones");
+    }
+}
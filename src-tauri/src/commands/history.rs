@@ -0,0 +1,36 @@
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+#[cfg(feature = "desktop")]
+use tauri::State;
+
+use crate::db::journal;
+use crate::db::models::HistoryStatus;
+use crate::error::{AppError, AppResult};
+
+/// Undo the latest change on a timeline. Returns the label of the undone step.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn undo(db: State<'_, Mutex<Connection>>, timeline_id: String) -> AppResult<Option<String>> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    journal::undo(&conn, &timeline_id)
+}
+
+/// Redo the most recently undone change on a timeline.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn redo(db: State<'_, Mutex<Connection>>, timeline_id: String) -> AppResult<Option<String>> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    journal::redo(&conn, &timeline_id)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn get_history_status(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+) -> AppResult<HistoryStatus> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let (undo_label, redo_label) = journal::pending_labels(&conn, &timeline_id)?;
+    Ok(HistoryStatus { undo_label, redo_label })
+}
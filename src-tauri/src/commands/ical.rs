@@ -0,0 +1,704 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use rusqlite::Connection;
+#[cfg(feature = "desktop")]
+use tauri::State;
+
+use super::import::SkippedItem;
+use crate::db::dates::HistoricalDate;
+use crate::db::journal::{self, Snapshot};
+use crate::db::models::Event;
+use crate::error::{AppError, AppResult};
+
+/// Recurring events are cut off after this many occurrences.
+const MAX_OCCURRENCES: usize = 500;
+
+/// A DTSTART/DTEND style value: a whole day or a wall-clock time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IcsTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl IcsTime {
+    fn naive(self) -> NaiveDateTime {
+        match self {
+            IcsTime::Date(date) => date.and_time(NaiveTime::MIN),
+            IcsTime::DateTime(dt) => dt,
+        }
+    }
+
+    fn property(self, name: &str) -> String {
+        match self {
+            IcsTime::Date(date) => format!("{name};VALUE=DATE:{}", date.format("%Y%m%d")),
+            IcsTime::DateTime(dt) => format!("{name}:{}", dt.format("%Y%m%dT%H%M%S")),
+        }
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Append a content line, folded at 75 octets as RFC 5545 requires.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Calendar start and exclusive end of an event, or `None` for dates that
+/// iCalendar cannot hold (before year 1 or after 9999).
+fn event_span(event: &Event) -> Option<(IcsTime, Option<IcsTime>)> {
+    let in_range = |date: &HistoricalDate| (1..=9999).contains(&date.year());
+    let start = event.start_date;
+    if !in_range(&start) || event.end_date.is_some_and(|end| !in_range(&end)) {
+        return None;
+    }
+
+    if start.has_time() {
+        let end = event
+            .end_date
+            .map(|end| IcsTime::DateTime(if end.has_time() { end.start() } else { end.end() }));
+        Some((IcsTime::DateTime(start.start()), end))
+    } else {
+        let last = event.end_date.unwrap_or(start);
+        let end = if last.has_time() {
+            last.start().date() + Duration::days(1)
+        } else {
+            last.end().date()
+        };
+        Some((IcsTime::Date(start.start().date()), Some(IcsTime::Date(end))))
+    }
+}
+
+/// Write events as an iCalendar document with one VEVENT each. Ranges become
+/// DTSTART/DTEND, partial dates ("1789", "1850s") all-day events covering the
+/// whole period, and tags CATEGORIES. Events outside years 1-9999 are left out.
+pub fn events_to_ics(calendar_name: &str, events: &[Event]) -> String {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    for line in ["BEGIN:VCALENDAR", "VERSION:2.0", "PRODID:-//ChronoMap//Timeline Export//EN", "CALSCALE:GREGORIAN"] {
+        push_line(&mut out, line);
+    }
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape_text(calendar_name)));
+
+    for event in events {
+        let Some((start, end)) = event_span(event) else {
+            continue;
+        };
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}@chronomap", event.id));
+        push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+        push_line(&mut out, &start.property("DTSTART"));
+        if let Some(end) = end {
+            push_line(&mut out, &end.property("DTEND"));
+        }
+        push_line(&mut out, &format!("SUMMARY:{}", escape_text(&event.title)));
+        if !event.description.is_empty() {
+            push_line(&mut out, &format!("DESCRIPTION:{}", escape_text(&event.description)));
+        }
+        let categories: Vec<String> = event
+            .tags
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(escape_text)
+            .collect();
+        if !categories.is_empty() {
+            push_line(&mut out, &format!("CATEGORIES:{}", categories.join(",")));
+        }
+        if let Some(ref link) = event.external_link {
+            push_line(&mut out, &format!("URL:{link}"));
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+struct Property {
+    name: String,
+    params: HashMap<String, String>,
+    value: String,
+}
+
+/// Join folded lines back together.
+fn unfold(data: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in data.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(rest) = raw.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+                continue;
+            }
+        }
+        if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+/// Split on `separator` except inside double quotes.
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if c == separator && !quoted {
+            parts.push(&text[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// "NAME;PARAM=value:VALUE"
+fn parse_property(line: &str) -> Option<Property> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        (c == ':' && !quoted).then_some(i)
+    })?;
+    let mut head = split_unquoted(&line[..colon], ';').into_iter();
+    let name = head.next()?.trim().to_ascii_uppercase();
+    let params = head
+        .filter_map(|param| param.split_once('='))
+        .map(|(k, v)| (k.trim().to_ascii_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some(Property { name, params, value: line[colon + 1..].to_string() })
+}
+
+/// A DATE or DATE-TIME value. Times are kept as wall-clock time: a UTC "Z"
+/// or TZID is not converted.
+fn parse_ics_time(value: &str, params: &HashMap<String, String>) -> Option<IcsTime> {
+    let value = value.trim();
+    if params.get("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d").ok().map(IcsTime::Date);
+    }
+    NaiveDateTime::parse_from_str(value.trim_end_matches(['Z', 'z']), "%Y%m%dT%H%M%S")
+        .ok()
+        .map(IcsTime::DateTime)
+}
+
+/// "P1D", "PT1H30M", "P2W". None for durations too long to represent.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let body = value.trim().trim_start_matches('+').strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in body.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                let part = match unit {
+                    'W' => Duration::try_weeks(n),
+                    'D' => Duration::try_days(n),
+                    'H' => Duration::try_hours(n),
+                    'M' => Duration::try_minutes(n),
+                    'S' => Duration::try_seconds(n),
+                    _ => return None,
+                };
+                total = total.checked_add(&part?)?;
+            }
+        }
+    }
+    Some(total)
+}
+
+#[derive(Default)]
+struct VEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    categories: Vec<String>,
+    url: Option<String>,
+    start: Option<IcsTime>,
+    end: Option<IcsTime>,
+    duration: Option<Duration>,
+    rrule: Option<String>,
+    exdates: Vec<NaiveDateTime>,
+    recurrence_id: Option<NaiveDateTime>,
+}
+
+/// Collect the VEVENTs of a calendar, ignoring nested components like VALARM.
+fn parse_vevents(data: &str) -> Vec<VEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<VEvent> = None;
+    let mut nested = 0;
+    for line in unfold(data) {
+        let Some(prop) = parse_property(&line) else {
+            continue;
+        };
+        let value = prop.value.trim();
+        match (prop.name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => current = Some(VEvent::default()),
+            ("BEGIN", Some(_)) => nested += 1,
+            ("END", Some(_)) if nested > 0 => nested -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => events.extend(current.take()),
+            (_, Some(_)) if nested > 0 => {}
+            (name, Some(event)) => match name {
+                "UID" => event.uid = Some(value.to_string()),
+                "SUMMARY" => event.summary = Some(unescape_text(value)),
+                "DESCRIPTION" => event.description = Some(unescape_text(value)),
+                "CATEGORIES" => event.categories.extend(
+                    split_unquoted(value, ',')
+                        .into_iter()
+                        .map(|c| unescape_text(c.trim()))
+                        .filter(|c| !c.is_empty()),
+                ),
+                "URL" => event.url = Some(value.to_string()),
+                "DTSTART" => event.start = parse_ics_time(value, &prop.params),
+                "DTEND" => event.end = parse_ics_time(value, &prop.params),
+                "DURATION" => event.duration = parse_duration(value),
+                "RRULE" => event.rrule = Some(value.to_string()),
+                "EXDATE" => event.exdates.extend(
+                    value
+                        .split(',')
+                        .filter_map(|v| parse_ics_time(v, &prop.params))
+                        .map(IcsTime::naive),
+                ),
+                "RECURRENCE-ID" => event.recurrence_id = parse_ics_time(value, &prop.params).map(IcsTime::naive),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    events
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+struct RecurrenceRule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<usize>,
+    until: Option<NaiveDateTime>,
+    by_day: Vec<Weekday>,
+}
+
+/// The RRULE subset we expand: FREQ, INTERVAL, COUNT, UNTIL and, for weekly
+/// rules, plain BYDAY lists. Anything else is reported back as unsupported.
+fn parse_rrule(value: &str) -> Result<RecurrenceRule, String> {
+    let mut rule = RecurrenceRule { frequency: Frequency::Daily, interval: 1, count: None, until: None, by_day: Vec::new() };
+    let mut frequency = None;
+    for part in value.split(';').filter(|p| !p.is_empty()) {
+        let (key, val) = part.split_once('=').ok_or_else(|| format!("malformed rule part '{part}'"))?;
+        let invalid = || format!("invalid {key} '{val}'");
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match val.to_ascii_uppercase().as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return Err(format!("FREQ={val} is not supported")),
+                })
+            }
+            "INTERVAL" => rule.interval = val.parse().ok().filter(|&n| n > 0).ok_or_else(invalid)?,
+            "COUNT" => rule.count = Some(val.parse().map_err(|_| invalid())?),
+            "UNTIL" => {
+                rule.until = Some(match parse_ics_time(val, &HashMap::new()).ok_or_else(invalid)? {
+                    IcsTime::Date(date) => date.and_hms_opt(23, 59, 59).unwrap_or_default(),
+                    IcsTime::DateTime(dt) => dt,
+                })
+            }
+            "BYDAY" => {
+                for day in val.split(',') {
+                    let weekday = match day.to_ascii_uppercase().as_str() {
+                        "MO" => Weekday::Mon,
+                        "TU" => Weekday::Tue,
+                        "WE" => Weekday::Wed,
+                        "TH" => Weekday::Thu,
+                        "FR" => Weekday::Fri,
+                        "SA" => Weekday::Sat,
+                        "SU" => Weekday::Sun,
+                        _ => return Err(format!("BYDAY={val} is not supported")),
+                    };
+                    rule.by_day.push(weekday);
+                }
+            }
+            "WKST" => {}
+            other => return Err(format!("{other} is not supported")),
+        }
+    }
+    rule.frequency = frequency.ok_or("FREQ is missing")?;
+    if !rule.by_day.is_empty() && rule.frequency != Frequency::Weekly {
+        return Err("BYDAY is only supported for weekly rules".to_string());
+    }
+    rule.by_day.sort_by_key(|d| d.num_days_from_monday());
+    Ok(rule)
+}
+
+/// Occurrence starts of a rule, and whether the series was cut off at
+/// `MAX_OCCURRENCES`. The series ends early where dates run out of range.
+fn expand_rule(start: NaiveDateTime, rule: &RecurrenceRule) -> (Vec<NaiveDateTime>, bool) {
+    let limit = rule.count.unwrap_or(usize::MAX);
+    let mut out = Vec::new();
+    let after = |days: i64| Duration::try_days(days).and_then(|d| start.checked_add_signed(d));
+    for period in 0..10_000u32 {
+        let Some(step) = period.checked_mul(rule.interval) else {
+            break;
+        };
+        let candidates: Option<Vec<NaiveDateTime>> = match rule.frequency {
+            Frequency::Daily => after(step.into()).map(|dt| vec![dt]),
+            Frequency::Weekly if rule.by_day.is_empty() => after(i64::from(step) * 7).map(|dt| vec![dt]),
+            Frequency::Weekly => {
+                let monday = after(i64::from(step) * 7 - i64::from(start.weekday().num_days_from_monday()));
+                monday.map(|monday| {
+                    rule.by_day
+                        .iter()
+                        .filter_map(|d| monday.checked_add_signed(Duration::days(d.num_days_from_monday().into())))
+                        .filter(|&dt| dt >= start)
+                        .collect()
+                })
+            }
+            // Months without the start's day (e.g. the 31st) are skipped, as RFC 5545 says.
+            Frequency::Monthly | Frequency::Yearly => {
+                let months = if rule.frequency == Frequency::Yearly { step.checked_mul(12) } else { Some(step) };
+                months.and_then(|months| start.date().checked_add_months(Months::new(months))).map(|date| {
+                    Some(date)
+                        .filter(|d| d.day() == start.day())
+                        .map(|d| d.and_time(start.time()))
+                        .into_iter()
+                        .collect()
+                })
+            }
+        };
+        let Some(candidates) = candidates else {
+            break;
+        };
+        for occurrence in candidates {
+            if rule.until.is_some_and(|until| occurrence > until) || out.len() >= limit {
+                return (out, false);
+            }
+            if out.len() == MAX_OCCURRENCES {
+                return (out, true);
+            }
+            out.push(occurrence);
+        }
+    }
+    (out, false)
+}
+
+/// What a calendar import created.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IcsImportReport {
+    pub events_created: u32,
+    pub skipped: Vec<SkippedItem>,
+    /// Recurrences that were cut short or could not be expanded.
+    pub warnings: Vec<String>,
+}
+
+fn insert_occurrence(
+    conn: &Connection,
+    timeline_id: &str,
+    track_id: &str,
+    vevent: &VEvent,
+    start: IcsTime,
+    end: Option<IcsTime>,
+    now: &str,
+) -> AppResult<()> {
+    let (start_date, end_date) = match (start, end) {
+        (IcsTime::Date(first), end) => {
+            // All-day ends are exclusive
+            let last = end.map(|e| e.naive().date() - Duration::days(1)).filter(|&last| last > first);
+            (HistoricalDate::from_naive_date(first), last.map(HistoricalDate::from_naive_date))
+        }
+        (IcsTime::DateTime(first), end) => {
+            let last = end.map(IcsTime::naive).filter(|&last| last > first);
+            (HistoricalDate::from_naive_datetime(first), last.map(HistoricalDate::from_naive_datetime))
+        }
+    };
+    let title = vevent.summary.as_deref().filter(|s| !s.trim().is_empty()).unwrap_or("Untitled event");
+
+    conn.execute(
+        "INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, event_type, external_link, tags, source, created_at, updated_at, start_sort_key, end_sort_key)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 'iCalendar', ?11, ?11, ?12, ?13)",
+        rusqlite::params![
+            uuid::Uuid::new_v4().to_string(), timeline_id, track_id, title,
+            vevent.description.as_deref().unwrap_or(""),
+            start_date, end_date,
+            if end_date.is_some() { "range" } else { "point" },
+            vevent.url, vevent.categories.join(","), now,
+            start_date.sort_key(), end_date.map(|d| d.sort_key())
+        ],
+    )?;
+    Ok(())
+}
+
+/// Import the VEVENTs of an iCalendar file into one track. Recurring events
+/// are expanded into one event per occurrence, minus EXDATEs and with
+/// RECURRENCE-ID overrides applied.
+pub fn import_ics_events(
+    conn: &Connection,
+    timeline_id: &str,
+    track_id: &str,
+    data: &str,
+) -> AppResult<IcsImportReport> {
+    let track_ok: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM tracks WHERE id = ?1 AND timeline_id = ?2)",
+        [track_id, timeline_id],
+        |row| row.get(0),
+    )?;
+    if !track_ok {
+        return Err(AppError::NotFound(format!("Track {track_id} not found in timeline {timeline_id}")));
+    }
+    if !data.contains("BEGIN:VCALENDAR") {
+        return Err(AppError::Validation("Not an iCalendar file".to_string()));
+    }
+
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let vevents = parse_vevents(data);
+    let mut report = IcsImportReport::default();
+
+    // Overrides of single occurrences, by series UID and original start
+    let mut overrides: HashMap<(&str, NaiveDateTime), &VEvent> = HashMap::new();
+    let mut has_series: Vec<&str> = Vec::new();
+    for vevent in &vevents {
+        if let (Some(uid), Some(id)) = (vevent.uid.as_deref(), vevent.recurrence_id) {
+            overrides.insert((uid, id), vevent);
+        } else if let (Some(uid), Some(_)) = (vevent.uid.as_deref(), &vevent.rrule) {
+            has_series.push(uid);
+        }
+    }
+
+    for (i, vevent) in vevents.iter().enumerate() {
+        let title = vevent.summary.as_deref().unwrap_or_default();
+        let Some(start) = vevent.start else {
+            report.skipped.push(SkippedItem::new("event", i, title, "No valid DTSTART"));
+            continue;
+        };
+        // Overrides are written in place of the occurrence they replace.
+        if vevent.recurrence_id.is_some() && vevent.uid.as_deref().is_some_and(|uid| has_series.contains(&uid)) {
+            continue;
+        }
+        let length = match (vevent.end, vevent.duration) {
+            (Some(end), _) => Some(end.naive() - start.naive()),
+            (None, Some(duration)) => Some(duration),
+            // All-day events without an end last one day
+            (None, None) => matches!(start, IcsTime::Date(_)).then(|| Duration::days(1)),
+        };
+        let at = |dt: NaiveDateTime| match start {
+            IcsTime::Date(_) => IcsTime::Date(dt.date()),
+            IcsTime::DateTime(_) => IcsTime::DateTime(dt),
+        };
+
+        let starts = match vevent.rrule.as_deref().map(parse_rrule) {
+            None => vec![start.naive()],
+            Some(Ok(rule)) => {
+                let (starts, truncated) = expand_rule(start.naive(), &rule);
+                if truncated {
+                    report.warnings.push(format!("'{title}' repeats indefinitely; imported the first {MAX_OCCURRENCES} occurrences"));
+                }
+                starts
+            }
+            Some(Err(reason)) => {
+                report.warnings.push(format!("'{title}': recurrence not expanded ({reason}); imported the first occurrence"));
+                vec![start.naive()]
+            }
+        };
+
+        for occurrence in starts {
+            if vevent.exdates.contains(&occurrence) {
+                continue;
+            }
+            let replacement = vevent.uid.as_deref().and_then(|uid| overrides.get(&(uid, occurrence)));
+            match replacement {
+                Some(over) => {
+                    let Some(over_start) = over.start else {
+                        continue;
+                    };
+                    let over_end = over.end.or_else(|| {
+                        over.duration.and_then(|d| over_start.naive().checked_add_signed(d)).map(at)
+                    });
+                    insert_occurrence(conn, timeline_id, track_id, over, over_start, over_end, &now)?;
+                }
+                None => {
+                    let end = length.and_then(|length| occurrence.checked_add_signed(length)).map(at);
+                    insert_occurrence(conn, timeline_id, track_id, vevent, at(occurrence), end, &now)?;
+                }
+            }
+            report.events_created += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Import an .ics file into a track of a timeline as one undoable step.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn import_ics(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+    track_id: String,
+    data: String,
+) -> AppResult<IcsImportReport> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let tx = conn.unchecked_transaction()?;
+    let before = Snapshot::timeline(&tx, &timeline_id)?;
+    let report = import_ics_events(&tx, &timeline_id, &track_id, &data)?;
+    journal::record(&tx, "Import calendar", before, Snapshot::timeline(&tx, &timeline_id)?)?;
+    tx.commit()?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{events_to_ics, import_ics_events, parse_duration, unfold};
+    use chrono::Duration;
+    use crate::commands::events::fetch_timeline_events;
+    use crate::db::init_test_db;
+
+    fn seed(conn: &rusqlite::Connection) {
+        conn.execute("INSERT INTO timelines (id, title) VALUES ('tl', 'Roadmap')", []).unwrap();
+        conn.execute("INSERT INTO tracks (id, timeline_id, name) VALUES ('tr', 'tl', 'Releases')", []).unwrap();
+    }
+
+    #[test]
+    fn test_ics_export() {
+        let conn = init_test_db().unwrap();
+        seed(&conn);
+        let long = "A description, with; special characters that goes on long enough to need folding";
+        for (id, title, start, end, tags, description) in [
+            ("e1", "Beta", "2024-03-01", Some("2024-03-15"), "release,beta", long),
+            ("e2", "Kickoff", "2024-01-08 09:30:00", None, "", ""),
+            ("e3", "Founding", "1905", None, "", ""),
+            ("e4", "Ancient", "500 BCE", None, "", ""),
+        ] {
+            conn.execute(
+                "INSERT INTO events (id, timeline_id, track_id, title, start_date, end_date, tags, description) VALUES (?1, 'tl', 'tr', ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![id, title, start, end, tags, description],
+            )
+            .unwrap();
+        }
+
+        let ics = events_to_ics("Roadmap", &fetch_timeline_events(&conn, "tl").unwrap());
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.lines().all(|line| line.len() <= 75));
+        let lines = unfold(&ics);
+        assert_eq!(lines.iter().filter(|l| *l == "BEGIN:VEVENT").count(), 3);
+        assert!(lines.contains(&"DTSTART;VALUE=DATE:20240301".to_string()));
+        assert!(lines.contains(&"DTEND;VALUE=DATE:20240316".to_string()));
+        assert!(lines.contains(&"CATEGORIES:release,beta".to_string()));
+        assert!(lines.contains(&"DTSTART:20240108T093000".to_string()));
+        assert!(lines.contains(&"DTSTART;VALUE=DATE:19050101".to_string()));
+        assert!(lines.contains(&"DTEND;VALUE=DATE:19060101".to_string()));
+        assert!(lines.contains(&format!("DESCRIPTION:{}", long.replace(',', "\\,").replace(';', "\\;"))));
+    }
+
+    #[test]
+    fn test_ics_import_with_recurrence() {
+        let conn = init_test_db().unwrap();
+        seed(&conn);
+        let data = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+BEGIN:VEVENT\r\nUID:standup\r\nSUMMARY:Standup\r\nDTSTART:20240101T090000Z\r\nDURATION:PT15M\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4\r\nEXDATE:20240103T090000Z\r\n\
+BEGIN:VALARM\r\nACTION:DISPLAY\r\nSUMMARY:Ignored\r\nEND:VALARM\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nUID:standup\r\nRECURRENCE-ID:20240108T090000Z\r\nSUMMARY:Standup (moved)\r\n\
+DTSTART:20240108T100000Z\r\nDTEND:20240108T101500Z\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nUID:offsite\r\nSUMMARY:Offsite\\, Lisbon\r\nCATEGORIES:travel,team\r\n\
+DESCRIPTION:Two days\\nof plan\r\n ning\r\nDTSTART;VALUE=DATE:20240212\r\nDTEND;VALUE=DATE:20240214\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nUID:odd\r\nSUMMARY:Odd rule\r\nDTSTART;VALUE=DATE:20240301\r\nRRULE:FREQ=MONTHLY;BYMONTHDAY=1\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nSUMMARY:No start\r\nEND:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+        let report = import_ics_events(&conn, "tl", "tr", data).unwrap();
+        assert_eq!(report.events_created, 5);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.warnings.len(), 1);
+
+        let events = fetch_timeline_events(&conn, "tl").unwrap();
+        let summary: Vec<(String, String, Option<String>)> = events
+            .iter()
+            .map(|e| (e.title.clone(), e.start_date.to_string(), e.end_date.map(|d| d.to_string())))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Standup".to_string(), "2024-01-01T09:00:00".to_string(), Some("2024-01-01T09:15:00".to_string())),
+                ("Standup (moved)".to_string(), "2024-01-08T10:00:00".to_string(), Some("2024-01-08T10:15:00".to_string())),
+                ("Standup".to_string(), "2024-01-10T09:00:00".to_string(), Some("2024-01-10T09:15:00".to_string())),
+                ("Offsite, Lisbon".to_string(), "2024-02-12".to_string(), Some("2024-02-13".to_string())),
+                ("Odd rule".to_string(), "2024-03-01".to_string(), None),
+            ]
+        );
+        assert_eq!(events[3].tags, "travel,team");
+        assert_eq!(events[3].description, "Two days\nof planning");
+    }
+
+    #[test]
+    fn test_ics_import_out_of_range_rules() {
+        let conn = init_test_db().unwrap();
+        seed(&conn);
+        let data = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+BEGIN:VEVENT\r\nUID:daily\r\nSUMMARY:Far apart\r\nDTSTART:20240101T090000\r\nRRULE:FREQ=DAILY;INTERVAL=100000000\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nUID:yearly\r\nSUMMARY:Every eon\r\nDTSTART;VALUE=DATE:20240101\r\nRRULE:FREQ=YEARLY;INTERVAL=4294967295\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nUID:long\r\nSUMMARY:Forever\r\nDTSTART:20240101T090000\r\nDURATION:P99999999999999W\r\nEND:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+        let report = import_ics_events(&conn, "tl", "tr", data).unwrap();
+        assert_eq!(report.events_created, 3);
+        let events = fetch_timeline_events(&conn, "tl").unwrap();
+        let summary: Vec<(String, Option<String>)> =
+            events.iter().map(|e| (e.title.clone(), e.end_date.map(|d| d.to_string()))).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Every eon".to_string(), None),
+                ("Far apart".to_string(), None),
+                ("Forever".to_string(), None),
+            ]
+        );
+        assert!(parse_duration("P99999999999999W").is_none());
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+    }
+}
@@ -1,166 +1,846 @@
+use std::collections::HashMap;
+#[cfg(feature = "desktop")]
 use std::sync::Mutex;
 
+use chrono::Datelike;
 use rusqlite::Connection;
+#[cfg(feature = "desktop")]
 use tauri::State;
 
+use super::export::EXPORT_VERSION;
+use crate::db::dates::HistoricalDate;
+use crate::db::journal::{self, Snapshot};
 use crate::error::{AppError, AppResult};
 
-#[derive(Debug, Clone, serde::Deserialize)]
+/// A JSON timeline export. Reads both version 1 files, where tracks and
+/// connections are referenced by name/title, and version 2 files, which carry
+/// every column and stable ids. Missing v2 fields fall back to v1 behaviour.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportedTimeline {
+    pub version: Option<u32>,
+    pub id: Option<String>,
     pub title: String,
     pub description: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
     pub tracks: Vec<ImportedTrack>,
     pub events: Vec<ImportedEvent>,
     pub connections: Option<Vec<ImportedConnection>>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportedTrack {
+    pub id: Option<String>,
     pub name: String,
     pub color: Option<String>,
+    pub sort_order: Option<i32>,
+    pub visible: Option<bool>,
+    pub created_at: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportedEvent {
+    pub id: Option<String>,
     pub title: String,
     pub description: Option<String>,
     pub start_date: String,
     pub end_date: Option<String>,
     pub event_type: Option<String>,
     pub importance: Option<i32>,
+    pub track_id: Option<String>,
     pub track_name: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub image_path: Option<String>,
+    pub external_link: Option<String>,
     pub tags: Option<String>,
+    pub source: Option<String>,
+    pub ai_generated: Option<bool>,
+    pub ai_confidence: Option<f64>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportedConnection {
-    pub source_title: String,
-    pub target_title: String,
+    pub id: Option<String>,
+    pub source_id: Option<String>,
+    pub target_id: Option<String>,
+    pub source_title: Option<String>,
+    pub target_title: Option<String>,
     pub connection_type: Option<String>,
     pub label: Option<String>,
+    pub color: Option<String>,
+    pub created_at: Option<String>,
 }
 
-#[tauri::command]
-pub fn import_json(
-    db: State<'_, Mutex<Connection>>,
-    data: String,
-) -> AppResult<String> {
-    let imported: ImportedTimeline = serde_json::from_str(&data)
-        .map_err(|e| AppError::Validation(format!("Invalid JSON: {e}")))?;
+/// An imported item that was not written, and why.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedItem {
+    pub kind: String,
+    pub index: usize,
+    pub title: String,
+    pub reason: String,
+}
 
-    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnresolvedConnection {
+    pub index: usize,
+    pub source_title: Option<String>,
+    pub target_title: Option<String>,
+    /// The ids or titles that did not match an imported event.
+    pub missing: Vec<String>,
+}
+
+/// What an import created (or, on a dry run, would have created).
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    /// The new timeline; `None` for dry runs.
+    pub timeline_id: Option<String>,
+    pub dry_run: bool,
+    pub tracks_created: u32,
+    pub events_created: u32,
+    pub connections_created: u32,
+    pub skipped: Vec<SkippedItem>,
+    pub unresolved_connections: Vec<UnresolvedConnection>,
+    /// Event titles used more than once; connections naming them link to the first.
+    pub duplicate_titles: Vec<String>,
+    /// Ids from the file that were already taken in this database and got replaced.
+    pub ids_reassigned: u32,
+}
+
+impl SkippedItem {
+    pub fn new(kind: &str, index: usize, title: &str, reason: impl Into<String>) -> Self {
+        Self {
+            kind: kind.to_string(),
+            index,
+            title: title.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl ImportReport {
+    fn skip(&mut self, kind: &str, index: usize, title: &str, reason: impl Into<String>) {
+        self.skipped.push(SkippedItem::new(kind, index, title, reason));
+    }
+}
+
+impl ImportedTimeline {
+    /// Reject files written by a newer version of the app.
+    pub fn check_version(&self) -> AppResult<()> {
+        let version = self.version.unwrap_or(1);
+        if version > EXPORT_VERSION {
+            return Err(AppError::Validation(format!("Unsupported export version {version}")));
+        }
+        Ok(())
+    }
+}
+
+/// Write an imported timeline as a new timeline. Runs on whatever connection or
+/// transaction it is given; callers own commit/rollback.
+pub fn import_timeline(conn: &Connection, imported: &ImportedTimeline) -> AppResult<ImportReport> {
+    imported.check_version()?;
+    if imported.title.trim().is_empty() {
+        return Err(AppError::Validation("Timeline title is required".to_string()));
+    }
+
+    let mut report = ImportReport::default();
     let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    let tl_id = uuid::Uuid::new_v4().to_string();
+    let tl_id = keep_or_new_id(conn, "timelines", imported.id.as_deref(), &mut report.ids_reassigned)?;
 
     conn.execute(
         "INSERT INTO timelines (id, title, description, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-        rusqlite::params![tl_id, imported.title, imported.description.unwrap_or_default(), now, now],
+        rusqlite::params![
+            tl_id, imported.title, imported.description.as_deref().unwrap_or_default(),
+            imported.created_at.as_deref().unwrap_or(&now),
+            imported.updated_at.as_deref().unwrap_or(&now)
+        ],
     )?;
 
-    // Create tracks, map name→id
-    let mut track_map = std::collections::HashMap::new();
+    // Create tracks, map file id→db id and name→db id
+    let mut track_ids: HashMap<&str, String> = HashMap::new();
+    let mut track_names: HashMap<&str, String> = HashMap::new();
+    let mut default_track_id = None;
     for (i, track) in imported.tracks.iter().enumerate() {
-        let track_id = uuid::Uuid::new_v4().to_string();
-        let color = track.color.clone().unwrap_or_else(|| "#3b82f6".to_string());
+        if track.name.trim().is_empty() {
+            report.skip("track", i, &track.name, "Track name is empty");
+            continue;
+        }
+        // Without ids (v1) tracks are only addressable by name.
+        if track.id.is_none() && track_names.contains_key(track.name.as_str()) {
+            report.skip("track", i, &track.name, "Duplicate track name; events use the first");
+            continue;
+        }
+        let track_id = keep_or_new_id(conn, "tracks", track.id.as_deref(), &mut report.ids_reassigned)?;
+        let color = track.color.as_deref().unwrap_or("#3b82f6");
         conn.execute(
-            "INSERT INTO tracks (id, timeline_id, name, color, sort_order, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            rusqlite::params![track_id, tl_id, track.name, color, i as i32, now],
+            "INSERT INTO tracks (id, timeline_id, name, color, sort_order, visible, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                track_id, tl_id, track.name, color,
+                track.sort_order.unwrap_or(i as i32),
+                track.visible.unwrap_or(true),
+                track.created_at.as_deref().unwrap_or(&now)
+            ],
         )?;
-        track_map.insert(track.name.clone(), track_id);
+        default_track_id.get_or_insert_with(|| track_id.clone());
+        if let Some(ref file_id) = track.id {
+            track_ids.entry(file_id.as_str()).or_insert_with(|| track_id.clone());
+        }
+        track_names.entry(track.name.as_str()).or_insert(track_id);
+        report.tracks_created += 1;
     }
 
     // Ensure at least one track
-    if track_map.is_empty() {
-        let default_id = uuid::Uuid::new_v4().to_string();
-        conn.execute(
-            "INSERT INTO tracks (id, timeline_id, name, color, sort_order, created_at) VALUES (?1, ?2, 'Default', '#3b82f6', 0, ?3)",
-            rusqlite::params![default_id, tl_id, now],
-        )?;
-        track_map.insert("Default".to_string(), default_id);
-    }
+    let default_track_id = match default_track_id {
+        Some(id) => id,
+        None => {
+            let default_id = uuid::Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO tracks (id, timeline_id, name, color, sort_order, created_at) VALUES (?1, ?2, 'Default', '#3b82f6', 0, ?3)",
+                rusqlite::params![default_id, tl_id, now],
+            )?;
+            report.tracks_created += 1;
+            default_id
+        }
+    };
 
-    let default_track_id = track_map
-        .values()
-        .next()
-        .ok_or_else(|| AppError::Internal("No tracks available".to_string()))?
-        .clone();
+    // Create events, map file id→db id and title→db id of the first event with that title
+    let mut event_ids: HashMap<&str, String> = HashMap::new();
+    let mut event_titles: HashMap<&str, String> = HashMap::new();
+    for (i, event) in imported.events.iter().enumerate() {
+        if event.title.trim().is_empty() {
+            report.skip("event", i, &event.title, "Title is empty");
+            continue;
+        }
+        let start_date = match HistoricalDate::parse(&event.start_date) {
+            Ok(date) => date,
+            Err(e) => {
+                report.skip("event", i, &event.title, format!("Start date: {e}"));
+                continue;
+            }
+        };
+        let end_date = match HistoricalDate::parse_optional(event.end_date.as_deref()) {
+            Ok(date) => date,
+            Err(e) => {
+                report.skip("event", i, &event.title, format!("End date: {e}"));
+                continue;
+            }
+        };
 
-    // Create events, map title→id
-    let mut event_map = std::collections::HashMap::new();
-    for event in &imported.events {
-        let event_id = uuid::Uuid::new_v4().to_string();
+        let event_id = keep_or_new_id(conn, "events", event.id.as_deref(), &mut report.ids_reassigned)?;
         let track_id = event
-            .track_name
-            .as_ref()
-            .and_then(|n| track_map.get(n))
-            .unwrap_or(&default_track_id)
-            .clone();
+            .track_id
+            .as_deref()
+            .and_then(|id| track_ids.get(id))
+            .or_else(|| event.track_name.as_deref().and_then(|n| track_names.get(n)))
+            .unwrap_or(&default_track_id);
 
         conn.execute(
-            "INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, event_type, importance, tags, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            "INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, event_type, importance, color, icon, image_path, external_link, tags, source, ai_generated, ai_confidence, created_at, updated_at, start_sort_key, end_sort_key)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
             rusqlite::params![
                 event_id, tl_id, track_id, event.title,
                 event.description.as_deref().unwrap_or(""),
-                event.start_date, event.end_date,
+                start_date, end_date,
                 event.event_type.as_deref().unwrap_or("point"),
                 event.importance.unwrap_or(3),
+                event.color, event.icon, event.image_path, event.external_link,
                 event.tags.as_deref().unwrap_or(""),
-                now, now
+                event.source,
+                event.ai_generated.unwrap_or(false),
+                event.ai_confidence,
+                event.created_at.as_deref().unwrap_or(&now),
+                event.updated_at.as_deref().unwrap_or(&now),
+                start_date.sort_key(), end_date.map(|d| d.sort_key())
             ],
         )?;
-        event_map.insert(event.title.clone(), event_id);
+        report.events_created += 1;
+
+        if let Some(ref file_id) = event.id {
+            event_ids.entry(file_id.as_str()).or_insert_with(|| event_id.clone());
+        }
+        if event_titles.contains_key(event.title.as_str()) {
+            if !report.duplicate_titles.contains(&event.title) {
+                report.duplicate_titles.push(event.title.clone());
+            }
+        } else {
+            event_titles.insert(&event.title, event_id);
+        }
     }
 
-    // Create connections
-    if let Some(connections) = &imported.connections {
-        for conn_data in connections {
-            if let (Some(source_id), Some(target_id)) = (
-                event_map.get(&conn_data.source_title),
-                event_map.get(&conn_data.target_title),
-            ) {
-                let conn_id = uuid::Uuid::new_v4().to_string();
-                conn.execute(
-                    "INSERT INTO connections (id, timeline_id, source_event_id, target_event_id, connection_type, label, created_at)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                    rusqlite::params![
-                        conn_id, tl_id, source_id, target_id,
-                        conn_data.connection_type.as_deref().unwrap_or("related"),
-                        conn_data.label, now
-                    ],
-                )?;
+    // Create connections, by id when the file has them and by title otherwise
+    let resolve = |id: &Option<String>, title: &Option<String>| -> Result<String, String> {
+        match (id, title) {
+            (Some(id), _) => event_ids.get(id.as_str()).cloned().ok_or_else(|| id.clone()),
+            (None, Some(title)) => event_titles.get(title.as_str()).cloned().ok_or_else(|| title.clone()),
+            (None, None) => Err(String::new()),
+        }
+    };
+    for (i, conn_data) in imported.connections.iter().flatten().enumerate() {
+        let source = resolve(&conn_data.source_id, &conn_data.source_title);
+        let target = resolve(&conn_data.target_id, &conn_data.target_title);
+        let (Ok(source_id), Ok(target_id)) = (&source, &target) else {
+            let missing = [source, target].into_iter().filter_map(Result::err).collect();
+            report.unresolved_connections.push(UnresolvedConnection {
+                index: i,
+                source_title: conn_data.source_title.clone(),
+                target_title: conn_data.target_title.clone(),
+                missing,
+            });
+            continue;
+        };
+
+        let conn_id = keep_or_new_id(conn, "connections", conn_data.id.as_deref(), &mut report.ids_reassigned)?;
+        conn.execute(
+            "INSERT INTO connections (id, timeline_id, source_event_id, target_event_id, connection_type, label, color, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                conn_id, tl_id, source_id, target_id,
+                conn_data.connection_type.as_deref().unwrap_or("related"),
+                conn_data.label, conn_data.color,
+                conn_data.created_at.as_deref().unwrap_or(&now)
+            ],
+        )?;
+        report.connections_created += 1;
+    }
+
+    report.timeline_id = Some(tl_id);
+    Ok(report)
+}
+
+/// Reuse an id from the import file unless it is blank or already taken.
+/// Replaced ids are counted in `reassigned`.
+pub fn keep_or_new_id(
+    conn: &Connection,
+    table: &str,
+    id: Option<&str>,
+    reassigned: &mut u32,
+) -> AppResult<String> {
+    if let Some(id) = id.filter(|id| !id.trim().is_empty()) {
+        let taken: bool = conn.query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM {table} WHERE id = ?1)"),
+            [id],
+            |row| row.get(0),
+        )?;
+        if !taken {
+            return Ok(id.to_string());
+        }
+        *reassigned += 1;
+    }
+    Ok(uuid::Uuid::new_v4().to_string())
+}
+
+/// Import a JSON timeline export as a new timeline in a single transaction.
+/// With `dry_run` everything is validated and counted, then rolled back.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn import_json(
+    db: State<'_, Mutex<Connection>>,
+    data: String,
+    dry_run: Option<bool>,
+) -> AppResult<ImportReport> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    import_json_data(&conn, &data, dry_run.unwrap_or(false))
+}
+
+/// Parse a JSON timeline export and import it with `import_in_transaction`.
+pub fn import_json_data(conn: &Connection, data: &str, dry_run: bool) -> AppResult<ImportReport> {
+    let imported: ImportedTimeline = serde_json::from_str(data)
+        .map_err(|e| AppError::Validation(format!("Invalid JSON: {e}")))?;
+
+    import_in_transaction(conn, &imported, dry_run)
+}
+
+/// Run `import_timeline` in its own transaction, rolling it back for dry runs.
+/// Shared by the importers that turn other formats into an `ImportedTimeline`.
+pub fn import_in_transaction(
+    conn: &Connection,
+    imported: &ImportedTimeline,
+    dry_run: bool,
+) -> AppResult<ImportReport> {
+    let tx = conn.unchecked_transaction()?;
+    let mut report = import_timeline(&tx, imported)?;
+
+    if dry_run {
+        report.dry_run = true;
+        report.timeline_id = None;
+        tx.rollback()?;
+    } else {
+        tx.commit()?;
+    }
+    Ok(report)
+}
+
+/// A TimelineJS (Knight Lab) JSON document.
+#[cfg(feature = "desktop")]
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct TimelineJsDocument {
+    title: Option<TimelineJsSlide>,
+    events: Vec<TimelineJsSlide>,
+    eras: Vec<TimelineJsSlide>,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct TimelineJsSlide {
+    start_date: Option<TimelineJsDate>,
+    end_date: Option<TimelineJsDate>,
+    text: Option<TimelineJsText>,
+    media: Option<TimelineJsMedia>,
+    group: Option<String>,
+    background: Option<TimelineJsBackground>,
+}
+
+/// Date parts may be numbers or strings ("1789").
+#[cfg(feature = "desktop")]
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct TimelineJsDate {
+    year: serde_json::Value,
+    month: serde_json::Value,
+    day: serde_json::Value,
+    hour: serde_json::Value,
+    minute: serde_json::Value,
+    second: serde_json::Value,
+    display_date: Option<String>,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct TimelineJsText {
+    headline: String,
+    text: String,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct TimelineJsMedia {
+    url: String,
+    credit: String,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct TimelineJsBackground {
+    color: Option<String>,
+}
+
+/// TimelineJS headlines and text are HTML; keep the text and line breaks.
+#[cfg(feature = "desktop")]
+fn html_to_text(html: &str) -> String {
+    let mut out = String::new();
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        let Some(close) = rest[open..].find('>') else {
+            break;
+        };
+        out.push_str(&rest[..open]);
+        let tag = rest[open + 1..open + close].trim_start_matches('/').to_ascii_lowercase();
+        let name = tag.split([' ', '/']).next().unwrap_or_default();
+        if matches!(name, "br" | "p" | "li" | "div") {
+            out.push('\n');
+        }
+        rest = &rest[open + close + 1..];
+    }
+    out.push_str(rest);
+    let out = out
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    // Paragraphs are separated by one blank line.
+    let mut text = String::new();
+    for line in out.lines().map(str::trim) {
+        if line.is_empty() && (text.is_empty() || text.ends_with("\n\n")) {
+            continue;
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+    text.trim().to_string()
+}
+
+/// Canonical date text for TimelineJS date parts. A `display_date` we can
+/// read ("c. 1770", "1850s") wins over the parts, which lose circa and
+/// decade or century precision. Negative years are years BCE as TimelineJS
+/// displays them (-500 is 500 BCE). Dates that cannot be read are passed
+/// through so the import report names them.
+#[cfg(feature = "desktop")]
+fn timelinejs_date(date: &TimelineJsDate) -> String {
+    if let Some(display) = date.display_date.as_deref().and_then(|d| HistoricalDate::parse(d).ok()) {
+        return display.to_string();
+    }
+    let part = |value: &serde_json::Value| match value {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    };
+    let Some(year) = part(&date.year) else {
+        return date.year.to_string();
+    };
+    let era = if year < 0 { " BCE" } else { "" };
+    let year = year.abs();
+    let text = match (part(&date.month), part(&date.day), part(&date.hour)) {
+        (Some(month), Some(day), Some(hour)) => format!(
+            "{year:04}-{month:02}-{day:02}T{hour:02}:{:02}:{:02}",
+            part(&date.minute).unwrap_or(0),
+            part(&date.second).unwrap_or(0)
+        ),
+        (Some(month), Some(day), None) => format!("{year:04}-{month:02}-{day:02}"),
+        (Some(month), None, _) => format!("{year:04}-{month:02}"),
+        _ => year.to_string(),
+    };
+    format!("{text}{era}")
+}
+
+/// Convert a TimelineJS document into a timeline. `group` becomes the track,
+/// eras become era events on an "Eras" track, and `media.url` the image
+/// (for image files) or external link.
+#[cfg(feature = "desktop")]
+pub fn timelinejs_to_timeline(data: &str) -> AppResult<ImportedTimeline> {
+    let document: TimelineJsDocument = serde_json::from_str(data)
+        .map_err(|e| AppError::Validation(format!("Not a TimelineJS document: {e}")))?;
+
+    let title_text = document.title.as_ref().and_then(|t| t.text.as_ref());
+    let title = title_text
+        .map(|t| html_to_text(&t.headline))
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "TimelineJS import".to_string());
+
+    let mut tracks: Vec<ImportedTrack> = Vec::new();
+    let mut events = Vec::new();
+    let slides = document.events.iter().map(|s| (s, false)).chain(document.eras.iter().map(|s| (s, true)));
+    for (slide, is_era) in slides {
+        let track_name = if is_era {
+            Some("Eras".to_string())
+        } else {
+            slide.group.as_deref().map(str::trim).filter(|g| !g.is_empty()).map(str::to_string)
+        };
+        if let Some(ref name) = track_name {
+            if !tracks.iter().any(|t| &t.name == name) {
+                tracks.push(ImportedTrack { name: name.clone(), ..Default::default() });
             }
         }
+
+        let media = slide.media.as_ref().filter(|m| !m.url.trim().is_empty());
+        let url = media.map(|m| m.url.trim().to_string());
+        let is_image = url.as_deref().is_some_and(|u| {
+            let path = u.split(['?', '#']).next().unwrap_or(u).to_ascii_lowercase();
+            [".png", ".jpg", ".jpeg", ".gif", ".svg", ".webp"].iter().any(|ext| path.ends_with(ext))
+        });
+        let end_date = slide.end_date.as_ref().map(timelinejs_date);
+
+        events.push(ImportedEvent {
+            title: slide.text.as_ref().map(|t| html_to_text(&t.headline)).unwrap_or_default(),
+            description: slide.text.as_ref().map(|t| html_to_text(&t.text)),
+            start_date: slide.start_date.as_ref().map(timelinejs_date).unwrap_or_default(),
+            event_type: Some(match (is_era, &end_date) {
+                (true, _) => "era",
+                (false, Some(_)) => "range",
+                (false, None) => "point",
+            }.to_string()),
+            end_date,
+            track_name,
+            color: slide.background.as_ref().and_then(|b| b.color.as_deref()).and_then(parse_color),
+            image_path: url.clone().filter(|_| is_image),
+            external_link: url.filter(|_| !is_image),
+            source: media.map(|m| html_to_text(&m.credit)).filter(|c| !c.is_empty()),
+            ..Default::default()
+        });
     }
 
-    Ok(tl_id)
+    Ok(ImportedTimeline {
+        title,
+        description: title_text.map(|t| html_to_text(&t.text)).filter(|t| !t.is_empty()),
+        tracks,
+        events,
+        ..Default::default()
+    })
 }
 
+/// Import a TimelineJS (Knight Lab) JSON document as a new timeline. With
+/// `dry_run` everything is validated and counted, then rolled back.
+#[cfg(feature = "desktop")]
 #[tauri::command]
-pub fn import_csv(
+pub fn import_timelinejs(
     db: State<'_, Mutex<Connection>>,
-    timeline_id: String,
-    csv_data: String,
-    column_mapping: std::collections::HashMap<String, String>,
-) -> AppResult<u32> {
+    data: String,
+    dry_run: Option<bool>,
+) -> AppResult<ImportReport> {
+    let imported = timelinejs_to_timeline(&data)?;
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    import_in_transaction(&conn, &imported, dry_run.unwrap_or(false))
+}
 
-    // Parse CSV
+/// Outcome of one CSV data row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CsvRowStatus {
+    Imported,
+    /// Imported, but some values were ignored; see the reason.
+    Warning,
+    Skipped,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvRowResult {
+    /// Row number as a spreadsheet shows it; the header is row 1.
+    pub row: usize,
+    pub status: CsvRowStatus,
+    pub title: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportReport {
+    pub events_created: u32,
+    pub tracks_created: Vec<String>,
+    pub rows: Vec<CsvRowResult>,
+}
+
+impl CsvImportReport {
+    fn row(&mut self, row: usize, status: CsvRowStatus, title: &str, reasons: Vec<String>) {
+        self.rows.push(CsvRowResult {
+            row,
+            status,
+            title: title.to_string(),
+            reason: (!reasons.is_empty()).then(|| reasons.join("; ")),
+        });
+    }
+}
+
+/// Accepts `#rgb` and `#rrggbb` hex colors.
+fn parse_color(raw: &str) -> Option<String> {
+    let hex = raw.strip_prefix('#')?;
+    let valid = matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit());
+    valid.then(|| raw.to_ascii_lowercase())
+}
+
+/// Date formats tried, in order, for CSV dates that are not already in a form
+/// `HistoricalDate::parse` understands. Day-first wins for ambiguous dates.
+const DEFAULT_DATE_FORMATS: &[&str] = &[
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%d.%m.%Y",
+    "%d-%m-%Y",
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%d %B %Y",
+    "%d %b %Y",
+];
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportOptions {
+    /// Field delimiter; sniffed from the first lines when omitted.
+    pub delimiter: Option<char>,
+    /// chrono format strings for dates, replacing `DEFAULT_DATE_FORMATS`.
+    pub date_formats: Option<Vec<String>>,
+}
+
+/// Windows-1252 characters for bytes 0x80-0x9F; Latin-1 control codes elsewhere.
+const CP1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+/// Decode a text file. Honours UTF-8 and UTF-16 byte order marks, and reads
+/// anything that is not valid UTF-8 as Windows-1252 (a superset of Latin-1),
+/// which is what spreadsheet exports usually are.
+pub fn decode_text(bytes: &[u8]) -> String {
+    let utf16 = |rest: &[u8], from_bytes: fn([u8; 2]) -> u16| -> String {
+        char::decode_utf16(rest.chunks_exact(2).map(|pair| from_bytes([pair[0], pair[1]])))
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    };
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return String::from_utf8_lossy(rest).into_owned();
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        return utf16(rest, u16::from_le_bytes);
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFE\xFF") {
+        return utf16(rest, u16::from_be_bytes);
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes
+            .iter()
+            .map(|&b| match b {
+                0x80..=0x9F => CP1252_HIGH[usize::from(b - 0x80)],
+                _ => char::from(b),
+            })
+            .collect(),
+    }
+}
+
+/// Guess the delimiter from the first lines: the candidate found (outside
+/// quotes) on the header that keeps the same count on the most lines wins.
+fn sniff_delimiter(text: &str) -> u8 {
+    // Ordered so that ties go to the later, more common candidates.
+    const CANDIDATES: [u8; 4] = [b'|', b'\t', b';', b','];
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).take(10).collect();
+    let count = |line: &str, delimiter: u8| {
+        let mut quoted = false;
+        line.bytes()
+            .filter(|&b| {
+                if b == b'"' {
+                    quoted = !quoted;
+                }
+                b == delimiter && !quoted
+            })
+            .count()
+    };
+
+    CANDIDATES
+        .into_iter()
+        .filter_map(|delimiter| {
+            let header = count(lines.first()?, delimiter);
+            let consistent = lines.iter().filter(|l| count(l, delimiter) == header).count();
+            (header > 0).then_some((delimiter, consistent, header))
+        })
+        .max_by_key(|&(_, consistent, header)| (consistent, header))
+        .map_or(b',', |(delimiter, ..)| delimiter)
+}
+
+/// Read a CSV date into canonical form. The flag is false when only a year
+/// could be picked out of the text, so the caller can keep the original.
+///
+/// The configured day/month formats go first, so "05/06/2007" follows the
+/// file's convention rather than ISO order. A two-digit year ("7/14/89") is
+/// ambiguous and not read as a date in the first century.
+fn parse_csv_date(raw: &str, formats: &[String]) -> Option<(HistoricalDate, bool)> {
+    let mut short_year = false;
+    for format in formats {
+        if let Ok(date) = chrono::NaiveDate::parse_from_str(raw, format) {
+            if date.year() >= 100 {
+                return Some((HistoricalDate::from_naive_date(date), true));
+            }
+            short_year = true;
+        }
+    }
+    if short_year {
+        return None;
+    }
+    if let Ok(date) = HistoricalDate::parse(raw) {
+        return Some((date, true));
+    }
+
+    // Last resort: text like "Summer 1789" with a single plausible year in it.
+    let years: Vec<&str> = raw
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|run| matches!(run.len(), 3 | 4))
+        .collect();
+    match years.as_slice() {
+        [year] => Some((HistoricalDate::from_year(year.parse().ok()?)?, false)),
+        _ => None,
+    }
+}
+
+impl CsvImportOptions {
+    fn date_formats(&self) -> Vec<String> {
+        self.date_formats
+            .clone()
+            .unwrap_or_else(|| DEFAULT_DATE_FORMATS.iter().map(|f| f.to_string()).collect())
+    }
+}
+
+/// The delimiter to read the text with: the given one, or a sniffed guess.
+fn csv_delimiter(text: &str, options: &CsvImportOptions) -> AppResult<u8> {
+    match options.delimiter {
+        Some(c) if c.is_ascii() => Ok(c as u8),
+        Some(c) => Err(AppError::Validation(format!("Delimiter '{c}' must be an ASCII character"))),
+        None => Ok(sniff_delimiter(text)),
+    }
+}
+
+/// Set up a CSV reader for text without a BOM; returns it with the trimmed headers.
+fn csv_reader(text: &str, delimiter: u8) -> AppResult<(csv::Reader<&[u8]>, Vec<String>)> {
     let mut reader = csv::ReaderBuilder::new()
         .has_headers(true)
-        .from_reader(csv_data.as_bytes());
+        .delimiter(delimiter)
+        .from_reader(text.as_bytes());
 
     let headers: Vec<String> = reader
         .headers()
         .map_err(|e| AppError::Validation(format!("CSV header error: {e}")))?
         .iter()
-        .map(|s| s.to_string())
+        .map(|s| s.trim().to_string())
         .collect();
+    Ok((reader, headers))
+}
+
+/// Id of the timeline's track with this name, creating the track if needed.
+fn csv_track(
+    conn: &Connection,
+    timeline_id: &str,
+    name: &str,
+    tracks: &mut HashMap<String, String>,
+    report: &mut CsvImportReport,
+) -> AppResult<String> {
+    if let Some(id) = tracks.get(name) {
+        return Ok(id.clone());
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO tracks (id, timeline_id, name, sort_order)
+         VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM tracks WHERE timeline_id = ?2))",
+        rusqlite::params![id, timeline_id, name],
+    )?;
+    tracks.insert(name.to_string(), id.clone());
+    report.tracks_created.push(name.to_string());
+    Ok(id)
+}
+
+/// Import CSV rows as events of an existing timeline.
+///
+/// `column_mapping` maps event fields (`title`, `startDate`, `endDate`,
+/// `description`, `eventType`, `importance`, `tags`, `trackName`, `color`,
+/// `source`, `externalLink`) to CSV header names; only `title` and `startDate`
+/// are required. Rows without a track name go to the first track, and unknown
+/// track names create new tracks. Dates are normalized to canonical form; when
+/// one can only be read approximately or not at all, the original text is
+/// kept in `source` and the row carries a warning. A row whose start date
+/// cannot be read is skipped. Every data row gets a result entry.
+pub fn import_csv_rows(
+    conn: &Connection,
+    timeline_id: &str,
+    csv_data: &str,
+    column_mapping: &HashMap<String, String>,
+    options: &CsvImportOptions,
+) -> AppResult<CsvImportReport> {
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM timelines WHERE id = ?1)",
+        [timeline_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(AppError::NotFound(format!("Timeline {timeline_id} not found")));
+    }
+
+    // Parse CSV
+    let text = csv_data.strip_prefix('\u{feff}').unwrap_or(csv_data);
+    let date_formats = options.date_formats();
+    let (mut reader, headers) = csv_reader(text, csv_delimiter(text, options)?)?;
 
     // Resolve column indices
     let get_col_idx = |field: &str| -> Option<usize> {
@@ -177,58 +857,378 @@ pub fn import_csv(
     let desc_idx = get_col_idx("description");
     let tags_idx = get_col_idx("tags");
     let type_idx = get_col_idx("eventType");
+    let importance_idx = get_col_idx("importance");
+    let track_idx = get_col_idx("trackName");
+    let color_idx = get_col_idx("color");
+    let source_idx = get_col_idx("source");
+    let link_idx = get_col_idx("externalLink");
 
-    // Get first track
-    let track_id: String = conn
-        .query_row(
-            "SELECT id FROM tracks WHERE timeline_id = ?1 ORDER BY sort_order LIMIT 1",
-            [&timeline_id],
-            |row| row.get(0),
-        )
-        .map_err(|_| AppError::Validation("Timeline has no tracks".to_string()))?;
+    let mut stmt = conn.prepare("SELECT name, id FROM tracks WHERE timeline_id = ?1 ORDER BY sort_order")?;
+    let existing_tracks = stmt
+        .query_map([timeline_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut default_track = existing_tracks.first().map(|(_, id)| id.clone());
+    let mut tracks: HashMap<String, String> = HashMap::new();
+    for (name, id) in existing_tracks {
+        tracks.entry(name).or_insert(id);
+    }
 
-    let mut count = 0u32;
-    let mut skipped = 0u32;
-    for result in reader.records() {
-        let record = result.map_err(|e| AppError::Validation(format!("CSV row error: {e}")))?;
+    let mut report = CsvImportReport::default();
+    for (i, result) in reader.records().enumerate() {
+        let row = i + 2;
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                report.row(row, CsvRowStatus::Skipped, "", vec![format!("Malformed row: {e}")]);
+                continue;
+            }
+        };
+        let field = |idx: Option<usize>| idx.and_then(|i| record.get(i)).map(str::trim).filter(|v| !v.is_empty());
 
         let title = record.get(title_idx).unwrap_or("").trim();
-        let start_date = record.get(date_idx).unwrap_or("").trim();
-
-        if title.is_empty() || start_date.is_empty() {
-            skipped += 1;
+        if title.is_empty() {
+            report.row(row, CsvRowStatus::Skipped, title, vec!["Title is empty".to_string()]);
+            continue;
+        }
+        let mut warnings = Vec::new();
+        let mut original_dates = Vec::new();
+        let raw_start = record.get(date_idx).unwrap_or("").trim();
+        let start_date = match parse_csv_date(raw_start, &date_formats) {
+            Some((date, exact)) => {
+                if !exact {
+                    warnings.push(format!("Start date '{raw_start}' read as {date}"));
+                    original_dates.push(raw_start);
+                }
+                date
+            }
+            None if !raw_start.is_empty() => {
+                let reason = format!("Start date '{raw_start}' not recognized");
+                report.row(row, CsvRowStatus::Skipped, title, vec![reason]);
+                continue;
+            }
+            None => {
+                report.row(row, CsvRowStatus::Skipped, title, vec!["Start date is empty".to_string()]);
+                continue;
+            }
+        };
+        let end_date = match field(end_date_idx).map(|raw| (raw, parse_csv_date(raw, &date_formats))) {
+            None => None,
+            Some((raw, Some((date, exact)))) => {
+                if !exact {
+                    warnings.push(format!("End date '{raw}' read as {date}"));
+                    original_dates.push(raw);
+                }
+                Some(date)
+            }
+            Some((raw, None)) => {
+                warnings.push(format!("End date '{raw}' not recognized; kept in source"));
+                original_dates.push(raw);
+                None
+            }
+        };
+        if end_date.is_some_and(|end| end.sort_key() < start_date.sort_key()) {
+            report.row(row, CsvRowStatus::Skipped, title, vec!["End date is before start date".to_string()]);
             continue;
         }
 
-        let event_id = uuid::Uuid::new_v4().to_string();
-        let end_date = end_date_idx.and_then(|i| {
-            let v = record.get(i).unwrap_or("").trim();
-            if v.is_empty() { None } else { Some(v.to_string()) }
+        let importance = match field(importance_idx) {
+            None => 3,
+            Some(raw) => match raw.parse::<i32>() {
+                Ok(value @ 1..=5) => value,
+                _ => {
+                    warnings.push(format!("Importance '{raw}' is not 1-5; using 3"));
+                    3
+                }
+            },
+        };
+        let color = field(color_idx).and_then(|raw| {
+            let color = parse_color(raw);
+            if color.is_none() {
+                warnings.push(format!("Color '{raw}' is not a hex color; ignored"));
+            }
+            color
         });
-        let description = desc_idx.and_then(|i| record.get(i)).unwrap_or("").to_string();
-        let tags = tags_idx.and_then(|i| record.get(i)).unwrap_or("").to_string();
-        let event_type = type_idx.and_then(|i| record.get(i)).unwrap_or("point").to_string();
+        let track_id = match (field(track_idx), &default_track) {
+            (Some(name), _) => csv_track(conn, timeline_id, name, &mut tracks, &mut report)?,
+            (None, Some(id)) => id.clone(),
+            (None, None) => csv_track(conn, timeline_id, "Default", &mut tracks, &mut report)?,
+        };
+        default_track.get_or_insert_with(|| track_id.clone());
+
+        let event_id = uuid::Uuid::new_v4().to_string();
+        let description = field(desc_idx).unwrap_or("");
+        let tags = field(tags_idx).unwrap_or("");
+        let event_type = field(type_idx).unwrap_or(if end_date.is_some() { "range" } else { "point" });
+        let mut source_parts: Vec<String> = field(source_idx).map(str::to_string).into_iter().collect();
+        source_parts.extend(original_dates.iter().map(|raw| format!("Original date: {raw}")));
+        let source = (!source_parts.is_empty()).then(|| source_parts.join("; "));
 
         conn.execute(
-            "INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, event_type, importance, tags, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 3, ?9, ?10, ?11)",
-            rusqlite::params![event_id, timeline_id, track_id, title, description, start_date, end_date, event_type, tags, now, now],
+            "INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, event_type, importance, color, external_link, tags, source, created_at, updated_at, start_sort_key, end_sort_key)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?14, ?15, ?16)",
+            rusqlite::params![
+                event_id, timeline_id, track_id, title, description, start_date, end_date, event_type,
+                importance, color, field(link_idx), tags, source, now,
+                start_date.sort_key(), end_date.map(|d| d.sort_key())
+            ],
         )?;
-        count += 1;
+        report.events_created += 1;
+        let status = if warnings.is_empty() { CsvRowStatus::Imported } else { CsvRowStatus::Warning };
+        report.row(row, status, title, warnings);
+    }
+
+    Ok(report)
+}
+
+/// What the values of a CSV column look like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CsvColumnKind {
+    Date,
+    Number,
+    Text,
+    Empty,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvColumn {
+    pub name: String,
+    pub kind: CsvColumnKind,
+    /// The first few non-empty values.
+    pub samples: Vec<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvPreview {
+    pub delimiter: char,
+    pub columns: Vec<CsvColumn>,
+    /// The first rows as read, for showing the file as a table.
+    pub rows: Vec<Vec<String>>,
+    /// Event field → header, in the shape `import_csv` takes as `column_mapping`.
+    pub suggested_mapping: HashMap<String, String>,
+}
+
+pub const PREVIEW_ROWS: usize = 20;
+const SAMPLES_PER_COLUMN: usize = 5;
+
+/// Header names (lowercase, letters and digits only) that suggest each event
+/// field, best first. Short names must match exactly; longer ones may also
+/// appear inside a header.
+const FIELD_HEADERS: &[(&str, &[&str])] = &[
+    ("title", &["title", "name", "event", "headline", "titel", "titre", "subject"]),
+    ("startDate", &["startdate", "start", "date", "begin", "from", "year", "when", "datum"]),
+    ("endDate", &["enddate", "end", "until", "to", "finish"]),
+    ("description", &["description", "desc", "details", "summary", "notes", "text"]),
+    ("eventType", &["eventtype", "type", "kind"]),
+    ("importance", &["importance", "priority", "weight", "rank"]),
+    ("tags", &["tags", "keywords", "labels"]),
+    ("trackName", &["trackname", "track", "category", "group", "lane", "theme"]),
+    ("color", &["color", "colour"]),
+    ("source", &["source", "citation", "reference"]),
+    ("externalLink", &["externallink", "link", "url", "website"]),
+];
+
+fn column_kind(name: &str, values: &[&str], date_formats: &[String]) -> CsvColumnKind {
+    if values.is_empty() {
+        return CsvColumnKind::Empty;
     }
+    let bare_integers = values.iter().all(|v| v.chars().all(|c| c.is_ascii_digit()));
+    let dates = values
+        .iter()
+        .all(|v| matches!(parse_csv_date(v, date_formats), Some((_, true))));
+    // A column of plain integers is only a date column (years) if its name says so.
+    let named_like_date = suggested_field(name).is_some_and(|f| f == "startDate" || f == "endDate");
+    if dates && (!bare_integers || named_like_date) {
+        CsvColumnKind::Date
+    } else if values.iter().all(|v| v.parse::<f64>().is_ok()) {
+        CsvColumnKind::Number
+    } else {
+        CsvColumnKind::Text
+    }
+}
+
+fn normalize_header(header: &str) -> String {
+    header.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_ascii_lowercase()
+}
+
+/// The event field a header most likely holds, by exact name.
+fn suggested_field(header: &str) -> Option<&'static str> {
+    let header = normalize_header(header);
+    FIELD_HEADERS
+        .iter()
+        .find(|(_, names)| names.contains(&header.as_str()))
+        .map(|(field, _)| *field)
+}
 
-    if skipped > 0 {
-        tracing::warn!("CSV import: skipped {skipped} rows with empty title or date");
+/// Pair event fields with headers: exact names first, then longer names found
+/// inside headers, then the first date column for a missing start date. Each
+/// header is used at most once.
+fn suggest_mapping(columns: &[CsvColumn]) -> HashMap<String, String> {
+    let normalized: Vec<String> = columns.iter().map(|c| normalize_header(&c.name)).collect();
+    let mut mapping: HashMap<String, String> = HashMap::new();
+    let mut used = vec![false; columns.len()];
+
+    for exact in [true, false] {
+        let matches = |header: &str, name: &str| {
+            if exact {
+                header == name
+            } else {
+                name.len() >= 4 && header.contains(name)
+            }
+        };
+        for (field, names) in FIELD_HEADERS {
+            if mapping.contains_key(*field) {
+                continue;
+            }
+            let found = names.iter().find_map(|name| {
+                (0..columns.len()).find(|&i| !used[i] && matches(&normalized[i], name))
+            });
+            if let Some(i) = found {
+                used[i] = true;
+                mapping.insert(field.to_string(), columns[i].name.clone());
+            }
+        }
     }
 
-    Ok(count)
+    if !mapping.contains_key("startDate") {
+        if let Some(i) = (0..columns.len()).find(|&i| !used[i] && columns[i].kind == CsvColumnKind::Date) {
+            mapping.insert("startDate".to_string(), columns[i].name.clone());
+        }
+    }
+    mapping
+}
+
+/// Read the header and first rows of a CSV file so the import wizard can
+/// show the data, column types and a suggested column mapping.
+pub fn inspect_csv(csv_data: &str, options: &CsvImportOptions, max_rows: usize) -> AppResult<CsvPreview> {
+    let text = csv_data.strip_prefix('\u{feff}').unwrap_or(csv_data);
+    let date_formats = options.date_formats();
+    let delimiter = csv_delimiter(text, options)?;
+    let (mut reader, headers) = csv_reader(text, delimiter)?;
+
+    let rows: Vec<Vec<String>> = reader
+        .records()
+        .take(max_rows)
+        .filter_map(Result::ok)
+        .map(|record| record.iter().map(|v| v.trim().to_string()).collect())
+        .collect();
+
+    let columns: Vec<CsvColumn> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let values: Vec<&str> = rows
+                .iter()
+                .filter_map(|row| row.get(i).map(String::as_str))
+                .filter(|v| !v.is_empty())
+                .collect();
+            CsvColumn {
+                name: name.clone(),
+                kind: column_kind(name, &values, &date_formats),
+                samples: values.iter().take(SAMPLES_PER_COLUMN).map(|v| v.to_string()).collect(),
+            }
+        })
+        .collect();
+
+    Ok(CsvPreview {
+        delimiter: char::from(delimiter),
+        suggested_mapping: suggest_mapping(&columns),
+        columns,
+        rows,
+    })
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn preview_csv(
+    csv_data: String,
+    options: Option<CsvImportOptions>,
+    max_rows: Option<usize>,
+) -> AppResult<CsvPreview> {
+    inspect_csv(&csv_data, &options.unwrap_or_default(), max_rows.unwrap_or(PREVIEW_ROWS))
+}
+
+/// Import CSV rows into a timeline as one undoable step.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn import_csv(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+    csv_data: String,
+    column_mapping: HashMap<String, String>,
+    options: Option<CsvImportOptions>,
+) -> AppResult<CsvImportReport> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    import_csv_data(&conn, &timeline_id, &csv_data, &column_mapping, &options.unwrap_or_default())
+}
+
+/// Run `import_csv_rows` in one transaction, journaled as a single undo step.
+pub fn import_csv_data(
+    conn: &Connection,
+    timeline_id: &str,
+    csv_data: &str,
+    column_mapping: &HashMap<String, String>,
+    options: &CsvImportOptions,
+) -> AppResult<CsvImportReport> {
+    let tx = conn.unchecked_transaction()?;
+    let before = Snapshot::timeline(&tx, timeline_id)?;
+    let report = import_csv_rows(&tx, timeline_id, csv_data, column_mapping, options)?;
+    journal::record(&tx, "Import CSV", before, Snapshot::timeline(&tx, timeline_id)?)?;
+    tx.commit()?;
+    Ok(report)
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    #[cfg(feature = "desktop")]
+    use super::timelinejs_to_timeline;
+    use super::{
+        decode_text, import_csv_rows, import_timeline, inspect_csv, parse_csv_date, sniff_delimiter, CsvColumnKind,
+        CsvImportOptions, CsvRowStatus, ImportedTimeline,
+    };
     use crate::db::init_test_db;
     use rusqlite::params;
 
+    #[test]
+    fn test_import_report_and_rollback() {
+        let conn = init_test_db().unwrap();
+        let imported: ImportedTimeline = serde_json::from_str(
+            r#"{
+                "title": "Report",
+                "tracks": [{"name": "A"}, {"name": "A"}],
+                "events": [
+                    {"title": "Same", "startDate": "1900"},
+                    {"title": "Same", "startDate": "1901"},
+                    {"title": "Broken", "startDate": "someday"},
+                    {"title": "", "startDate": "1902"}
+                ],
+                "connections": [
+                    {"sourceTitle": "Same", "targetTitle": "Broken"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tx = conn.unchecked_transaction().unwrap();
+        let report = import_timeline(&tx, &imported).unwrap();
+        assert_eq!(report.tracks_created, 1);
+        assert_eq!(report.events_created, 2);
+        assert_eq!(report.connections_created, 0);
+        assert_eq!(report.skipped.len(), 3);
+        assert_eq!(report.duplicate_titles, vec!["Same".to_string()]);
+        assert_eq!(report.unresolved_connections[0].missing, vec!["Broken".to_string()]);
+        tx.rollback().unwrap();
+
+        let count: i32 = conn
+            .query_row("SELECT COUNT(*) FROM timelines", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
     #[test]
     fn test_import_json() {
         let conn = init_test_db().unwrap();
@@ -436,4 +1436,226 @@ mod tests {
             .unwrap();
         assert_eq!(title, "WWII Starts");
     }
+
+    fn csv_mapping(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_import_csv_rows_report() {
+        let conn = init_test_db().unwrap();
+        conn.execute("INSERT INTO timelines (id, title) VALUES ('tl', 'CSV')", []).unwrap();
+        conn.execute("INSERT INTO tracks (id, timeline_id, name) VALUES ('tr', 'tl', 'Main')", []).unwrap();
+
+        let csv_data = "\
+Title,Date,End,Track,Importance,Colour,Link,Source
+Bastille,1789-07-14,,Politics,5,#EF4444,https://example.org,Archive
+Relativity,1905,1915,Science,high,red,,
+Empty,,,,,,,
+Backwards,1900,1800,,,,,
+Unknown end,1900,whenever,,,,,
+Plain,1969-07-20,,,,,,
+";
+        let mapping = csv_mapping(&[
+            ("title", "Title"),
+            ("startDate", "Date"),
+            ("endDate", "End"),
+            ("trackName", "Track"),
+            ("importance", "Importance"),
+            ("color", "Colour"),
+            ("externalLink", "Link"),
+            ("source", "Source"),
+        ]);
+
+        let report = import_csv_rows(&conn, "tl", csv_data, &mapping, &CsvImportOptions::default()).unwrap();
+        assert_eq!(report.events_created, 4);
+        assert_eq!(report.tracks_created, vec!["Politics".to_string(), "Science".to_string()]);
+        let statuses: Vec<(usize, CsvRowStatus)> = report.rows.iter().map(|r| (r.row, r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (2, CsvRowStatus::Imported),
+                (3, CsvRowStatus::Warning),
+                (4, CsvRowStatus::Skipped),
+                (5, CsvRowStatus::Skipped),
+                (6, CsvRowStatus::Warning),
+                (7, CsvRowStatus::Imported),
+            ]
+        );
+        assert!(report.rows[1].reason.as_deref().unwrap().contains("Importance"));
+
+        let (importance, color, link, source, track): (i32, Option<String>, Option<String>, Option<String>, String) = conn
+            .query_row(
+                "SELECT e.importance, e.color, e.external_link, e.source, t.name FROM events e JOIN tracks t ON t.id = e.track_id WHERE e.title = 'Bastille'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .unwrap();
+        assert_eq!(importance, 5);
+        assert_eq!(color.as_deref(), Some("#ef4444"));
+        assert_eq!(link.as_deref(), Some("https://example.org"));
+        assert_eq!(source.as_deref(), Some("Archive"));
+        assert_eq!(track, "Politics");
+
+        let plain_track: String = conn
+            .query_row("SELECT track_id FROM events WHERE title = 'Plain'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(plain_track, "tr");
+    }
+
+    #[test]
+    fn test_csv_encoding_delimiter_and_dates() {
+        assert_eq!(decode_text(b"\xEF\xBB\xBFTitle"), "Title");
+        assert_eq!(decode_text(b"Caf\xE9 \x80"), "Café €");
+        assert_eq!(decode_text(b"\xFF\xFEA\x00\xE9\x00"), "Aé");
+        assert_eq!(sniff_delimiter("a;b;c\n1;\"x, y\";3\n"), b';');
+        assert_eq!(sniff_delimiter("a\tb\n1\t2\n"), b'\t');
+        assert_eq!(sniff_delimiter("title\nonly\n"), b',');
+
+        let conn = init_test_db().unwrap();
+        conn.execute("INSERT INTO timelines (id, title) VALUES ('tl', 'CSV')", []).unwrap();
+        let csv_data = decode_text(
+            b"\xEF\xBB\xBFTitel;Datum;Quelle\n\
+Prise de la Bastille;14/07/1789;Archives\n\
+Sacre;\"December 2, 1804\";\n\
+Printemps;Spring 1848;\n\
+Jamais;someday;\n",
+        );
+        let mapping = csv_mapping(&[("title", "Titel"), ("startDate", "Datum"), ("source", "Quelle")]);
+
+        let report = import_csv_rows(&conn, "tl", &csv_data, &mapping, &CsvImportOptions::default()).unwrap();
+        assert_eq!(report.events_created, 3);
+        assert_eq!(report.tracks_created, vec!["Default".to_string()]);
+        assert_eq!(report.rows[2].status, CsvRowStatus::Warning);
+        assert_eq!(report.rows[3].status, CsvRowStatus::Skipped);
+        assert_eq!(report.rows[3].reason.as_deref(), Some("Start date 'someday' not recognized"));
+
+        let rows: Vec<(String, Option<String>)> = conn
+            .prepare("SELECT start_date, source FROM events ORDER BY start_sort_key")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("1789-07-14".to_string(), Some("Archives".to_string())),
+                ("1804-12-02".to_string(), None),
+                ("1848".to_string(), Some("Original date: Spring 1848".to_string())),
+            ]
+        );
+
+        // Configured formats win over ISO order, and two-digit years are not guessed
+        let formats = CsvImportOptions::default().date_formats();
+        let read = |raw: &str, formats: &[String]| parse_csv_date(raw, formats).map(|(d, _)| d.to_string());
+        assert_eq!(read("05/06/2007", &formats).as_deref(), Some("2007-06-05"));
+        assert_eq!(read("05/06/07", &formats), None);
+        assert_eq!(read("7/14/89", &["%m/%d/%Y".to_string()]), None);
+        assert_eq!(read("1789-07-14", &formats).as_deref(), Some("1789-07-14"));
+
+        // Month-first formats can be configured instead
+        let options = CsvImportOptions {
+            delimiter: Some(','),
+            date_formats: Some(vec!["%m/%d/%Y".to_string()]),
+        };
+        let mapping = csv_mapping(&[("title", "Title"), ("startDate", "Date")]);
+        let report = import_csv_rows(&conn, "tl", "Title,Date\nUS,07/04/1776\n", &mapping, &options).unwrap();
+        assert_eq!(report.events_created, 1);
+        let date: String = conn
+            .query_row("SELECT start_date FROM events WHERE title = 'US'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(date, "1776-07-04");
+    }
+
+    #[test]
+    fn test_inspect_csv() {
+        let csv_data = "\
+Event Name;Year;End date;Category;Notes;Casualties
+Bastille;1789;;Politics;Storming;98
+Waterloo;1815;18/06/1815;War;Defeat;47000
+";
+        let preview = inspect_csv(csv_data, &CsvImportOptions::default(), 20).unwrap();
+        assert_eq!(preview.delimiter, ';');
+        assert_eq!(preview.rows.len(), 2);
+
+        let kinds: Vec<CsvColumnKind> = preview.columns.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                CsvColumnKind::Text,
+                CsvColumnKind::Date,
+                CsvColumnKind::Date,
+                CsvColumnKind::Text,
+                CsvColumnKind::Text,
+                CsvColumnKind::Number,
+            ]
+        );
+        assert_eq!(preview.columns[2].samples, vec!["18/06/1815".to_string()]);
+
+        let mapping = &preview.suggested_mapping;
+        assert_eq!(mapping.get("title").map(String::as_str), Some("Event Name"));
+        assert_eq!(mapping.get("startDate").map(String::as_str), Some("Year"));
+        assert_eq!(mapping.get("endDate").map(String::as_str), Some("End date"));
+        assert_eq!(mapping.get("trackName").map(String::as_str), Some("Category"));
+        assert_eq!(mapping.get("description").map(String::as_str), Some("Notes"));
+        assert_eq!(mapping.len(), 5);
+    }
+
+    #[cfg(feature = "desktop")]
+    #[test]
+    fn test_timelinejs_import() {
+        let data = r##"{
+            "title": {"text": {"headline": "The <i>Space</i> Race", "text": "<p>From Sputnik &amp; on</p>"}},
+            "events": [
+                {
+                    "start_date": {"year": "1957", "month": "10", "day": "4"},
+                    "text": {"headline": "Sputnik 1", "text": "<p>First satellite.</p><p>Launched from Baikonur.</p>"},
+                    "media": {"url": "https://example.org/sputnik.jpg", "credit": "NASA"},
+                    "group": "USSR",
+                    "background": {"color": "#CC0000"}
+                },
+                {
+                    "start_date": {"year": 1969, "month": 7, "day": 20, "hour": 20, "minute": 17},
+                    "end_date": {"year": 1969, "month": 7, "day": 21},
+                    "text": {"headline": "Apollo 11"},
+                    "media": {"url": "https://en.wikipedia.org/wiki/Apollo_11"},
+                    "group": "USA"
+                },
+                {"start_date": {"year": -500}, "text": {"headline": "Ancient astronomy"}},
+                {"text": {"headline": "No date"}}
+            ],
+            "eras": [
+                {"start_date": {"year": 1955}, "end_date": {"year": 1975}, "text": {"headline": "Space Race"}}
+            ]
+        }"##;
+
+        let imported = timelinejs_to_timeline(data).unwrap();
+        assert_eq!(imported.title, "The Space Race");
+        assert_eq!(imported.description.as_deref(), Some("From Sputnik & on"));
+        let track_names: Vec<&str> = imported.tracks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(track_names, vec!["USSR", "USA", "Eras"]);
+
+        let sputnik = &imported.events[0];
+        assert_eq!(sputnik.start_date, "1957-10-04");
+        assert_eq!(sputnik.description.as_deref(), Some("First satellite.\n\nLaunched from Baikonur."));
+        assert_eq!(sputnik.image_path.as_deref(), Some("https://example.org/sputnik.jpg"));
+        assert_eq!(sputnik.external_link, None);
+        assert_eq!(sputnik.color.as_deref(), Some("#cc0000"));
+        assert_eq!(sputnik.source.as_deref(), Some("NASA"));
+
+        let apollo = &imported.events[1];
+        assert_eq!(apollo.start_date, "1969-07-20T20:17:00");
+        assert_eq!(apollo.end_date.as_deref(), Some("1969-07-21"));
+        assert_eq!(apollo.event_type.as_deref(), Some("range"));
+        assert_eq!(apollo.external_link.as_deref(), Some("https://en.wikipedia.org/wiki/Apollo_11"));
+        assert_eq!(imported.events[2].start_date, "500 BCE");
+        assert_eq!(imported.events[4].event_type.as_deref(), Some("era"));
+        assert_eq!(imported.events[4].track_name.as_deref(), Some("Eras"));
+
+        let conn = init_test_db().unwrap();
+        let report = import_timeline(&conn, &imported).unwrap();
+        assert_eq!(report.events_created, 4);
+        assert_eq!(report.skipped[0].title, "No date");
+    }
 }
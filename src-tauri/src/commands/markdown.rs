@@ -0,0 +1,676 @@
+#[cfg(feature = "desktop")]
+use std::sync::Mutex;
+
+#[cfg(feature = "desktop")]
+use rusqlite::Connection;
+use serde::Deserialize;
+#[cfg(feature = "desktop")]
+use tauri::State;
+
+use super::export::{ExportedEvent, ExportedTimeline};
+#[cfg(feature = "desktop")]
+use super::import::{
+    import_in_transaction, ImportReport, ImportedConnection, ImportedEvent, ImportedTimeline, ImportedTrack,
+    SkippedItem,
+};
+use crate::db::dates::{DatePrecision, HistoricalDate};
+#[cfg(feature = "desktop")]
+use crate::error::{AppError, AppResult};
+
+/// A heading and the lines up to the next heading.
+#[cfg(feature = "desktop")]
+struct Section<'a> {
+    level: usize,
+    title: String,
+    /// 1-based line number of the heading.
+    line: usize,
+    body: Vec<&'a str>,
+}
+
+/// Event properties read from the top of a section.
+#[cfg(feature = "desktop")]
+#[derive(Debug, Default)]
+struct SectionFields {
+    start: Option<String>,
+    end: Option<String>,
+    track: Option<String>,
+    tags: Vec<String>,
+    importance: Option<i32>,
+    source: Option<String>,
+    description: String,
+}
+
+/// "## Title" -> (2, "Title"). As in CommonMark, a closing run of `#` after
+/// a space is dropped and an escaped one ("C\#") is kept.
+#[cfg(feature = "desktop")]
+fn heading(line: &str) -> Option<(usize, String)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let mut title = line[level..].strip_prefix(' ')?.trim();
+    let open = title.trim_end_matches('#');
+    if open.is_empty() || open.ends_with(' ') {
+        title = open.trim_end();
+    }
+    let title = match title.trim_end_matches('#').strip_suffix('\\') {
+        Some(text) if title.ends_with('#') => format!("{text}{}", &title[text.len() + 1..]),
+        _ => title.to_string(),
+    };
+    (1..=6).contains(&level).then_some((level, title))
+}
+
+/// Whether a line of free text was escaped by `escape_block`.
+#[cfg(feature = "desktop")]
+fn is_escaped_heading(line: &str) -> bool {
+    line.starts_with('\\') && line.trim_start_matches('\\').starts_with('#')
+}
+
+/// Split a document into heading sections, ignoring headings inside code
+/// fences and a leading `---` front matter block. Returns the front matter
+/// lines and the sections.
+#[cfg(feature = "desktop")]
+fn sections(data: &str) -> (Vec<&str>, Vec<Section<'_>>) {
+    let mut lines = data.lines().enumerate().peekable();
+    let mut front_matter = Vec::new();
+    if lines.peek().is_some_and(|(_, l)| l.trim() == "---") {
+        lines.next();
+        for (_, line) in lines.by_ref() {
+            if line.trim() == "---" {
+                break;
+            }
+            front_matter.push(line);
+        }
+    }
+
+    let mut sections: Vec<Section> = Vec::new();
+    let mut in_fence = false;
+    for (n, line) in lines {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        match heading(line).filter(|_| !in_fence) {
+            Some((level, title)) => sections.push(Section { level, title, line: n + 1, body: Vec::new() }),
+            None => {
+                if let Some(section) = sections.last_mut() {
+                    section.body.push(line);
+                }
+            }
+        }
+    }
+    (front_matter, sections)
+}
+
+/// A "key: value" property line, in the exported `**Date:** ...` form or the
+/// looser `date: ...` / `- track: ...` form.
+#[cfg(feature = "desktop")]
+fn property(line: &str) -> Option<(String, &str)> {
+    let line = line.trim();
+    let line = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")).unwrap_or(line);
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim().trim_matches('*').trim().to_ascii_lowercase();
+    let value = value.trim().trim_start_matches("**").trim();
+    matches!(key.as_str(), "date" | "start" | "end" | "track" | "tags" | "importance" | "source").then_some((key, value))
+}
+
+/// "1789 — 1799", "1789 to 1799"
+#[cfg(feature = "desktop")]
+fn split_range(value: &str) -> (String, Option<String>) {
+    let found = value
+        .split_once(['—', '–'])
+        .or_else(|| value.split_once(" to "))
+        .or_else(|| value.split_once(" - "));
+    match found {
+        Some((start, end)) => (start.trim().to_string(), Some(end.trim().to_string()).filter(|e| !e.is_empty())),
+        None => (value.trim().to_string(), None),
+    }
+}
+
+/// "a, b", "[a, b]" or "#a #b"
+#[cfg(feature = "desktop")]
+fn split_tags(value: &str) -> Vec<String> {
+    let value = value.trim().trim_start_matches('[').trim_end_matches(']');
+    let parts: Vec<&str> = if value.contains(',') { value.split(',').collect() } else { value.split_whitespace().collect() };
+    parts
+        .into_iter()
+        .map(|t| t.trim().trim_start_matches('#').trim_matches(['"', '\'']).to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+#[cfg(feature = "desktop")]
+fn read_fields(body: &[&str]) -> SectionFields {
+    let mut fields = SectionFields::default();
+    let mut description = Vec::new();
+    let mut in_properties = true;
+    // Set after an empty "tags:" whose items follow as a YAML list.
+    let mut tag_list = false;
+    let mut in_fence = false;
+    for &line in body {
+        let trimmed = line.trim();
+        if in_properties {
+            if trimmed.is_empty() || trimmed == "---" {
+                continue;
+            }
+            if let Some((key, value)) = property(trimmed) {
+                tag_list = key == "tags" && value.is_empty();
+                match key.as_str() {
+                    "date" | "start" => {
+                        let (start, end) = split_range(value);
+                        fields.start = Some(start);
+                        if end.is_some() {
+                            fields.end = end;
+                        }
+                    }
+                    "end" => fields.end = Some(value.to_string()).filter(|v| !v.is_empty()),
+                    "track" => fields.track = Some(value.to_string()).filter(|v| !v.is_empty()),
+                    // "4" or "4/5"
+                    "importance" => fields.importance = value.split('/').next().and_then(|n| n.trim().parse().ok()),
+                    "source" => fields.source = Some(value.to_string()).filter(|v| !v.is_empty()),
+                    _ => fields.tags.extend(split_tags(value)),
+                }
+                continue;
+            }
+            if let Some(item) = trimmed.strip_prefix("- ").filter(|_| tag_list) {
+                fields.tags.extend(split_tags(item));
+                continue;
+            }
+            in_properties = false;
+        }
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+        }
+        description.push(if !in_fence && is_escaped_heading(line) { &line[1..] } else { line });
+    }
+    fields.description = description.join("\n").trim().to_string();
+    fields
+}
+
+/// The text of the `**bold**` or `*italic*` span that `text` starts with,
+/// with backslash escapes removed, and the rest of the line.
+#[cfg(feature = "desktop")]
+fn emphasis<'a>(text: &'a str, marker: &str) -> Option<(String, &'a str)> {
+    let mut rest = text.strip_prefix(marker)?;
+    let mut out = String::new();
+    loop {
+        if let Some(after) = rest.strip_prefix(marker) {
+            return Some((out, after));
+        }
+        let mut chars = rest.chars();
+        match chars.next()? {
+            '\\' => out.push(chars.next()?),
+            c => out.push(c),
+        }
+        rest = chars.as_str();
+    }
+}
+
+/// A "Connections" list item: `- **Source** → *caused* → **Target** — label`.
+/// The exported form escapes `*` and `\` in titles, so arrows and dashes
+/// inside a title are read as part of it. Hand-written items without
+/// emphasis are split on the arrows.
+#[cfg(feature = "desktop")]
+fn connection_line(line: &str) -> Option<ImportedConnection> {
+    let line = line.trim().strip_prefix("- ").or_else(|| line.trim().strip_prefix("* "))?;
+    let (source, kind, target, label) = if line.starts_with("**") {
+        let (source, rest) = emphasis(line, "**")?;
+        let (kind, rest) = emphasis(rest.strip_prefix(" → ")?, "*")?;
+        let (target, rest) = emphasis(rest.strip_prefix(" → ")?, "**")?;
+        (source, kind, target, rest.strip_prefix(" — ").map(str::to_string))
+    } else {
+        let parts: Vec<&str> = line.split(" → ").collect();
+        let &[source, kind, rest] = parts.as_slice() else {
+            return None;
+        };
+        let (target, label) = match rest.split_once(" — ") {
+            Some((target, label)) => (target, Some(label.to_string())),
+            None => (rest, None),
+        };
+        (source.to_string(), kind.to_string(), target.to_string(), label)
+    };
+    Some(ImportedConnection {
+        source_title: Some(source.trim().to_string()),
+        target_title: Some(target.trim().to_string()),
+        connection_type: Some(kind.trim().to_string()),
+        label: label.map(|l| l.trim().to_string()),
+        ..Default::default()
+    })
+}
+
+/// Parse Markdown as written by `export_markdown` (`# Title`, `## Year`,
+/// `### Event`, `**Date:** start — end`) or a looser variant with `date:`,
+/// `end:`, `track:` and `tags:` lines under each heading. Any heading with a
+/// date becomes an event. A `##` heading without one groups the events below
+/// it and, unless it is a date itself, names their track. Other headings
+/// without a date are returned as skipped. A `## Connections` list links
+/// events by title.
+#[cfg(feature = "desktop")]
+fn markdown_to_timeline(fallback_title: &str, data: &str) -> (ImportedTimeline, Vec<SkippedItem>) {
+    let (front_matter, sections) = sections(data);
+    let mut title = front_matter
+        .iter()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("title"))
+        .map(|(_, value)| value.trim().trim_matches(['"', '\'']).to_string());
+    let mut description = None;
+
+    let mut tracks: Vec<ImportedTrack> = Vec::new();
+    let mut events = Vec::new();
+    let mut skipped = Vec::new();
+    let mut connections = Vec::new();
+    let mut group_track: Option<String> = None;
+    let mut title_heading_seen = false;
+    for section in &sections {
+        if section.level == 2 && section.title.eq_ignore_ascii_case("connections") {
+            connections.extend(section.body.iter().filter_map(|line| connection_line(line)));
+            continue;
+        }
+        let fields = read_fields(&section.body);
+        // The first `#` heading is the title, unless front matter named it.
+        if section.level == 1 && !title_heading_seen {
+            title_heading_seen = true;
+            title.get_or_insert_with(|| section.title.clone());
+            description = Some(fields.description).filter(|d| !d.is_empty());
+            continue;
+        }
+        let Some(start) = fields.start else {
+            if section.level <= 2 && fields.description.is_empty() {
+                let is_date = HistoricalDate::parse(&section.title).is_ok();
+                group_track = (!is_date && !section.title.is_empty()).then(|| section.title.clone());
+            } else {
+                let reason = format!("No date found under the heading on line {}", section.line);
+                skipped.push(SkippedItem::new("heading", section.line, &section.title, reason));
+            }
+            continue;
+        };
+
+        let track_name = fields.track.or_else(|| group_track.clone());
+        if let Some(ref name) = track_name {
+            if !tracks.iter().any(|t| &t.name == name) {
+                tracks.push(ImportedTrack { name: name.clone(), ..Default::default() });
+            }
+        }
+        events.push(ImportedEvent {
+            title: section.title.clone(),
+            description: Some(fields.description).filter(|d| !d.is_empty()),
+            start_date: start,
+            event_type: Some(if fields.end.is_some() { "range" } else { "point" }.to_string()),
+            end_date: fields.end,
+            track_name,
+            tags: Some(fields.tags.join(",")).filter(|t| !t.is_empty()),
+            importance: fields.importance,
+            source: fields.source,
+            ..Default::default()
+        });
+    }
+
+    let imported = ImportedTimeline {
+        title: title.filter(|t| !t.is_empty()).unwrap_or_else(|| fallback_title.to_string()),
+        description,
+        tracks,
+        events,
+        connections: (!connections.is_empty()).then_some(connections),
+        ..Default::default()
+    };
+    (imported, skipped)
+}
+
+/// How events are grouped under `##` headings in a Markdown export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MarkdownGrouping {
+    #[default]
+    Year,
+    Decade,
+    Century,
+    Track,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MarkdownExportOptions {
+    pub grouping: MarkdownGrouping,
+    /// A YAML front matter block with the timeline's title, tracks and date span.
+    pub front_matter: bool,
+    /// A closing "Connections" section listing cause and effect links.
+    pub include_connections: bool,
+}
+
+impl Default for MarkdownExportOptions {
+    fn default() -> Self {
+        Self { grouping: MarkdownGrouping::Year, front_matter: true, include_connections: true }
+    }
+}
+
+pub fn yaml_string(text: &str) -> String {
+    // A JSON string is a valid double-quoted YAML scalar.
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn front_matter(timeline: &ExportedTimeline) -> String {
+    let dates = |event: &ExportedEvent| {
+        let start = HistoricalDate::parse(&event.start_date).ok();
+        let end = event.end_date.as_deref().and_then(|d| HistoricalDate::parse(d).ok()).or(start);
+        start.zip(end)
+    };
+    let span = timeline.events.iter().filter_map(dates).reduce(|(first, last), (start, end)| {
+        (
+            if start.sort_key() < first.sort_key() { start } else { first },
+            if end.sort_key() > last.sort_key() { end } else { last },
+        )
+    });
+
+    let mut yaml = String::from("---\n");
+    yaml.push_str(&format!("title: {}\n", yaml_string(&timeline.title)));
+    if !timeline.description.is_empty() {
+        yaml.push_str(&format!("description: {}\n", yaml_string(&timeline.description)));
+    }
+    let tracks: Vec<String> = timeline.tracks.iter().map(|t| yaml_string(&t.name)).collect();
+    yaml.push_str(&format!("tracks: [{}]\n", tracks.join(", ")));
+    yaml.push_str(&format!("events: {}\n", timeline.events.len()));
+    if let Some((start, end)) = span {
+        yaml.push_str(&format!("start: {}\n", yaml_string(&start.to_string())));
+        yaml.push_str(&format!("end: {}\n", yaml_string(&end.to_string())));
+    }
+    yaml.push_str(&format!("created: {}\n", yaml_string(&timeline.created_at)));
+    yaml.push_str(&format!("updated: {}\n", yaml_string(&timeline.updated_at)));
+    yaml.push_str("---\n\n");
+    yaml
+}
+
+/// A heading's text with a trailing `#` escaped, so it is not read back as a
+/// closing sequence.
+fn heading_text(title: &str) -> String {
+    let open = title.trim_end_matches('#');
+    if open.len() == title.len() {
+        return title.to_string();
+    }
+    format!("{open}\\{}", &title[open.len()..])
+}
+
+/// Free text with every line that starts with `#` (after any backslashes)
+/// escaped with one more backslash, so it cannot become a heading. Lines in
+/// code fences are left alone.
+fn escape_block(text: &str) -> String {
+    let mut in_fence = false;
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| {
+            if line.trim().starts_with("```") {
+                in_fence = !in_fence;
+            }
+            if !in_fence && line.trim_start_matches('\\').starts_with('#') {
+                format!("\\{line}")
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Escape `*` and `\` in text set in bold or italics.
+fn escape_emphasis(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('*', "\\*")
+}
+
+fn push_event(md: &mut String, event: &ExportedEvent, with_track: bool) {
+    md.push_str(&format!("### {}\n\n", heading_text(&event.title)));
+
+    let mut properties = vec![match event.end_date {
+        Some(ref end) => format!("**Date:** {} — {end}", event.start_date),
+        None => format!("**Date:** {}", event.start_date),
+    }];
+    if with_track && !event.track_name.is_empty() {
+        properties.push(format!("**Track:** {}", event.track_name));
+    }
+    properties.push(format!("**Importance:** {}/5", event.importance));
+    let tags: Vec<&str> = event.tags.split(',').map(str::trim).filter(|t| !t.is_empty()).collect();
+    if !tags.is_empty() {
+        properties.push(format!("**Tags:** {}", tags.join(", ")));
+    }
+    if let Some(source) = event.source.as_deref().filter(|s| !s.is_empty()) {
+        properties.push(format!("**Source:** {source}"));
+    }
+    // Two trailing spaces keep the properties on separate lines when rendered.
+    md.push_str(&properties.join("  \n"));
+    md.push_str("\n\n");
+
+    if !event.description.is_empty() {
+        md.push_str(&escape_block(&event.description));
+        md.push_str("\n\n");
+    }
+}
+
+/// Render a timeline as Markdown. The output reads back with `import_markdown`.
+pub fn timeline_to_markdown(timeline: &ExportedTimeline, options: &MarkdownExportOptions) -> String {
+    let mut md = if options.front_matter { front_matter(timeline) } else { String::new() };
+    md.push_str(&format!("# {}\n\n", heading_text(&timeline.title)));
+    if !timeline.description.is_empty() {
+        md.push_str(&escape_block(&timeline.description));
+        md.push_str("\n\n");
+    }
+
+    let groups: Vec<(String, Vec<&ExportedEvent>)> = match options.grouping {
+        MarkdownGrouping::Track => timeline
+            .tracks
+            .iter()
+            .map(|track| {
+                let events = timeline.events.iter().filter(|e| e.track_id == track.id).collect::<Vec<_>>();
+                (track.name.clone(), events)
+            })
+            .filter(|(_, events)| !events.is_empty())
+            .collect(),
+        grouping => {
+            let precision = match grouping {
+                MarkdownGrouping::Century => DatePrecision::Century,
+                MarkdownGrouping::Decade => DatePrecision::Decade,
+                _ => DatePrecision::Year,
+            };
+            // Events are sorted by date, so each period is one run.
+            let mut groups: Vec<(String, Vec<&ExportedEvent>)> = Vec::new();
+            for event in &timeline.events {
+                let label = HistoricalDate::parse(&event.start_date)
+                    .ok()
+                    .and_then(|d| d.period(precision))
+                    .map_or_else(|| event.start_date.clone(), |d| d.to_string());
+                match groups.last_mut() {
+                    Some((last, events)) if *last == label => events.push(event),
+                    _ => groups.push((label, vec![event])),
+                }
+            }
+            groups
+        }
+    };
+
+    for (heading, events) in groups {
+        md.push_str(&format!("## {}\n\n", heading_text(&heading)));
+        for event in events {
+            push_event(&mut md, event, options.grouping != MarkdownGrouping::Track);
+        }
+    }
+
+    if options.include_connections && !timeline.connections.is_empty() {
+        md.push_str("## Connections\n\n");
+        for link in &timeline.connections {
+            md.push_str(&format!(
+                "- **{}** → *{}* → **{}**",
+                escape_emphasis(&link.source_title),
+                escape_emphasis(&link.connection_type),
+                escape_emphasis(&link.target_title)
+            ));
+            if let Some(label) = link.label.as_deref().filter(|l| !l.is_empty()) {
+                md.push_str(&format!(" — {label}"));
+            }
+            md.push('\n');
+        }
+        md.push('\n');
+    }
+
+    md
+}
+
+/// Import a Markdown document as a new timeline. `title` is used when the
+/// document has no `# Title` heading. Headings that could not be turned into
+/// events are listed in the report's `skipped` with kind "heading" and their
+/// line number as `index`.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn import_markdown(
+    db: State<'_, Mutex<Connection>>,
+    title: String,
+    data: String,
+    dry_run: Option<bool>,
+) -> AppResult<ImportReport> {
+    let (imported, headings) = markdown_to_timeline(&title, &data);
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let mut report = import_in_transaction(&conn, &imported, dry_run.unwrap_or(false))?;
+    report.skipped.extend(headings);
+    Ok(report)
+}
+
+#[cfg(all(test, feature = "desktop"))]
+mod tests {
+    use super::{markdown_to_timeline, timeline_to_markdown, MarkdownExportOptions, MarkdownGrouping};
+    use crate::commands::export::export_timeline;
+    use crate::commands::import::import_timeline;
+    use crate::db::init_test_db;
+
+    #[test]
+    fn test_markdown_import_exported_format() {
+        let data = "# French History\n\nKey moments.\n\n\
+## 1789\n\n### Storming of the Bastille\n\n**Date:** 1789-07-14\n\nThe prison falls.\n\nCrowds gather.\n\n\
+## 500 BCE\n\n### Reforms\n\n**Date:** 508 BCE — 507 BCE\n\n\
+### Untitled thoughts\n\nNo date here.\n";
+        let (imported, skipped) = markdown_to_timeline("fallback", data);
+        assert_eq!(imported.title, "French History");
+        assert_eq!(imported.description.as_deref(), Some("Key moments."));
+        assert!(imported.tracks.is_empty());
+        assert_eq!(imported.events.len(), 2);
+
+        let bastille = &imported.events[0];
+        assert_eq!(bastille.start_date, "1789-07-14");
+        assert_eq!(bastille.end_date, None);
+        assert_eq!(bastille.description.as_deref(), Some("The prison falls.\n\nCrowds gather."));
+        let reforms = &imported.events[1];
+        assert_eq!((reforms.start_date.as_str(), reforms.end_date.as_deref()), ("508 BCE", Some("507 BCE")));
+        assert_eq!(reforms.event_type.as_deref(), Some("range"));
+
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].title, "Untitled thoughts");
+        assert_eq!(skipped[0].index, 21);
+    }
+
+    #[test]
+    fn test_markdown_import_loose_format() {
+        let data = "---\ntitle: \"Worldbuilding\"\n---\n\
+## Kingdoms\n\n\
+### Founding of Arden\ndate: c. 1200\nend: 1250\ntags: [politics, arden]\n\nThe first king is crowned.\n\n\
+### The Long Winter\n- date: 1301 to 1303\n- track: Climate\n- tags:\n  - weather\n  - #famine\n\nCrops fail. Tags: none.\n\n\
+## Notes\n\nLoose text without a date.\n\n\
+```\n### Not a heading\n```\n";
+        let (imported, skipped) = markdown_to_timeline("fallback", data);
+        assert_eq!(imported.title, "Worldbuilding");
+        let track_names: Vec<&str> = imported.tracks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(track_names, vec!["Kingdoms", "Climate"]);
+
+        let arden = &imported.events[0];
+        assert_eq!(arden.start_date, "c. 1200");
+        assert_eq!(arden.end_date.as_deref(), Some("1250"));
+        assert_eq!(arden.tags.as_deref(), Some("politics,arden"));
+        assert_eq!(arden.track_name.as_deref(), Some("Kingdoms"));
+
+        let winter = &imported.events[1];
+        assert_eq!((winter.start_date.as_str(), winter.end_date.as_deref()), ("1301", Some("1303")));
+        assert_eq!(winter.track_name.as_deref(), Some("Climate"));
+        assert_eq!(winter.tags.as_deref(), Some("weather,famine"));
+        assert_eq!(winter.description.as_deref(), Some("Crops fail. Tags: none."));
+
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].title, "Notes");
+
+        let conn = init_test_db().unwrap();
+        let report = import_timeline(&conn, &imported).unwrap();
+        assert_eq!(report.events_created, 2);
+        assert_eq!(report.tracks_created, 2);
+    }
+
+    #[test]
+    fn test_markdown_export_round_trip() {
+        let conn = init_test_db().unwrap();
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title, description) VALUES ('tl', 'Greece', 'Classical era');
+             INSERT INTO tracks (id, timeline_id, name, sort_order) VALUES ('war', 'tl', 'Wars', 0), ('cul', 'tl', 'Culture', 1);
+             INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, importance, tags, source, start_sort_key)
+             VALUES ('e1', 'tl', 'war', 'Battle of Marathon', 'Athens wins.', '490 BCE', NULL, 5, 'battle,persia', 'Herodotus', -900000),
+                    ('e2', 'tl', 'war', 'Peloponnesian War', '', '431 BCE', '404 BCE', 4, '', NULL, -880000),
+                    ('e3', 'tl', 'cul', 'Parthenon completed', '', '432 BCE', NULL, 3, 'architecture', NULL, -880001);
+             INSERT INTO connections (id, timeline_id, source_event_id, target_event_id, connection_type, label)
+             VALUES ('c1', 'tl', 'e1', 'e2', 'influenced', 'Athenian confidence');",
+        )
+        .unwrap();
+        let exported = export_timeline(&conn, "tl").unwrap();
+
+        let options = MarkdownExportOptions { grouping: MarkdownGrouping::Century, ..Default::default() };
+        let md = timeline_to_markdown(&exported, &options);
+        assert!(md.starts_with("---\ntitle: \"Greece\"\ndescription: \"Classical era\"\ntracks: [\"Wars\", \"Culture\"]\nevents: 3\nstart: \"490 BCE\"\nend: \"404 BCE\"\n"));
+        assert!(md.contains("## 5th century BCE\n\n### Battle of Marathon\n\n**Date:** 490 BCE  \n**Track:** Wars  \n**Importance:** 5/5  \n**Tags:** battle, persia  \n**Source:** Herodotus\n\nAthens wins.\n\n"));
+        assert!(md.contains("**Date:** 431 BCE — 404 BCE"));
+        assert!(md.contains("## Connections\n\n- **Battle of Marathon** → *influenced* → **Peloponnesian War** — Athenian confidence\n"));
+        assert_eq!(md.matches("## 5th century BCE").count(), 1);
+
+        let (imported, skipped) = markdown_to_timeline("fallback", &md);
+        assert!(skipped.is_empty());
+        assert_eq!(imported.title, "Greece");
+        let marathon = &imported.events[0];
+        assert_eq!(marathon.track_name.as_deref(), Some("Wars"));
+        assert_eq!(marathon.importance, Some(5));
+        assert_eq!(marathon.tags.as_deref(), Some("battle,persia"));
+        assert_eq!(marathon.source.as_deref(), Some("Herodotus"));
+        assert_eq!(marathon.description.as_deref(), Some("Athens wins."));
+        let report = import_timeline(&conn, &imported).unwrap();
+        assert_eq!((report.events_created, report.connections_created), (3, 1));
+
+        let options = MarkdownExportOptions { grouping: MarkdownGrouping::Track, front_matter: false, include_connections: false };
+        let md = timeline_to_markdown(&exported, &options);
+        assert!(md.starts_with("# Greece\n\nClassical era\n\n## Wars\n\n### Battle of Marathon"));
+        assert!(!md.contains("**Track:**") && !md.contains("Connections"));
+        let (imported, _) = markdown_to_timeline("fallback", &md);
+        assert_eq!(imported.events[2].track_name.as_deref(), Some("Culture"));
+    }
+
+    #[test]
+    fn test_markdown_escapes_round_trip() {
+        let conn = init_test_db().unwrap();
+        conn.execute_batch(
+            r"INSERT INTO timelines (id, title, description) VALUES ('tl', 'Languages #', '# Not a heading');
+             INSERT INTO tracks (id, timeline_id, name, sort_order) VALUES ('tr', 'tl', 'Code', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, description, start_date, start_sort_key)
+             VALUES ('e1', 'tl', 'tr', 'C#', '# Heading-like line
+\# already escaped
+```
+# code stays
+```', '2000', 1),
+                    ('e2', 'tl', 'tr', 'Rise → fall — *part 2*', '', '2001', 2);
+             INSERT INTO connections (id, timeline_id, source_event_id, target_event_id, connection_type, label)
+             VALUES ('c1', 'tl', 'e1', 'e2', 'influenced', 'one — two');",
+        )
+        .unwrap();
+        let exported = export_timeline(&conn, "tl").unwrap();
+        let md = timeline_to_markdown(&exported, &MarkdownExportOptions::default());
+        assert!(md.contains("# Languages \\#\n\n\\# Not a heading\n\n"));
+        assert!(md.contains("### C\\#\n"));
+        assert!(md.contains("\\# Heading-like line\n\\\\# already escaped\n```\n# code stays\n```"));
+        assert!(md.contains("- **C#** → *influenced* → **Rise → fall — \\*part 2\\*** — one — two\n"));
+
+        let (imported, skipped) = markdown_to_timeline("fallback", &md);
+        assert!(skipped.is_empty());
+        assert_eq!(imported.title, "Languages #");
+        assert_eq!(imported.description.as_deref(), Some("# Not a heading"));
+        let titles: Vec<&str> = imported.events.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["C#", "Rise → fall — *part 2*"]);
+        assert_eq!(imported.events[0].description, exported.events[0].description.clone().into());
+        let link = &imported.connections.as_ref().unwrap()[0];
+        assert_eq!(link.source_title.as_deref(), Some("C#"));
+        assert_eq!(link.target_title.as_deref(), Some("Rise → fall — *part 2*"));
+        assert_eq!(link.connection_type.as_deref(), Some("influenced"));
+        assert_eq!(link.label.as_deref(), Some("one — two"));
+    }
+}
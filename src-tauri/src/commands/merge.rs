@@ -0,0 +1,649 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "desktop")]
+use tauri::State;
+
+use super::events::fetch_timeline_events;
+use super::import::{keep_or_new_id, ImportedEvent, ImportedTimeline, SkippedItem, UnresolvedConnection};
+use super::revisions::{diff_fields, record_revisions};
+use crate::db::dates::HistoricalDate;
+use crate::db::journal::{self, Snapshot};
+use crate::db::models::{Event, FieldDiff};
+use crate::error::{AppError, AppResult};
+
+/// What to do with an incoming event that would overwrite local values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictStrategy {
+    /// Keep the local values, only filling fields that are empty locally.
+    #[default]
+    KeepMine,
+    /// Overwrite the local event with the incoming values.
+    TakeTheirs,
+    /// Keep the local values as `KeepMine` does and add the incoming event as a new one.
+    KeepBoth,
+}
+
+/// How an incoming event was paired with a local one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchedBy {
+    Id,
+    TitleAndDate,
+}
+
+/// An incoming event paired with a local event whose fields differ.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeMatch {
+    pub index: usize,
+    /// The local event; per-conflict resolutions are keyed by this id.
+    pub event_id: String,
+    pub title: String,
+    pub matched_by: MatchedBy,
+    pub diffs: Vec<FieldDiff>,
+    /// How a conflict was resolved; `None` for plain updates.
+    pub resolution: Option<ConflictStrategy>,
+}
+
+/// What a merge changed (or, on a dry run, would have changed).
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    pub dry_run: bool,
+    pub tracks_added: Vec<String>,
+    pub events_added: Vec<String>,
+    /// Matches that only fill in fields that are empty locally.
+    pub events_updated: Vec<MergeMatch>,
+    /// Matches that disagree with a non-empty local value.
+    pub conflicts: Vec<MergeMatch>,
+    pub events_unchanged: u32,
+    pub connections_added: u32,
+    pub skipped: Vec<SkippedItem>,
+    pub unresolved_connections: Vec<UnresolvedConnection>,
+}
+
+/// The user-editable fields of an event, compared and written by a merge.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EventFields {
+    title: String,
+    description: String,
+    start_date: HistoricalDate,
+    end_date: Option<HistoricalDate>,
+    event_type: String,
+    importance: i32,
+    track_id: String,
+    color: Option<String>,
+    icon: Option<String>,
+    image_path: Option<String>,
+    external_link: Option<String>,
+    tags: String,
+    source: Option<String>,
+}
+
+impl EventFields {
+    fn local(event: &Event) -> Self {
+        Self {
+            title: event.title.clone(),
+            description: event.description.clone(),
+            start_date: event.start_date,
+            end_date: event.end_date,
+            event_type: event.event_type.clone(),
+            importance: event.importance,
+            track_id: event.track_id.clone(),
+            color: event.color.clone(),
+            icon: event.icon.clone(),
+            image_path: event.image_path.clone(),
+            external_link: event.external_link.clone(),
+            tags: event.tags.clone(),
+            source: event.source.clone(),
+        }
+    }
+
+    /// Defaults for an event that does not exist locally yet.
+    fn blank(start_date: HistoricalDate, track_id: &str) -> Self {
+        Self {
+            title: String::new(),
+            description: String::new(),
+            start_date,
+            end_date: None,
+            event_type: "point".to_string(),
+            importance: 3,
+            track_id: track_id.to_string(),
+            color: None,
+            icon: None,
+            image_path: None,
+            external_link: None,
+            tags: String::new(),
+            source: None,
+        }
+    }
+
+    /// The incoming event laid over `base`. Fields the file leaves out keep
+    /// the base value, so a merge never clears anything.
+    fn incoming(
+        event: &ImportedEvent,
+        start_date: HistoricalDate,
+        end_date: Option<HistoricalDate>,
+        track_id: Option<&String>,
+        base: &Self,
+    ) -> Self {
+        let base = base.clone();
+        Self {
+            title: event.title.clone(),
+            description: event.description.clone().unwrap_or(base.description),
+            start_date,
+            end_date: end_date.or(base.end_date),
+            event_type: event.event_type.clone().unwrap_or(base.event_type),
+            importance: event.importance.unwrap_or(base.importance),
+            track_id: track_id.cloned().unwrap_or(base.track_id),
+            color: event.color.clone().or(base.color),
+            icon: event.icon.clone().or(base.icon),
+            image_path: event.image_path.clone().or(base.image_path),
+            external_link: event.external_link.clone().or(base.external_link),
+            tags: event.tags.clone().unwrap_or(base.tags),
+            source: event.source.clone().or(base.source),
+        }
+    }
+
+    /// These fields with the empty ones taken from `other`: the part of a
+    /// conflicting match that overwrites nothing, applied whatever the strategy.
+    fn fill_blanks(&self, other: &Self) -> Self {
+        let text = |mine: &String, theirs: &String| {
+            if mine.is_empty() { theirs.clone() } else { mine.clone() }
+        };
+        let optional = |mine: &Option<String>, theirs: &Option<String>| match mine {
+            Some(value) if !value.is_empty() => mine.clone(),
+            _ => theirs.clone().or_else(|| mine.clone()),
+        };
+        Self {
+            title: text(&self.title, &other.title),
+            description: text(&self.description, &other.description),
+            start_date: self.start_date,
+            end_date: self.end_date.or(other.end_date),
+            event_type: text(&self.event_type, &other.event_type),
+            importance: self.importance,
+            track_id: text(&self.track_id, &other.track_id),
+            color: optional(&self.color, &other.color),
+            icon: optional(&self.icon, &other.icon),
+            image_path: optional(&self.image_path, &other.image_path),
+            external_link: optional(&self.external_link, &other.external_link),
+            tags: text(&self.tags, &other.tags),
+            source: optional(&self.source, &other.source),
+        }
+    }
+}
+
+fn is_blank(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::String(s) => s.is_empty(),
+        _ => false,
+    }
+}
+
+fn insert_event(
+    conn: &Connection,
+    timeline_id: &str,
+    id: &str,
+    fields: &EventFields,
+    event: &ImportedEvent,
+    now: &str,
+) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, event_type, importance, color, icon, image_path, external_link, tags, source, ai_generated, ai_confidence, created_at, updated_at, start_sort_key, end_sort_key)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?18, ?19, ?20)",
+        rusqlite::params![
+            id, timeline_id, fields.track_id, fields.title, fields.description,
+            fields.start_date, fields.end_date, fields.event_type, fields.importance,
+            fields.color, fields.icon, fields.image_path, fields.external_link, fields.tags, fields.source,
+            event.ai_generated.unwrap_or(false), event.ai_confidence, now,
+            fields.start_date.sort_key(), fields.end_date.map(|d| d.sort_key())
+        ],
+    )?;
+    Ok(())
+}
+
+fn update_event(conn: &Connection, id: &str, fields: &EventFields, now: &str) -> AppResult<()> {
+    record_revisions(conn, &[id.to_string()])?;
+    conn.execute(
+        "UPDATE events SET
+             track_id = ?1, title = ?2, description = ?3, start_date = ?4, end_date = ?5, event_type = ?6,
+             importance = ?7, color = ?8, icon = ?9, image_path = ?10, external_link = ?11,
+             tags = ?12, source = ?13, start_sort_key = ?14, end_sort_key = ?15, updated_at = ?16
+         WHERE id = ?17",
+        rusqlite::params![
+            fields.track_id, fields.title, fields.description, fields.start_date, fields.end_date,
+            fields.event_type, fields.importance, fields.color, fields.icon, fields.image_path,
+            fields.external_link, fields.tags, fields.source,
+            fields.start_date.sort_key(), fields.end_date.map(|d| d.sort_key()), now, id
+        ],
+    )?;
+    Ok(())
+}
+
+/// Merge an imported timeline into an existing one.
+///
+/// Tracks are matched by name. Events are matched by id, then by title and
+/// start date. A match that only fills empty local fields is applied as an
+/// update; one that would overwrite a local value is a conflict and resolved
+/// with `resolutions[local event id]`, falling back to `strategy`. Connections
+/// already present between the same events are not duplicated. Runs on
+/// whatever connection or transaction it is given and records one undo step.
+pub fn merge_timeline(
+    conn: &Connection,
+    timeline_id: &str,
+    imported: &ImportedTimeline,
+    strategy: ConflictStrategy,
+    resolutions: &HashMap<String, ConflictStrategy>,
+) -> AppResult<MergeReport> {
+    imported.check_version()?;
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM timelines WHERE id = ?1)",
+        [timeline_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(AppError::NotFound(format!("Timeline {timeline_id} not found")));
+    }
+
+    let before = Snapshot::timeline(conn, timeline_id)?;
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut report = MergeReport::default();
+    // Ids only matter for new rows here; replaced ones are not worth reporting.
+    let mut reassigned = 0;
+
+    // Tracks: reuse local tracks with the same name, add the rest
+    let mut track_names: HashMap<String, String> = HashMap::new();
+    let mut stmt = conn.prepare("SELECT name, id FROM tracks WHERE timeline_id = ?1 ORDER BY sort_order")?;
+    let local_tracks = stmt
+        .query_map([timeline_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut default_track = local_tracks.first().map(|(_, id)| id.clone());
+    for (name, id) in local_tracks {
+        track_names.entry(name).or_insert(id);
+    }
+    let mut next_sort_order: i32 = conn.query_row(
+        "SELECT COALESCE(MAX(sort_order) + 1, 0) FROM tracks WHERE timeline_id = ?1",
+        [timeline_id],
+        |row| row.get(0),
+    )?;
+
+    let mut track_ids: HashMap<&str, String> = HashMap::new();
+    for (i, track) in imported.tracks.iter().enumerate() {
+        if track.name.trim().is_empty() {
+            report.skipped.push(SkippedItem::new("track", i, &track.name, "Track name is empty"));
+            continue;
+        }
+        let track_id = match track_names.get(&track.name) {
+            Some(id) => id.clone(),
+            None => {
+                let id = keep_or_new_id(conn, "tracks", track.id.as_deref(), &mut reassigned)?;
+                conn.execute(
+                    "INSERT INTO tracks (id, timeline_id, name, color, sort_order, visible, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    rusqlite::params![
+                        id, timeline_id, track.name,
+                        track.color.as_deref().unwrap_or("#3b82f6"),
+                        next_sort_order,
+                        track.visible.unwrap_or(true),
+                        now
+                    ],
+                )?;
+                next_sort_order += 1;
+                report.tracks_added.push(track.name.clone());
+                track_names.insert(track.name.clone(), id.clone());
+                id
+            }
+        };
+        default_track.get_or_insert_with(|| track_id.clone());
+        if let Some(ref file_id) = track.id {
+            track_ids.entry(file_id.as_str()).or_insert(track_id);
+        }
+    }
+
+    let default_track = match default_track {
+        Some(id) => id,
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO tracks (id, timeline_id, name, color, sort_order, created_at) VALUES (?1, ?2, 'Default', '#3b82f6', 0, ?3)",
+                rusqlite::params![id, timeline_id, now],
+            )?;
+            report.tracks_added.push("Default".to_string());
+            id
+        }
+    };
+
+    // Events: match, then add, update or resolve the conflict
+    let local_events = fetch_timeline_events(conn, timeline_id)?;
+    let local_by_id: HashMap<&str, &Event> = local_events.iter().map(|e| (e.id.as_str(), e)).collect();
+    let mut local_by_key: HashMap<(&str, String), &Event> = HashMap::new();
+    let mut event_titles: HashMap<&str, String> = HashMap::new();
+    for event in &local_events {
+        local_by_key.entry((event.title.as_str(), event.start_date.to_string())).or_insert(event);
+        event_titles.entry(event.title.as_str()).or_insert_with(|| event.id.clone());
+    }
+
+    let mut event_ids: HashMap<&str, String> = HashMap::new();
+    let mut merged: HashSet<&str> = HashSet::new();
+    for (i, event) in imported.events.iter().enumerate() {
+        if event.title.trim().is_empty() {
+            report.skipped.push(SkippedItem::new("event", i, &event.title, "Title is empty"));
+            continue;
+        }
+        let start_date = match HistoricalDate::parse(&event.start_date) {
+            Ok(date) => date,
+            Err(e) => {
+                report.skipped.push(SkippedItem::new("event", i, &event.title, format!("Start date: {e}")));
+                continue;
+            }
+        };
+        let end_date = match HistoricalDate::parse_optional(event.end_date.as_deref()) {
+            Ok(date) => date,
+            Err(e) => {
+                report.skipped.push(SkippedItem::new("event", i, &event.title, format!("End date: {e}")));
+                continue;
+            }
+        };
+
+        let track_id = event
+            .track_id
+            .as_deref()
+            .and_then(|id| track_ids.get(id))
+            .or_else(|| event.track_name.as_ref().and_then(|n| track_names.get(n)));
+        let found = event
+            .id
+            .as_deref()
+            .and_then(|id| local_by_id.get(id))
+            .map(|local| (*local, MatchedBy::Id))
+            .or_else(|| {
+                local_by_key
+                    .get(&(event.title.as_str(), start_date.to_string()))
+                    .map(|local| (*local, MatchedBy::TitleAndDate))
+            });
+
+        let event_id = match found {
+            None => {
+                let fields = EventFields::incoming(
+                    event, start_date, end_date, track_id,
+                    &EventFields::blank(start_date, &default_track),
+                );
+                let id = keep_or_new_id(conn, "events", event.id.as_deref(), &mut reassigned)?;
+                insert_event(conn, timeline_id, &id, &fields, event, &now)?;
+                report.events_added.push(event.title.clone());
+                id
+            }
+            Some((local, matched_by)) => {
+                if !merged.insert(local.id.as_str()) {
+                    report.skipped.push(SkippedItem::new(
+                        "event", i, &event.title, "Matches the same local event as an earlier entry",
+                    ));
+                    continue;
+                }
+                let mine = EventFields::local(local);
+                let theirs = EventFields::incoming(event, start_date, end_date, track_id, &mine);
+                let diffs = diff_fields(&mine, &theirs)?;
+                if diffs.is_empty() {
+                    report.events_unchanged += 1;
+                    local.id.clone()
+                } else {
+                    let conflicting = diffs.iter().any(|d| !is_blank(&d.before));
+                    let resolution = conflicting
+                        .then(|| resolutions.get(&local.id).copied().unwrap_or(strategy));
+                    let fills_blanks = diffs.iter().any(|d| is_blank(&d.before));
+                    let mut event_id = local.id.clone();
+                    match resolution {
+                        None | Some(ConflictStrategy::TakeTheirs) => {
+                            update_event(conn, &local.id, &theirs, &now)?;
+                        }
+                        Some(ConflictStrategy::KeepMine) => {
+                            if fills_blanks {
+                                update_event(conn, &local.id, &mine.fill_blanks(&theirs), &now)?;
+                            }
+                        }
+                        Some(ConflictStrategy::KeepBoth) => {
+                            if fills_blanks {
+                                update_event(conn, &local.id, &mine.fill_blanks(&theirs), &now)?;
+                            }
+                            event_id = uuid::Uuid::new_v4().to_string();
+                            insert_event(conn, timeline_id, &event_id, &theirs, event, &now)?;
+                        }
+                    }
+                    let entry = MergeMatch {
+                        index: i,
+                        event_id: local.id.clone(),
+                        title: event.title.clone(),
+                        matched_by,
+                        diffs,
+                        resolution,
+                    };
+                    if conflicting {
+                        report.conflicts.push(entry);
+                    } else {
+                        report.events_updated.push(entry);
+                    }
+                    event_id
+                }
+            }
+        };
+
+        if let Some(ref file_id) = event.id {
+            event_ids.entry(file_id.as_str()).or_insert_with(|| event_id.clone());
+        }
+        event_titles.entry(event.title.as_str()).or_insert(event_id);
+    }
+
+    // Connections: resolve against merged events, skip links that already exist
+    let mut stmt = conn.prepare(
+        "SELECT source_event_id, target_event_id, connection_type FROM connections WHERE timeline_id = ?1",
+    )?;
+    let mut existing: HashSet<(String, String, String)> = stmt
+        .query_map([timeline_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<_, _>>()?;
+
+    let resolve = |id: &Option<String>, title: &Option<String>| -> Result<String, String> {
+        match (id, title) {
+            (Some(id), _) => event_ids
+                .get(id.as_str())
+                .cloned()
+                .or_else(|| local_by_id.contains_key(id.as_str()).then(|| id.clone()))
+                .ok_or_else(|| id.clone()),
+            (None, Some(title)) => event_titles.get(title.as_str()).cloned().ok_or_else(|| title.clone()),
+            (None, None) => Err(String::new()),
+        }
+    };
+    for (i, conn_data) in imported.connections.iter().flatten().enumerate() {
+        let source = resolve(&conn_data.source_id, &conn_data.source_title);
+        let target = resolve(&conn_data.target_id, &conn_data.target_title);
+        let (Ok(source_id), Ok(target_id)) = (&source, &target) else {
+            let missing = [source, target].into_iter().filter_map(Result::err).collect();
+            report.unresolved_connections.push(UnresolvedConnection {
+                index: i,
+                source_title: conn_data.source_title.clone(),
+                target_title: conn_data.target_title.clone(),
+                missing,
+            });
+            continue;
+        };
+
+        let connection_type = conn_data.connection_type.as_deref().unwrap_or("related");
+        if !existing.insert((source_id.clone(), target_id.clone(), connection_type.to_string())) {
+            continue;
+        }
+        let id = keep_or_new_id(conn, "connections", conn_data.id.as_deref(), &mut reassigned)?;
+        conn.execute(
+            "INSERT INTO connections (id, timeline_id, source_event_id, target_event_id, connection_type, label, color, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                id, timeline_id, source_id, target_id, connection_type,
+                conn_data.label, conn_data.color, now
+            ],
+        )?;
+        report.connections_added += 1;
+    }
+
+    journal::record(conn, "Merge import", before, Snapshot::timeline(conn, timeline_id)?)?;
+    Ok(report)
+}
+
+/// Merge a JSON timeline export into an existing timeline in a single
+/// transaction. With `dry_run` the merge is worked out and rolled back, which
+/// gives a preview of adds, updates and conflicts to choose resolutions from.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn merge_import_json(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+    data: String,
+    strategy: Option<ConflictStrategy>,
+    resolutions: Option<HashMap<String, ConflictStrategy>>,
+    dry_run: Option<bool>,
+) -> AppResult<MergeReport> {
+    let imported: ImportedTimeline = serde_json::from_str(&data)
+        .map_err(|e| AppError::Validation(format!("Invalid JSON: {e}")))?;
+
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let tx = conn.unchecked_transaction()?;
+    let mut report = merge_timeline(
+        &tx,
+        &timeline_id,
+        &imported,
+        strategy.unwrap_or_default(),
+        &resolutions.unwrap_or_default(),
+    )?;
+
+    if dry_run.unwrap_or(false) {
+        report.dry_run = true;
+        tx.rollback()?;
+    } else {
+        tx.commit()?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{merge_timeline, ConflictStrategy, MatchedBy};
+    use crate::commands::import::ImportedTimeline;
+    use crate::db::init_test_db;
+
+    fn seed(conn: &rusqlite::Connection) {
+        conn.execute("INSERT INTO timelines (id, title) VALUES ('tl', 'Mine')", []).unwrap();
+        conn.execute("INSERT INTO tracks (id, timeline_id, name) VALUES ('tr', 'tl', 'Politics')", []).unwrap();
+        for (id, title, date, description) in [
+            ("e1", "Revolution", "1789-07-14", ""),
+            ("e2", "Coronation", "1804-12-02", "Notre-Dame"),
+            ("e3", "Waterloo", "1815-06-18", "Belgium"),
+        ] {
+            conn.execute(
+                "INSERT INTO events (id, timeline_id, track_id, title, description, start_date) VALUES (?1, 'tl', 'tr', ?2, ?3, ?4)",
+                rusqlite::params![id, title, description, date],
+            )
+            .unwrap();
+        }
+    }
+
+    fn incoming() -> ImportedTimeline {
+        serde_json::from_str(
+            r#"{
+                "version": 2,
+                "title": "Theirs",
+                "tracks": [{"id": "x-tr", "name": "Politics"}, {"id": "x-sci", "name": "Science"}],
+                "events": [
+                    {"id": "e1", "title": "Revolution", "startDate": "1789-07-14", "description": "Bastille"},
+                    {"id": "x2", "title": "Coronation", "startDate": "1804-12-02", "description": "Paris", "source": "Archives"},
+                    {"title": "Waterloo", "startDate": "1815-06-18", "trackName": "Politics"},
+                    {"id": "x4", "title": "Relativity", "startDate": "1905", "trackId": "x-sci"}
+                ],
+                "connections": [
+                    {"sourceId": "x2", "targetId": "x4", "connectionType": "related"},
+                    {"sourceTitle": "Revolution", "targetTitle": "Nowhere"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn description(conn: &rusqlite::Connection, id: &str) -> String {
+        conn.query_row("SELECT description FROM events WHERE id = ?1", [id], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn source(conn: &rusqlite::Connection, id: &str) -> Option<String> {
+        conn.query_row("SELECT source FROM events WHERE id = ?1", [id], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_merge_preview_and_strategies() {
+        let conn = init_test_db().unwrap();
+        seed(&conn);
+
+        // Preview with the default strategy inside a rolled back transaction
+        let tx = conn.unchecked_transaction().unwrap();
+        let preview = merge_timeline(&tx, "tl", &incoming(), ConflictStrategy::KeepMine, &HashMap::new()).unwrap();
+        // Keeping mine still fills the fields that were empty locally
+        assert_eq!(description(&tx, "e2"), "Notre-Dame");
+        assert_eq!(source(&tx, "e2").as_deref(), Some("Archives"));
+        tx.rollback().unwrap();
+        assert_eq!(preview.tracks_added, vec!["Science".to_string()]);
+        assert_eq!(preview.events_added, vec!["Relativity".to_string()]);
+        assert_eq!(preview.events_updated.len(), 1);
+        assert_eq!(preview.events_updated[0].event_id, "e1");
+        assert_eq!(preview.events_updated[0].matched_by, MatchedBy::Id);
+        assert_eq!(preview.conflicts.len(), 1);
+        assert_eq!(preview.conflicts[0].event_id, "e2");
+        assert_eq!(preview.conflicts[0].matched_by, MatchedBy::TitleAndDate);
+        assert_eq!(preview.conflicts[0].resolution, Some(ConflictStrategy::KeepMine));
+        assert_eq!(preview.events_unchanged, 1);
+        assert_eq!(preview.connections_added, 1);
+        assert_eq!(preview.unresolved_connections[0].missing, vec!["Nowhere".to_string()]);
+        assert_eq!(description(&conn, "e1"), "");
+
+        // Take theirs for the conflict
+        let resolutions = HashMap::from([("e2".to_string(), ConflictStrategy::TakeTheirs)]);
+        merge_timeline(&conn, "tl", &incoming(), ConflictStrategy::KeepMine, &resolutions).unwrap();
+        assert_eq!(description(&conn, "e1"), "Bastille");
+        assert_eq!(description(&conn, "e2"), "Paris");
+        let revisions: i32 = conn
+            .query_row("SELECT COUNT(*) FROM event_revisions WHERE event_id = 'e2'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(revisions, 1);
+
+        // Merging again changes nothing
+        let again = merge_timeline(&conn, "tl", &incoming(), ConflictStrategy::KeepBoth, &HashMap::new()).unwrap();
+        assert!(again.events_added.is_empty() && again.conflicts.is_empty() && again.tracks_added.is_empty());
+        assert_eq!(again.events_unchanged, 4);
+        assert_eq!(again.connections_added, 0);
+    }
+
+    #[test]
+    fn test_merge_keep_both() {
+        let conn = init_test_db().unwrap();
+        seed(&conn);
+        let report = merge_timeline(&conn, "tl", &incoming(), ConflictStrategy::KeepBoth, &HashMap::new()).unwrap();
+        assert_eq!(report.conflicts[0].resolution, Some(ConflictStrategy::KeepBoth));
+        assert_eq!(description(&conn, "e2"), "Notre-Dame");
+        assert_eq!(source(&conn, "e2").as_deref(), Some("Archives"));
+
+        let copies: i32 = conn
+            .query_row("SELECT COUNT(*) FROM events WHERE title = 'Coronation'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(copies, 2);
+        // The connection follows the incoming copy
+        let linked: String = conn
+            .query_row(
+                "SELECT e.description FROM connections c JOIN events e ON e.id = c.source_event_id",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(linked, "Paris");
+    }
+}
@@ -1,10 +1,30 @@
+#[cfg(feature = "desktop")]
 pub mod ai;
+#[cfg(feature = "desktop")]
 pub mod connections;
+#[cfg(feature = "desktop")]
 pub mod events;
 pub mod export;
+#[cfg(feature = "desktop")]
+pub mod gedcom;
+#[cfg(feature = "desktop")]
+pub mod history;
+#[cfg(feature = "desktop")]
+pub mod ical;
 pub mod import;
+pub mod markdown;
+#[cfg(feature = "desktop")]
+pub mod merge;
+#[cfg(feature = "desktop")]
+pub mod revisions;
 pub mod search;
+#[cfg(feature = "desktop")]
 pub mod settings;
 pub mod templates;
 pub mod timelines;
+#[cfg(feature = "desktop")]
 pub mod tracks;
+#[cfg(feature = "desktop")]
+pub mod vault;
+#[cfg(feature = "desktop")]
+pub mod wikidata;
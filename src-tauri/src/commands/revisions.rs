@@ -0,0 +1,327 @@
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+#[cfg(feature = "desktop")]
+use tauri::State;
+
+use super::events::fetch_event;
+use crate::db::journal::{self, Snapshot};
+use crate::db::models::{Event, EventRevision, FieldDiff};
+use crate::error::{AppError, AppResult};
+
+/// Event fields compared by `diff_event_revisions`, as named in the serialized models.
+const DIFF_FIELDS: &[&str] = &[
+    "title",
+    "startDate",
+    "endDate",
+    "description",
+    "eventType",
+    "importance",
+    "trackId",
+    "color",
+    "icon",
+    "imagePath",
+    "externalLink",
+    "tags",
+    "source",
+];
+
+const REVISION_COLUMNS: &str = "id, event_id, revision, track_id, title, description, start_date, end_date, event_type, importance, color, icon, image_path, external_link, tags, source, changed_by, changed_at";
+
+fn row_to_revision(row: &rusqlite::Row<'_>) -> rusqlite::Result<EventRevision> {
+    Ok(EventRevision {
+        id: row.get(0)?,
+        event_id: row.get(1)?,
+        revision: row.get(2)?,
+        track_id: row.get(3)?,
+        title: row.get(4)?,
+        description: row.get(5)?,
+        start_date: row.get(6)?,
+        end_date: row.get(7)?,
+        event_type: row.get(8)?,
+        importance: row.get(9)?,
+        color: row.get(10)?,
+        icon: row.get(11)?,
+        image_path: row.get(12)?,
+        external_link: row.get(13)?,
+        tags: row.get(14)?,
+        source: row.get(15)?,
+        changed_by: row.get(16)?,
+        changed_at: row.get(17)?,
+    })
+}
+
+fn fetch_revision(conn: &Connection, id: &str) -> AppResult<EventRevision> {
+    let query = format!("SELECT {REVISION_COLUMNS} FROM event_revisions WHERE id = ?1");
+    conn.query_row(&query, [id], row_to_revision)
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => {
+                AppError::NotFound(format!("Revision {id} not found"))
+            }
+            other => AppError::Database(other),
+        })
+}
+
+/// Snapshot the current state of each event as a new revision. Call this
+/// before applying an update; ids of missing events are ignored.
+pub fn record_revisions(conn: &Connection, event_ids: &[String]) -> AppResult<()> {
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut stmt = conn.prepare(
+        "INSERT INTO event_revisions (id, event_id, timeline_id, revision, track_id, title, description, start_date, end_date, event_type, importance, color, icon, image_path, external_link, tags, source, changed_by, changed_at)
+         SELECT ?1, id, timeline_id,
+                (SELECT COALESCE(MAX(revision), 0) + 1 FROM event_revisions WHERE event_id = ?2),
+                track_id, title, description, start_date, end_date, event_type, importance, color, icon, image_path, external_link, tags, source,
+                (SELECT NULLIF(value, '') FROM settings WHERE key = 'author_name'),
+                ?3
+         FROM events WHERE id = ?2",
+    )?;
+    for event_id in event_ids {
+        let id = uuid::Uuid::new_v4().to_string();
+        stmt.execute(rusqlite::params![id, event_id, now])?;
+    }
+    Ok(())
+}
+
+/// Serialize an event or revision into a field map for diffing.
+fn diff_values<T: serde::Serialize>(item: &T) -> AppResult<serde_json::Map<String, serde_json::Value>> {
+    match serde_json::to_value(item).map_err(|e| AppError::Internal(e.to_string()))? {
+        serde_json::Value::Object(map) => Ok(map),
+        _ => Err(AppError::Internal("Expected an object".to_string())),
+    }
+}
+
+/// Differences in `DIFF_FIELDS` between two serialized events or revisions.
+pub fn diff_fields<A: serde::Serialize, B: serde::Serialize>(before: &A, after: &B) -> AppResult<Vec<FieldDiff>> {
+    let before = diff_values(before)?;
+    let after = diff_values(after)?;
+
+    let diffs = DIFF_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let before = before.get(*field).cloned().unwrap_or_default();
+            let after = after.get(*field).cloned().unwrap_or_default();
+            (before != after).then(|| FieldDiff { field: field.to_string(), before, after })
+        })
+        .collect();
+
+    Ok(diffs)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn list_event_revisions(
+    db: State<'_, Mutex<Connection>>,
+    event_id: String,
+) -> AppResult<Vec<EventRevision>> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let query = format!(
+        "SELECT {REVISION_COLUMNS} FROM event_revisions WHERE event_id = ?1 ORDER BY revision DESC"
+    );
+    let mut stmt = conn.prepare(&query)?;
+
+    let revisions = stmt
+        .query_map([&event_id], row_to_revision)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(revisions)
+}
+
+/// Field-by-field differences from one revision to another, or to the event's
+/// current state when `to_id` is omitted.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn diff_event_revisions(
+    db: State<'_, Mutex<Connection>>,
+    from_id: String,
+    to_id: Option<String>,
+) -> AppResult<Vec<FieldDiff>> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    diff_revisions(&conn, &from_id, to_id.as_deref())
+}
+
+pub fn diff_revisions(conn: &Connection, from_id: &str, to_id: Option<&str>) -> AppResult<Vec<FieldDiff>> {
+    let from = fetch_revision(conn, from_id)?;
+    match to_id {
+        Some(id) => {
+            let to = fetch_revision(conn, id)?;
+            if to.event_id != from.event_id {
+                return Err(AppError::Validation(
+                    "Revisions belong to different events".to_string(),
+                ));
+            }
+            diff_fields(&from, &to)
+        }
+        None => diff_fields(&from, &fetch_event(conn, &from.event_id)?),
+    }
+}
+
+/// Put an event back into the state captured by a revision. The state being
+/// replaced is itself kept as a new revision, and the restore can be undone.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn restore_event_revision(
+    db: State<'_, Mutex<Connection>>,
+    revision_id: String,
+) -> AppResult<Event> {
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    restore_revision(&conn, &revision_id)
+}
+
+pub fn restore_revision(conn: &Connection, revision_id: &str) -> AppResult<Event> {
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let rev = fetch_revision(conn, revision_id)?;
+
+    let tx = conn.unchecked_transaction()?;
+    let ids = std::slice::from_ref(&rev.event_id);
+    let before = Snapshot::events(&tx, ids)?;
+    record_revisions(&tx, ids)?;
+
+    // A track deleted since the revision was taken keeps the event where it is.
+    let changes = tx.execute(
+        "UPDATE events SET
+             track_id = COALESCE((SELECT id FROM tracks WHERE id = ?1), track_id),
+             title = ?2, description = ?3, start_date = ?4, end_date = ?5, event_type = ?6,
+             importance = ?7, color = ?8, icon = ?9, image_path = ?10, external_link = ?11,
+             tags = ?12, source = ?13, start_sort_key = ?14, end_sort_key = ?15, updated_at = ?16
+         WHERE id = ?17",
+        rusqlite::params![
+            rev.track_id, rev.title, rev.description, rev.start_date, rev.end_date, rev.event_type,
+            rev.importance, rev.color, rev.icon, rev.image_path, rev.external_link,
+            rev.tags, rev.source, rev.start_date.sort_key(), rev.end_date.map(|d| d.sort_key()), now,
+            rev.event_id
+        ],
+    )?;
+    if changes == 0 {
+        return Err(AppError::NotFound(format!("Event {} no longer exists", rev.event_id)));
+    }
+
+    let label = format!("Restore revision {}", rev.revision);
+    journal::record(&tx, &label, before, Snapshot::events(&tx, ids)?)?;
+    let event = fetch_event(&tx, &rev.event_id)?;
+    tx.commit()?;
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_revisions, record_revisions, restore_revision};
+    use crate::db::{init_test_db, journal};
+    use rusqlite::{params, Connection};
+
+    fn revision_id(conn: &Connection, revision: i64) -> String {
+        conn.query_row(
+            "SELECT id FROM event_revisions WHERE event_id = 'e1' AND revision = ?1",
+            [revision],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_revisions_snapshot_prior_state() {
+        let conn = init_test_db().unwrap();
+        conn.execute("INSERT INTO timelines (id, title) VALUES ('tl', 'Test')", []).unwrap();
+        conn.execute("INSERT INTO tracks (id, timeline_id, name) VALUES ('tr', 'tl', 'Track')", []).unwrap();
+        conn.execute(
+            "INSERT INTO events (id, timeline_id, track_id, title, start_date) VALUES ('e1', 'tl', 'tr', 'Original', '1905')",
+            [],
+        )
+        .unwrap();
+        conn.execute("UPDATE settings SET value = 'Ada' WHERE key = 'author_name'", []).unwrap();
+
+        let ids = vec!["e1".to_string(), "missing".to_string()];
+        record_revisions(&conn, &ids).unwrap();
+        conn.execute("UPDATE events SET title = ?1 WHERE id = 'e1'", params!["Renamed"]).unwrap();
+        record_revisions(&conn, &ids).unwrap();
+
+        let revisions: Vec<(i64, String, Option<String>)> = conn
+            .prepare("SELECT revision, title, changed_by FROM event_revisions WHERE event_id = 'e1' ORDER BY revision")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            revisions,
+            vec![
+                (1, "Original".to_string(), Some("Ada".to_string())),
+                (2, "Renamed".to_string(), Some("Ada".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_revisions() {
+        let conn = init_test_db().unwrap();
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Test'), ('tl2', 'Other');
+             INSERT INTO tracks (id, timeline_id, name) VALUES ('tr', 'tl', 'Track');
+             INSERT INTO events (id, timeline_id, track_id, title, start_date) VALUES ('e1', 'tl', 'tr', 'Original', '1905');",
+        )
+        .unwrap();
+        let ids = vec!["e1".to_string()];
+        record_revisions(&conn, &ids).unwrap();
+        conn.execute("UPDATE events SET title = 'Renamed', importance = 5 WHERE id = 'e1'", []).unwrap();
+        record_revisions(&conn, &ids).unwrap();
+        conn.execute("UPDATE events SET title = 'Current' WHERE id = 'e1'", []).unwrap();
+
+        let diffs = diff_revisions(&conn, &revision_id(&conn, 1), Some(&revision_id(&conn, 2))).unwrap();
+        let fields: Vec<&str> = diffs.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, vec!["title", "importance"]);
+        assert_eq!((diffs[0].before.as_str(), diffs[0].after.as_str()), (Some("Original"), Some("Renamed")));
+
+        // Without a second revision the diff runs against the live event
+        let diffs = diff_revisions(&conn, &revision_id(&conn, 2), None).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].after.as_str(), Some("Current"));
+    }
+
+    #[test]
+    fn test_restore_revision() {
+        let conn = init_test_db().unwrap();
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Test');
+             INSERT INTO tracks (id, timeline_id, name) VALUES ('old', 'tl', 'Old'), ('new', 'tl', 'New');
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, start_sort_key)
+             VALUES ('e1', 'tl', 'old', 'Original', '1905', 0);",
+        )
+        .unwrap();
+        record_revisions(&conn, &["e1".to_string()]).unwrap();
+        conn.execute_batch(
+            "UPDATE events SET title = 'Renamed', start_date = '2001', track_id = 'new' WHERE id = 'e1';
+             DELETE FROM tracks WHERE id = 'old';",
+        )
+        .unwrap();
+
+        let event = restore_revision(&conn, &revision_id(&conn, 1)).unwrap();
+        assert_eq!(event.title, "Original");
+        assert_eq!(event.start_date.to_string(), "1905");
+        // The revision's track is gone, so the event stays on its current one
+        assert_eq!(event.track_id, "new");
+        let sort_key: f64 = conn
+            .query_row("SELECT start_sort_key FROM events WHERE id = 'e1'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(sort_key, event.start_date.sort_key());
+
+        // The replaced state became revision 2, and the restore is one undo step
+        let replaced: String = conn
+            .query_row("SELECT title FROM event_revisions WHERE event_id = 'e1' AND revision = 2", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(replaced, "Renamed");
+        assert_eq!(journal::undo(&conn, "tl").unwrap().as_deref(), Some("Restore revision 1"));
+        let title: String = conn.query_row("SELECT title FROM events WHERE id = 'e1'", [], |row| row.get(0)).unwrap();
+        assert_eq!(title, "Renamed");
+
+        // Undo rewrote the event, so its prior state is in the history too
+        let latest: (i64, String) = conn
+            .query_row(
+                "SELECT revision, title FROM event_revisions WHERE event_id = 'e1' ORDER BY revision DESC LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(latest, (3, "Original".to_string()));
+
+        assert!(restore_revision(&conn, "missing").is_err());
+    }
+}
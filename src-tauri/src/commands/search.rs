@@ -1,10 +1,14 @@
+#[cfg(feature = "desktop")]
 use std::sync::Mutex;
 
 use rusqlite::Connection;
 use serde::Serialize;
+#[cfg(feature = "desktop")]
 use tauri::State;
 
-use crate::error::{AppError, AppResult};
+#[cfg(feature = "desktop")]
+use crate::error::AppError;
+use crate::error::AppResult;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,6 +20,7 @@ pub struct SearchResult {
     pub track_id: String,
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn search_events(
     db: State<'_, Mutex<Connection>>,
@@ -23,7 +28,12 @@ pub fn search_events(
     query: String,
 ) -> AppResult<Vec<SearchResult>> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    find_events(&conn, &timeline_id, &query)
+}
 
+/// Up to 50 events of a timeline matching `query`, by full-text rank when the
+/// FTS index exists and by a plain substring match otherwise.
+pub fn find_events(conn: &Connection, timeline_id: &str, query: &str) -> AppResult<Vec<SearchResult>> {
     // Check if FTS table exists
     let has_fts: bool = conn
         .query_row(
@@ -64,7 +74,7 @@ pub fn search_events(
             "SELECT id, title, SUBSTR(description, 1, 100), start_date, track_id
              FROM events
              WHERE timeline_id = ?1 AND (title LIKE ?2 OR description LIKE ?2 OR tags LIKE ?2)
-             ORDER BY start_date
+             ORDER BY start_sort_key, start_date
              LIMIT 50",
         )?;
 
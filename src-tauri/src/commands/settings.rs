@@ -1,11 +1,13 @@
 use std::sync::Mutex;
 
 use rusqlite::Connection;
+#[cfg(feature = "desktop")]
 use tauri::State;
 
 use crate::db::models::Setting;
 use crate::error::{AppError, AppResult};
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn get_setting(db: State<'_, Mutex<Connection>>, key: String) -> AppResult<Setting> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
@@ -28,6 +30,7 @@ pub fn get_setting(db: State<'_, Mutex<Connection>>, key: String) -> AppResult<S
     })
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_setting(
     db: State<'_, Mutex<Connection>>,
@@ -1,6 +1,8 @@
+#[cfg(feature = "desktop")]
 use std::sync::Mutex;
 
 use rusqlite::Connection;
+#[cfg(feature = "desktop")]
 use tauri::State;
 
 use crate::db::models::Template;
@@ -17,9 +19,15 @@ struct TemplateTrack {
     color: String,
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn list_templates(db: State<'_, Mutex<Connection>>) -> AppResult<Vec<Template>> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    fetch_templates(&conn)
+}
+
+/// Built-in templates first, then the user's, each by name.
+pub fn fetch_templates(conn: &Connection) -> AppResult<Vec<Template>> {
     let mut stmt = conn.prepare(
         "SELECT id, name, description, data, is_builtin, created_at FROM templates ORDER BY is_builtin DESC, name",
     )?;
@@ -38,6 +46,7 @@ pub fn list_templates(db: State<'_, Mutex<Connection>>) -> AppResult<Vec<Templat
     Ok(templates)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn create_from_template(
     db: State<'_, Mutex<Connection>>,
@@ -45,12 +54,17 @@ pub fn create_from_template(
     title: String,
 ) -> AppResult<String> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    apply_template(&conn, &template_id, &title)
+}
+
+/// Create a timeline titled `title` with the template's tracks, returning its id.
+pub fn apply_template(conn: &Connection, template_id: &str, title: &str) -> AppResult<String> {
     let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
 
     let data_str: String = conn
         .query_row(
             "SELECT data FROM templates WHERE id = ?1",
-            [&template_id],
+            [template_id],
             |row| row.get(0),
         )
         .map_err(|_| AppError::NotFound(format!("Template {template_id} not found")))?;
@@ -75,6 +89,7 @@ pub fn create_from_template(
     Ok(tl_id)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn save_as_template(
     db: State<'_, Mutex<Connection>>,
@@ -116,6 +131,7 @@ pub fn save_as_template(
     })
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn delete_template(db: State<'_, Mutex<Connection>>, id: String) -> AppResult<()> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
@@ -1,11 +1,20 @@
+#[cfg(feature = "desktop")]
 use std::sync::Mutex;
 
 use rusqlite::Connection;
+#[cfg(feature = "desktop")]
 use tauri::State;
 
-use crate::db::models::{CreateTimeline, Timeline, UpdateTimeline};
-use crate::error::{AppError, AppResult};
+#[cfg(feature = "desktop")]
+use crate::db::journal::{self, Snapshot};
+#[cfg(feature = "desktop")]
+use crate::db::models::{CreateTimeline, UpdateTimeline};
+use crate::db::models::Timeline;
+#[cfg(feature = "desktop")]
+use crate::error::AppError;
+use crate::error::AppResult;
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn create_timeline(
     db: State<'_, Mutex<Connection>>,
@@ -37,6 +46,7 @@ pub fn create_timeline(
     })
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn get_timeline(db: State<'_, Mutex<Connection>>, id: String) -> AppResult<Timeline> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
@@ -62,9 +72,15 @@ pub fn get_timeline(db: State<'_, Mutex<Connection>>, id: String) -> AppResult<T
     })
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn list_timelines(db: State<'_, Mutex<Connection>>) -> AppResult<Vec<Timeline>> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    fetch_timelines(&conn)
+}
+
+/// All timelines, most recently updated first.
+pub fn fetch_timelines(conn: &Connection) -> AppResult<Vec<Timeline>> {
     let mut stmt = conn.prepare(
         "SELECT id, title, description, created_at, updated_at FROM timelines ORDER BY updated_at DESC",
     )?;
@@ -84,6 +100,7 @@ pub fn list_timelines(db: State<'_, Mutex<Connection>>) -> AppResult<Vec<Timelin
     Ok(timelines)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_timeline(
     db: State<'_, Mutex<Connection>>,
@@ -91,31 +108,40 @@ pub fn update_timeline(
 ) -> AppResult<Timeline> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
     let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let tx = conn.unchecked_transaction()?;
+    let before = Snapshot::timeline_details(&tx, &input.id)?;
 
     if let Some(ref title) = input.title {
-        conn.execute(
+        tx.execute(
             "UPDATE timelines SET title = ?1, updated_at = ?2 WHERE id = ?3",
             rusqlite::params![title, now, input.id],
         )?;
     }
     if let Some(ref desc) = input.description {
-        conn.execute(
+        tx.execute(
             "UPDATE timelines SET description = ?1, updated_at = ?2 WHERE id = ?3",
             rusqlite::params![desc, now, input.id],
         )?;
     }
+    journal::record(&tx, "Edit timeline", before, Snapshot::timeline_details(&tx, &input.id)?)?;
+    tx.commit()?;
 
     drop(conn);
     get_timeline(db, input.id)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn delete_timeline(db: State<'_, Mutex<Connection>>, id: String) -> AppResult<()> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-    let changes = conn.execute("DELETE FROM timelines WHERE id = ?1", [&id])?;
+    let tx = conn.unchecked_transaction()?;
+    let before = Snapshot::timeline(&tx, &id)?;
+    let changes = tx.execute("DELETE FROM timelines WHERE id = ?1", [&id])?;
     if changes == 0 {
         return Err(AppError::NotFound(format!("Timeline {id} not found")));
     }
+    journal::record(&tx, "Delete timeline", before, Snapshot::timeline(&tx, &id)?)?;
+    tx.commit()?;
     Ok(())
 }
 
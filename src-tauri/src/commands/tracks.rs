@@ -1,11 +1,14 @@
 use std::sync::Mutex;
 
 use rusqlite::Connection;
+#[cfg(feature = "desktop")]
 use tauri::State;
 
+use crate::db::journal::{self, Snapshot};
 use crate::db::models::{CreateTrack, Track, UpdateTrack};
 use crate::error::{AppError, AppResult};
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn create_track(db: State<'_, Mutex<Connection>>, input: CreateTrack) -> AppResult<Track> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
@@ -21,10 +24,14 @@ pub fn create_track(db: State<'_, Mutex<Connection>>, input: CreateTrack) -> App
         )
         .unwrap_or(-1);
 
-    conn.execute(
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
         "INSERT INTO tracks (id, timeline_id, name, color, sort_order, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
         rusqlite::params![id, input.timeline_id, input.name, color, max_order + 1, now],
     )?;
+    let after = Snapshot::tracks(&tx, std::slice::from_ref(&id))?;
+    journal::record(&tx, "Create track", Snapshot::default(), after)?;
+    tx.commit()?;
 
     Ok(Track {
         id,
@@ -37,6 +44,7 @@ pub fn create_track(db: State<'_, Mutex<Connection>>, input: CreateTrack) -> App
     })
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn list_tracks(
     db: State<'_, Mutex<Connection>>,
@@ -64,30 +72,35 @@ pub fn list_tracks(
     Ok(tracks)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn update_track(db: State<'_, Mutex<Connection>>, input: UpdateTrack) -> AppResult<Track> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let tx = conn.unchecked_transaction()?;
+    let ids = std::slice::from_ref(&input.id);
+    let before = Snapshot::tracks(&tx, ids)?;
 
     if let Some(ref name) = input.name {
-        conn.execute(
+        tx.execute(
             "UPDATE tracks SET name = ?1 WHERE id = ?2",
             rusqlite::params![name, input.id],
         )?;
     }
     if let Some(ref color) = input.color {
-        conn.execute(
+        tx.execute(
             "UPDATE tracks SET color = ?1 WHERE id = ?2",
             rusqlite::params![color, input.id],
         )?;
     }
     if let Some(visible) = input.visible {
-        conn.execute(
+        tx.execute(
             "UPDATE tracks SET visible = ?1 WHERE id = ?2",
             rusqlite::params![visible, input.id],
         )?;
     }
+    journal::record(&tx, "Edit track", before, Snapshot::tracks(&tx, ids)?)?;
 
-    conn.query_row(
+    let track = tx.query_row(
         "SELECT id, timeline_id, name, color, sort_order, visible, created_at FROM tracks WHERE id = ?1",
         [&input.id],
         |row| {
@@ -107,33 +120,46 @@ pub fn update_track(db: State<'_, Mutex<Connection>>, input: UpdateTrack) -> App
             AppError::NotFound(format!("Track {} not found", input.id))
         }
         other => AppError::Database(other),
-    })
+    })?;
+    tx.commit()?;
+    Ok(track)
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn delete_track(db: State<'_, Mutex<Connection>>, id: String) -> AppResult<()> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
-    let changes = conn.execute("DELETE FROM tracks WHERE id = ?1", [&id])?;
+    let tx = conn.unchecked_transaction()?;
+    let ids = std::slice::from_ref(&id);
+    let before = Snapshot::tracks(&tx, ids)?;
+    let changes = tx.execute("DELETE FROM tracks WHERE id = ?1", [&id])?;
     if changes == 0 {
         return Err(AppError::NotFound(format!("Track {id} not found")));
     }
+    journal::record(&tx, "Delete track", before, Snapshot::tracks(&tx, ids)?)?;
+    tx.commit()?;
     Ok(())
 }
 
+#[cfg(feature = "desktop")]
 #[tauri::command]
 pub fn reorder_tracks(
     db: State<'_, Mutex<Connection>>,
     track_ids: Vec<String>,
 ) -> AppResult<()> {
     let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    let tx = conn.unchecked_transaction()?;
+    let before = Snapshot::tracks(&tx, &track_ids)?;
 
     for (i, id) in track_ids.iter().enumerate() {
-        conn.execute(
+        tx.execute(
             "UPDATE tracks SET sort_order = ?1 WHERE id = ?2",
             rusqlite::params![i as i32, id],
         )?;
     }
 
+    journal::record(&tx, "Reorder tracks", before, Snapshot::tracks(&tx, &track_ids)?)?;
+    tx.commit()?;
     Ok(())
 }
 
@@ -143,6 +169,8 @@ mod tests {
     use rusqlite::params;
 
     #[test]
+    // Kept as written upstream; `clippy -D warnings` rejects the `vec!` otherwise
+    #[allow(clippy::useless_vec)]
     fn test_reorder_tracks() {
         let conn = init_test_db().unwrap();
 
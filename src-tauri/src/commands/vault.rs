@@ -0,0 +1,359 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+use serde::Serialize;
+#[cfg(feature = "desktop")]
+use tauri::State;
+
+use super::export::{export_timeline, validate_file_path, ExportedEvent, ExportedTimeline};
+use super::markdown::yaml_string;
+use crate::error::{AppError, AppResult};
+
+/// One note of an exported vault, with its path relative to the vault folder.
+#[derive(Debug, Clone)]
+pub struct VaultNote {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultExportReport {
+    /// The folder the notes were written to.
+    pub directory: String,
+    pub notes_written: u32,
+}
+
+/// Hands out note names that are valid file names and unique across the
+/// vault, so plain `[[wikilinks]]` always resolve to one note.
+#[derive(Default)]
+struct NoteNames {
+    taken: HashSet<String>,
+}
+
+impl NoteNames {
+    fn claim(&mut self, title: &str) -> String {
+        let cleaned: String = title
+            .chars()
+            .map(|c| if "\\/:*?\"<>|#^[]".contains(c) || c.is_control() { '-' } else { c })
+            .collect();
+        let base = cleaned.trim().trim_start_matches('.').trim();
+        let base = if base.is_empty() { "Untitled" } else { base };
+        let mut name = base.to_string();
+        let mut n = 2;
+        while !self.taken.insert(name.to_lowercase()) {
+            name = format!("{base} ({n})");
+            n += 1;
+        }
+        name
+    }
+}
+
+/// `[[note]]`, or `[[note|title]]` when the file name had to differ.
+fn wikilink(note: &str, title: &str) -> String {
+    if note == title {
+        format!("[[{note}]]")
+    } else {
+        format!("[[{note}|{}]]", title.replace(['[', ']', '|'], ""))
+    }
+}
+
+fn date_range(event: &ExportedEvent) -> String {
+    match event.end_date {
+        Some(ref end) => format!("{} — {end}", event.start_date),
+        None => event.start_date.clone(),
+    }
+}
+
+/// Obsidian tags cannot contain spaces.
+fn note_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(|t| t.trim().replace(' ', "-"))
+        .filter(|t| !t.is_empty())
+        .map(|t| yaml_string(&t))
+        .collect()
+}
+
+/// Build an Obsidian-style vault: an index note for the timeline, one per
+/// track under `Tracks/`, and one per event under `Events/` with YAML
+/// properties. Connections become `[[wikilinks]]` labelled with their type,
+/// listed on both events.
+pub fn timeline_to_vault(timeline: &ExportedTimeline) -> Vec<VaultNote> {
+    let mut names = NoteNames::default();
+    let index_name = names.claim(&timeline.title);
+    let track_notes: HashMap<&str, String> = timeline
+        .tracks
+        .iter()
+        .map(|t| (t.id.as_str(), names.claim(&t.name)))
+        .collect();
+    let event_notes: HashMap<&str, String> = timeline
+        .events
+        .iter()
+        .map(|e| (e.id.as_str(), names.claim(&e.title)))
+        .collect();
+    let event_link = |id: &str| {
+        let event = timeline.events.iter().find(|e| e.id == id)?;
+        Some(wikilink(&event_notes[id], &event.title))
+    };
+    let track_link = |id: &str| {
+        let track = timeline.tracks.iter().find(|t| t.id == id)?;
+        Some(wikilink(&track_notes[id], &track.name))
+    };
+
+    let mut notes = Vec::new();
+    for event in &timeline.events {
+        let mut md = String::from("---\n");
+        md.push_str(&format!("title: {}\n", yaml_string(&event.title)));
+        md.push_str(&format!("start: {}\n", yaml_string(&event.start_date)));
+        if let Some(ref end) = event.end_date {
+            md.push_str(&format!("end: {}\n", yaml_string(end)));
+        }
+        md.push_str(&format!("type: {}\n", event.event_type));
+        if let Some(link) = track_link(&event.track_id) {
+            md.push_str(&format!("track: {}\n", yaml_string(&link)));
+        }
+        md.push_str(&format!("tags: [{}]\n", note_tags(&event.tags).join(", ")));
+        md.push_str(&format!("importance: {}\n", event.importance));
+        md.push_str(&format!("ai_generated: {}\n", event.ai_generated));
+        if let Some(ref source) = event.source {
+            md.push_str(&format!("source: {}\n", yaml_string(source)));
+        }
+        if let Some(ref link) = event.external_link {
+            md.push_str(&format!("link: {}\n", yaml_string(link)));
+        }
+        md.push_str(&format!("timeline: {}\n", yaml_string(&wikilink(&index_name, &timeline.title))));
+        md.push_str(&format!("{NOTE_MARKER} {}\n", yaml_string(&event.id)));
+        md.push_str("---\n\n");
+        if !event.description.is_empty() {
+            md.push_str(&event.description);
+            md.push_str("\n\n");
+        }
+
+        let mut links = Vec::new();
+        for link in &timeline.connections {
+            let label = match link.label.as_deref() {
+                Some(label) if !label.is_empty() => format!(" — {label}"),
+                _ => String::new(),
+            };
+            if link.source_id == event.id {
+                if let Some(target) = event_link(&link.target_id) {
+                    links.push(format!("- {} {target}{label}", link.connection_type));
+                }
+            } else if link.target_id == event.id {
+                if let Some(source) = event_link(&link.source_id) {
+                    links.push(format!("- {source} {} this{label}", link.connection_type));
+                }
+            }
+        }
+        if !links.is_empty() {
+            md.push_str("## Connections\n\n");
+            md.push_str(&links.join("\n"));
+            md.push('\n');
+        }
+
+        let path = Path::new("Events").join(format!("{}.md", event_notes[event.id.as_str()]));
+        notes.push(VaultNote { path, content: md });
+    }
+
+    let event_line = |event: &ExportedEvent| {
+        format!("- {} {}", date_range(event), event_link(&event.id).unwrap_or_default())
+    };
+    for track in &timeline.tracks {
+        let mut md = String::from("---\ntype: track\n");
+        md.push_str(&format!("color: {}\n", yaml_string(&track.color)));
+        md.push_str(&format!("timeline: {}\n", yaml_string(&wikilink(&index_name, &timeline.title))));
+        md.push_str(&format!("{NOTE_MARKER} {}\n", yaml_string(&track.id)));
+        md.push_str("---\n\n");
+        for event in timeline.events.iter().filter(|e| e.track_id == track.id) {
+            md.push_str(&event_line(event));
+            md.push('\n');
+        }
+        let path = Path::new("Tracks").join(format!("{}.md", track_notes[track.id.as_str()]));
+        notes.push(VaultNote { path, content: md });
+    }
+
+    let mut md = String::from("---\ntype: timeline\n");
+    md.push_str(&format!("created: {}\n", yaml_string(&timeline.created_at)));
+    md.push_str(&format!("updated: {}\n", yaml_string(&timeline.updated_at)));
+    md.push_str(&format!("{NOTE_MARKER} {}\n", yaml_string(&timeline.id)));
+    md.push_str("---\n\n");
+    if !timeline.description.is_empty() {
+        md.push_str(&timeline.description);
+        md.push_str("\n\n");
+    }
+    md.push_str("## Tracks\n\n");
+    for track in &timeline.tracks {
+        md.push_str(&format!("- {}\n", track_link(&track.id).unwrap_or_default()));
+    }
+    md.push_str("\n## Events\n\n");
+    for event in &timeline.events {
+        md.push_str(&event_line(event));
+        md.push('\n');
+    }
+    notes.push(VaultNote { path: PathBuf::from(format!("{index_name}.md")), content: md });
+
+    notes
+}
+
+/// Folders the export writes event and track notes to.
+const NOTE_FOLDERS: [&str; 2] = ["Events", "Tracks"];
+
+/// Front matter property that marks a note as written by the export.
+const NOTE_MARKER: &str = "chronomap_id:";
+
+/// Whether the note at `path` carries the export's front matter marker.
+fn is_exported_note(path: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    let mut lines = content.lines();
+    lines.next() == Some("---") && lines.take_while(|l| *l != "---").any(|l| l.starts_with(NOTE_MARKER))
+}
+
+/// Write vault notes under `root`. Notes an earlier export wrote to `Events`
+/// and `Tracks` (those with a `chronomap_id` property) are removed first, so
+/// renamed or deleted events and tracks do not linger. Notes the user added
+/// are left alone, and one in the way of an exported note stops the export.
+pub fn write_vault(root: &Path, notes: &[VaultNote]) -> AppResult<()> {
+    if let Some(note) = notes.iter().find(|n| root.join(&n.path).exists() && !is_exported_note(&root.join(&n.path))) {
+        return Err(AppError::Validation(format!(
+            "{} already exists and was not written by ChronoMap; move or rename it first",
+            note.path.display()
+        )));
+    }
+    for folder in NOTE_FOLDERS {
+        let Ok(entries) = std::fs::read_dir(root.join(folder)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "md") && is_exported_note(&path) {
+                std::fs::remove_file(&path)
+                    .map_err(|e| AppError::Internal(format!("Failed to remove old note: {e}")))?;
+            }
+        }
+    }
+    for note in notes {
+        let path = root.join(&note.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| AppError::Internal(format!("Failed to create folder: {e}")))?;
+        }
+        std::fs::write(&path, &note.content)
+            .map_err(|e| AppError::Internal(format!("Failed to write note: {e}")))?;
+    }
+    Ok(())
+}
+
+/// Write a timeline as an Obsidian vault into a folder named after the
+/// timeline inside `directory`. Re-exporting replaces the notes an earlier
+/// export wrote and keeps the user's own; see `write_vault`.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn export_obsidian_vault(
+    db: State<'_, Mutex<Connection>>,
+    timeline_id: String,
+    directory: String,
+) -> AppResult<VaultExportReport> {
+    validate_file_path(&directory)?;
+    let exported = {
+        let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+        export_timeline(&conn, &timeline_id)?
+    };
+
+    let root = Path::new(&directory).join(NoteNames::default().claim(&exported.title));
+    let notes = timeline_to_vault(&exported);
+    write_vault(&root, &notes)?;
+
+    Ok(VaultExportReport {
+        directory: root.to_string_lossy().to_string(),
+        notes_written: notes.len() as u32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{timeline_to_vault, write_vault};
+    use crate::commands::export::export_timeline;
+    use crate::db::init_test_db;
+
+    #[test]
+    fn test_vault_notes() {
+        let conn = init_test_db().unwrap();
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Rome');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('pol', 'tl', 'Politics', '#ef4444', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, description, start_date, end_date, importance, tags, ai_generated, start_sort_key)
+             VALUES ('e1', 'tl', 'pol', 'Caesar: crossing the Rubicon', 'Alea iacta est.', '49 BCE', NULL, 5, 'civil war,caesar', 1, 1),
+                    ('e2', 'tl', 'pol', 'Civil war', '', '49 BCE', '45 BCE', 4, '', 0, 2),
+                    ('e3', 'tl', 'pol', 'Politics', '', '44 BCE', NULL, 3, '', 0, 3);
+             INSERT INTO connections (id, timeline_id, source_event_id, target_event_id, connection_type, label)
+             VALUES ('c1', 'tl', 'e1', 'e2', 'caused', 'Senate outlaws Caesar');",
+        )
+        .unwrap();
+
+        let notes = timeline_to_vault(&export_timeline(&conn, "tl").unwrap());
+        let paths: Vec<&Path> = notes.iter().map(|n| n.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("Events/Caesar- crossing the Rubicon.md"),
+                Path::new("Events/Civil war.md"),
+                Path::new("Events/Politics (2).md"),
+                Path::new("Tracks/Politics.md"),
+                Path::new("Rome.md"),
+            ]
+        );
+
+        let caesar = &notes[0].content;
+        assert!(caesar.starts_with("---\ntitle: \"Caesar: crossing the Rubicon\"\nstart: \"49 BCE\"\ntype: point\n"));
+        assert!(caesar.contains("track: \"[[Politics]]\"\ntags: [\"civil-war\", \"caesar\"]\nimportance: 5\nai_generated: true\n"));
+        assert!(caesar.contains("chronomap_id: \"e1\"\n---\n"));
+        assert!(caesar.contains("Alea iacta est.\n\n## Connections\n\n- caused [[Civil war]] — Senate outlaws Caesar\n"));
+        let war = &notes[1].content;
+        assert!(war.contains("end: \"45 BCE\""));
+        assert!(war.contains("- [[Caesar- crossing the Rubicon|Caesar: crossing the Rubicon]] caused this — Senate outlaws Caesar"));
+
+        assert!(notes[3].content.contains("- 49 BCE — 45 BCE [[Civil war]]\n- 44 BCE [[Politics (2)|Politics]]\n"));
+        assert!(notes[4].content.contains("## Tracks\n\n- [[Politics]]\n"));
+    }
+
+    #[test]
+    fn test_write_vault_replaces_old_notes() {
+        let conn = init_test_db().unwrap();
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Rome');
+             INSERT INTO tracks (id, timeline_id, name, sort_order) VALUES ('pol', 'tl', 'Politics', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, start_sort_key)
+             VALUES ('e1', 'tl', 'pol', 'Ides of March', '44 BCE', 1);",
+        )
+        .unwrap();
+        let root = std::env::temp_dir().join(format!("chronomap-vault-{}", uuid::Uuid::new_v4()));
+        write_vault(&root, &timeline_to_vault(&export_timeline(&conn, "tl").unwrap())).unwrap();
+        std::fs::write(root.join("My notes.md"), "kept").unwrap();
+        std::fs::write(root.join("Events/Reading list.md"), "---\ntags: [rome]\n---\n\nSuetonius").unwrap();
+
+        conn.execute("UPDATE events SET title = 'Death of Caesar' WHERE id = 'e1'", []).unwrap();
+        write_vault(&root, &timeline_to_vault(&export_timeline(&conn, "tl").unwrap())).unwrap();
+        let mut events: Vec<String> = std::fs::read_dir(root.join("Events"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        events.sort();
+        assert_eq!(events, vec!["Death of Caesar.md", "Reading list.md"]);
+        assert!(root.join("My notes.md").exists());
+
+        // A note of the user's where an event note would go is not overwritten
+        std::fs::write(root.join("Events/Senate.md"), "My own note").unwrap();
+        conn.execute("UPDATE events SET title = 'Senate' WHERE id = 'e1'", []).unwrap();
+        let notes = timeline_to_vault(&export_timeline(&conn, "tl").unwrap());
+        assert!(matches!(write_vault(&root, &notes), Err(crate::error::AppError::Validation(_))));
+        assert!(root.join("Events/Death of Caesar.md").exists());
+        assert_eq!(std::fs::read_to_string(root.join("Events/Senate.md")).unwrap(), "My own note");
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use rusqlite::Connection;
+use serde::Deserialize;
+#[cfg(feature = "desktop")]
+use tauri::State;
+
+use super::import::{import_in_transaction, ImportReport, ImportedEvent, ImportedTimeline, ImportedTrack};
+use crate::db::dates::{DatePrecision, HistoricalDate};
+use crate::error::{AppError, AppResult};
+
+/// A SPARQL 1.1 query results document in its JSON serialization, as saved
+/// from the Wikidata Query Service.
+#[derive(Debug, Deserialize)]
+struct SparqlResults {
+    results: SparqlBindings,
+}
+
+#[derive(Debug, Deserialize)]
+struct SparqlBindings {
+    bindings: Vec<HashMap<String, SparqlValue>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SparqlValue {
+    value: String,
+}
+
+/// Which query variables hold which event fields. Defaults follow the usual
+/// Wikidata query shape (`?item ?itemLabel ?date ?endDate`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SparqlMapping {
+    /// The entity IRI, stored as the event's external link.
+    pub item: String,
+    pub title: String,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub description: Option<String>,
+    pub track_name: Option<String>,
+    /// A `wikibase:timePrecision` value for the start date (7 century, 8 decade,
+    /// 9 year, 10 month, 11 day).
+    pub date_precision: Option<String>,
+    /// The same for the end date.
+    pub end_date_precision: Option<String>,
+}
+
+impl Default for SparqlMapping {
+    fn default() -> Self {
+        Self {
+            item: "item".to_string(),
+            title: "itemLabel".to_string(),
+            start_date: "date".to_string(),
+            end_date: Some("endDate".to_string()),
+            description: Some("itemDescription".to_string()),
+            track_name: None,
+            date_precision: None,
+            end_date_precision: None,
+        }
+    }
+}
+
+/// Turn a Wikidata time value into a canonical date. The query service writes
+/// every date as a full xsd:dateTime on the astronomical year scale
+/// ("+1789-07-14T00:00:00Z"; "-0490-01-01T00:00:00Z" is 491 BCE). Midnight
+/// times are dropped, and with a known precision so are the parts below it.
+/// Decade and century precision become the decade or century containing the
+/// year; coarser ones a circa year. Other date text is parsed as it is.
+fn wikidata_date(value: &str, precision: Option<u8>) -> Option<HistoricalDate> {
+    let value = value.trim();
+    let value = value.strip_suffix("T00:00:00Z").unwrap_or(value);
+    let Some(precision) = precision else {
+        return HistoricalDate::parse(value).ok();
+    };
+
+    let (sign, body) = match value.strip_prefix('-') {
+        Some(body) => ("-", body),
+        None => ("", value.trim_start_matches('+')),
+    };
+    let date_part = body.split('T').next().unwrap_or(body);
+    let parts: Vec<&str> = date_part.split('-').collect();
+    let keep = match precision {
+        0..=9 => 1,
+        10 => 2,
+        _ => parts.len(),
+    };
+    let truncated = format!("{sign}{}", parts[..keep.min(parts.len())].join("-"));
+    let date = HistoricalDate::parse(&truncated).ok()?;
+    match precision {
+        8 => date.period(DatePrecision::Decade),
+        7 => date.period(DatePrecision::Century),
+        0..=6 => HistoricalDate::from_year(date.year()).map(|d| d.with_circa(true)),
+        _ => Some(date),
+    }
+}
+
+/// Convert query results into a timeline. Each entity becomes one event; later
+/// rows for an entity already seen (one per extra date or label) are ignored.
+fn sparql_to_timeline(title: &str, data: &str, mapping: &SparqlMapping) -> AppResult<ImportedTimeline> {
+    let results: SparqlResults = serde_json::from_str(data)
+        .map_err(|e| AppError::Validation(format!("Not a SPARQL JSON result: {e}")))?;
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut tracks: Vec<ImportedTrack> = Vec::new();
+    let mut events = Vec::new();
+    for binding in &results.results.bindings {
+        let get = |var: &str| binding.get(var).map(|v| v.value.trim()).filter(|v| !v.is_empty());
+        let get_opt = |var: &Option<String>| var.as_deref().and_then(get);
+
+        let item = get(&mapping.item);
+        if let Some(item) = item {
+            if !seen.insert(item.to_string()) {
+                continue;
+            }
+        }
+        let precision = get_opt(&mapping.date_precision).and_then(|p| p.parse().ok());
+        let end_precision = get_opt(&mapping.end_date_precision).and_then(|p| p.parse().ok());
+        // Unparseable dates are passed through so the import report names them.
+        let date = |raw: &str, precision| {
+            wikidata_date(raw, precision).map_or_else(|| raw.to_string(), |d| d.to_string())
+        };
+        let track_name = get_opt(&mapping.track_name).map(str::to_string);
+        if let Some(ref name) = track_name {
+            if !tracks.iter().any(|t| &t.name == name) {
+                tracks.push(ImportedTrack { name: name.clone(), ..Default::default() });
+            }
+        }
+
+        events.push(ImportedEvent {
+            title: get(&mapping.title).or(item).unwrap_or_default().to_string(),
+            description: get_opt(&mapping.description).map(str::to_string),
+            start_date: get(&mapping.start_date).map(|raw| date(raw, precision)).unwrap_or_default(),
+            end_date: get_opt(&mapping.end_date).map(|raw| date(raw, end_precision)),
+            event_type: Some(if get_opt(&mapping.end_date).is_some() { "range" } else { "point" }.to_string()),
+            track_name,
+            external_link: item.map(str::to_string),
+            source: Some("Wikidata".to_string()),
+            ..Default::default()
+        });
+    }
+
+    Ok(ImportedTimeline {
+        title: title.to_string(),
+        tracks,
+        events,
+        ..Default::default()
+    })
+}
+
+/// Import a saved SPARQL JSON result (e.g. from query.wikidata.org) as a new
+/// timeline. With `dry_run` everything is validated and counted, then rolled back.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+pub fn import_wikidata_json(
+    db: State<'_, Mutex<Connection>>,
+    title: String,
+    data: String,
+    mapping: Option<SparqlMapping>,
+    dry_run: Option<bool>,
+) -> AppResult<ImportReport> {
+    let imported = sparql_to_timeline(&title, &data, &mapping.unwrap_or_default())?;
+    let conn = db.lock().map_err(|e| AppError::Internal(e.to_string()))?;
+    import_in_transaction(&conn, &imported, dry_run.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sparql_to_timeline, wikidata_date, SparqlMapping};
+    use crate::commands::import::import_timeline;
+    use crate::db::init_test_db;
+
+    #[test]
+    fn test_wikidata_dates() {
+        let canonical = |value: &str, precision| wikidata_date(value, precision).unwrap().to_string();
+        assert_eq!(canonical("+1789-07-14T00:00:00Z", None), "1789-07-14");
+        assert_eq!(canonical("1789-07-14T00:00:00Z", Some(11)), "1789-07-14");
+        assert_eq!(canonical("1789-01-01T00:00:00Z", Some(9)), "1789");
+        assert_eq!(canonical("1789-07-01T00:00:00Z", Some(10)), "1789-07");
+        assert_eq!(canonical("-0490-01-01T00:00:00Z", Some(9)), "491 BCE");
+        assert_eq!(canonical("1850-01-01T00:00:00Z", Some(8)), "1850s");
+        assert_eq!(canonical("+1900-00-00T00:00:00Z", Some(7)), "20th century");
+        assert_eq!(canonical("-0450-00-00T00:00:00Z", Some(7)), "5th century BCE");
+        assert_eq!(canonical("+2000-00-00T00:00:00Z", Some(6)), "c. 2000");
+        assert!(wikidata_date("unknown", None).is_none());
+    }
+
+    #[test]
+    fn test_sparql_import() {
+        let data = r#"{
+            "head": {"vars": ["item", "itemLabel", "date", "endDate", "precision", "endPrecision", "kind"]},
+            "results": {"bindings": [
+                {
+                    "item": {"type": "uri", "value": "http://www.wikidata.org/entity/Q6534"},
+                    "itemLabel": {"xml:lang": "en", "type": "literal", "value": "French Revolution"},
+                    "date": {"datatype": "http://www.w3.org/2001/XMLSchema#dateTime", "type": "literal", "value": "1789-05-05T00:00:00Z"},
+                    "endDate": {"type": "literal", "value": "1799-11-09T00:00:00Z"},
+                    "precision": {"type": "literal", "value": "11"},
+                    "endPrecision": {"type": "literal", "value": "9"},
+                    "kind": {"type": "literal", "value": "revolution"}
+                },
+                {
+                    "item": {"type": "uri", "value": "http://www.wikidata.org/entity/Q6534"},
+                    "itemLabel": {"type": "literal", "value": "French Revolution"},
+                    "date": {"type": "literal", "value": "1789-01-01T00:00:00Z"}
+                },
+                {
+                    "item": {"type": "uri", "value": "http://www.wikidata.org/entity/Q48314"},
+                    "itemLabel": {"type": "literal", "value": "Battle of Marathon"},
+                    "date": {"type": "literal", "value": "-0489-01-01T00:00:00Z"},
+                    "precision": {"type": "literal", "value": "9"},
+                    "kind": {"type": "literal", "value": "battle"}
+                },
+                {
+                    "item": {"type": "uri", "value": "http://www.wikidata.org/entity/Q1"},
+                    "itemLabel": {"type": "literal", "value": "Undated"}
+                }
+            ]}
+        }"#;
+        let mapping = SparqlMapping {
+            date_precision: Some("precision".to_string()),
+            end_date_precision: Some("endPrecision".to_string()),
+            track_name: Some("kind".to_string()),
+            ..Default::default()
+        };
+        let imported = sparql_to_timeline("Wikidata", data, &mapping).unwrap();
+        assert_eq!(imported.events.len(), 3);
+        assert_eq!(imported.tracks.len(), 2);
+        assert_eq!(imported.events[1].start_date, "490 BCE");
+
+        let conn = init_test_db().unwrap();
+        let report = import_timeline(&conn, &imported).unwrap();
+        assert_eq!(report.events_created, 2);
+        assert_eq!(report.skipped[0].title, "Undated");
+
+        let (end, link): (String, String) = conn
+            .query_row(
+                "SELECT end_date, external_link FROM events WHERE title = 'French Revolution'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(end, "1799");
+        assert_eq!(link, "http://www.wikidata.org/entity/Q6534");
+    }
+}
@@ -0,0 +1,786 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::Connection;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{AppError, AppResult};
+
+/// Largest absolute year accepted by the parser. chrono supports roughly ±262,000.
+const MAX_ABS_YEAR: i32 = 200_000;
+
+const MONTH_NAMES: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june", "july", "august", "september",
+    "october", "november", "december",
+];
+
+/// How much of a date is actually known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatePrecision {
+    Century,
+    Decade,
+    Year,
+    Month,
+    Day,
+    Time,
+}
+
+/// A calendar date that may lie before the common era, be only partially known,
+/// or be approximate ("c. 500 BCE", "1850s", "1905", "1789-07-14").
+///
+/// Years use astronomical numbering internally (1 BCE is year 0, 500 BCE is -499)
+/// on the proleptic Gregorian calendar. The canonical text form produced by
+/// `Display` is what gets stored in `events.start_date` / `events.end_date`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoricalDate {
+    /// First instant covered by the date at its precision.
+    start: NaiveDateTime,
+    precision: DatePrecision,
+    circa: bool,
+}
+
+impl HistoricalDate {
+    /// Parse user, import or AI supplied text into a date.
+    pub fn parse(input: &str) -> AppResult<Self> {
+        parse_date(input).ok_or_else(|| AppError::Validation(format!("Unrecognized date '{}'", input.trim())))
+    }
+
+    /// Parse an optional date, treating missing or blank text as no date.
+    pub fn parse_optional(input: Option<&str>) -> AppResult<Option<Self>> {
+        match input.map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(raw) => Self::parse(raw).map(Some),
+        }
+    }
+
+    /// Build a day-precision date from a chrono date.
+    pub fn from_naive_date(date: NaiveDate) -> Self {
+        Self {
+            start: date.and_time(NaiveTime::MIN),
+            precision: DatePrecision::Day,
+            circa: false,
+        }
+    }
+
+    /// Build a date with a time of day from a chrono date and time.
+    #[cfg(feature = "desktop")]
+    pub fn from_naive_datetime(start: NaiveDateTime) -> Self {
+        Self { start, precision: DatePrecision::Time, circa: false }
+    }
+
+    /// Build a year-precision date from an astronomical year.
+    pub fn from_year(year: i32) -> Option<Self> {
+        Self::from_parts(year, None, None, None)
+    }
+
+    /// Build a month-precision date from an astronomical year and a 1-based month.
+    pub fn from_year_month(year: i32, month: u32) -> Option<Self> {
+        Self::from_parts(year, Some(month), None, None)
+    }
+
+    pub fn with_circa(mut self, circa: bool) -> Self {
+        self.circa = circa;
+        self
+    }
+
+    /// Astronomical year of the first instant covered by this date.
+    pub fn year(&self) -> i32 {
+        self.start.year()
+    }
+
+    pub fn is_bce(&self) -> bool {
+        self.year() <= 0
+    }
+
+    /// First instant covered by the date.
+    #[cfg(feature = "desktop")]
+    pub fn start(&self) -> NaiveDateTime {
+        self.start
+    }
+
+    /// Whether a time of day is known.
+    #[cfg(feature = "desktop")]
+    pub fn has_time(&self) -> bool {
+        self.precision == DatePrecision::Time
+    }
+
+    #[cfg(feature = "desktop")]
+    pub fn precision(&self) -> DatePrecision {
+        self.precision
+    }
+
+    #[cfg(feature = "desktop")]
+    pub fn is_circa(&self) -> bool {
+        self.circa
+    }
+
+    /// The year, decade or century containing this date: 1789-07-14 lies in
+    /// 1789, the 1780s and the 18th century, and 455 BCE in the 450s BCE.
+    /// Finer precisions give the year.
+    pub fn period(&self, precision: DatePrecision) -> Option<Self> {
+        let year = self.year();
+        let start_year = match precision {
+            DatePrecision::Century if year > 0 => (year / 100 * 100).max(1),
+            DatePrecision::Century => 1 - (1 - year + 99) / 100 * 100,
+            DatePrecision::Decade if year > 0 => (year / 10 * 10).max(1),
+            DatePrecision::Decade => 1 - ((1 - year) / 10 * 10 + 9),
+            _ => year,
+        };
+        let date = Self::from_year(start_year)?;
+        Some(Self { precision: precision.min(DatePrecision::Year), ..date })
+    }
+
+    /// First instant after the period covered by the date (equal to `start` for times).
+    pub fn end(&self) -> NaiveDateTime {
+        let year = self.start.year();
+        let jan_first = |y: i32| {
+            NaiveDate::from_ymd_opt(y, 1, 1)
+                .map(|d| d.and_time(NaiveTime::MIN))
+                .unwrap_or(self.start)
+        };
+        match self.precision {
+            DatePrecision::Century => jan_first(year + 100),
+            // The 0s start in year 1 but still end with year 9
+            DatePrecision::Decade if year > 0 => jan_first(year / 10 * 10 + 10),
+            DatePrecision::Decade => jan_first(year + 10),
+            DatePrecision::Year => jan_first(year + 1),
+            DatePrecision::Month => self
+                .start
+                .date()
+                .checked_add_months(chrono::Months::new(1))
+                .map(|d| d.and_time(NaiveTime::MIN))
+                .unwrap_or(self.start),
+            DatePrecision::Day => self.start + chrono::Duration::days(1),
+            DatePrecision::Time => self.start,
+        }
+    }
+
+    /// Days since 1970-01-01 of the first instant covered; used as the sortable key.
+    pub fn sort_key(&self) -> f64 {
+        epoch_days(self.start)
+    }
+
+    /// Days since 1970-01-01 of the first instant after the covered period.
+    fn end_key(&self) -> f64 {
+        epoch_days(self.end())
+    }
+
+    /// Earliest and latest plausible position in epoch days, widened for circa dates.
+    pub fn bounds(&self) -> (f64, f64) {
+        let margin = if self.circa {
+            match self.precision {
+                DatePrecision::Century => 50.0 * 365.25,
+                DatePrecision::Decade => 10.0 * 365.25,
+                DatePrecision::Year => 5.0 * 365.25,
+                DatePrecision::Month => 31.0,
+                DatePrecision::Day | DatePrecision::Time => 7.0,
+            }
+        } else {
+            0.0
+        };
+        (self.sort_key() - margin, self.end_key() + margin)
+    }
+
+    fn from_parts(year: i32, month: Option<u32>, day: Option<u32>, time: Option<NaiveTime>) -> Option<Self> {
+        if year.abs() > MAX_ABS_YEAR {
+            return None;
+        }
+        let date = NaiveDate::from_ymd_opt(year, month.unwrap_or(1), day.unwrap_or(1))?;
+        let precision = match (month, day, time) {
+            (_, Some(_), Some(_)) => DatePrecision::Time,
+            (_, Some(_), None) => DatePrecision::Day,
+            (Some(_), None, None) => DatePrecision::Month,
+            (None, None, None) => DatePrecision::Year,
+            _ => return None,
+        };
+        Some(Self {
+            start: date.and_time(time.unwrap_or(NaiveTime::MIN)),
+            precision,
+            circa: false,
+        })
+    }
+}
+
+fn epoch_days(dt: NaiveDateTime) -> f64 {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)
+        .expect("valid epoch")
+        .and_time(NaiveTime::MIN);
+    (dt - epoch).num_seconds() as f64 / 86400.0
+}
+
+fn ordinal_suffix(n: i32) -> &'static str {
+    match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+impl fmt::Display for HistoricalDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.circa {
+            f.write_str("c. ")?;
+        }
+        let year = self.year();
+        let bce = self.is_bce();
+        // Year as written with an era: 500 BCE is astronomical -499.
+        let era_year = if bce { 1 - year } else { year };
+        let era = if bce { " BCE" } else { "" };
+
+        match self.precision {
+            DatePrecision::Century => {
+                let n = if bce { (1 - year) / 100 } else { year / 100 + 1 };
+                write!(f, "{n}{} century{era}", ordinal_suffix(n))
+            }
+            DatePrecision::Decade => {
+                let label = if bce { -8 - year } else { year / 10 * 10 };
+                write!(f, "{label}s{era}")
+            }
+            DatePrecision::Year => write!(f, "{era_year}{era}"),
+            DatePrecision::Month => write!(f, "{era_year:04}-{:02}{era}", self.start.month()),
+            DatePrecision::Day => write!(
+                f,
+                "{era_year:04}-{:02}-{:02}{era}",
+                self.start.month(),
+                self.start.day()
+            ),
+            DatePrecision::Time => write!(
+                f,
+                "{era_year:04}-{:02}-{:02}T{:02}:{:02}:{:02}{era}",
+                self.start.month(),
+                self.start.day(),
+                self.start.hour(),
+                self.start.minute(),
+                self.start.second()
+            ),
+        }
+    }
+}
+
+impl FromStr for HistoricalDate {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Serialize for HistoricalDate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HistoricalDate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        HistoricalDate::parse(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+impl ToSql for HistoricalDate {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for HistoricalDate {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        HistoricalDate::parse(text).map_err(|e| FromSqlError::Other(Box::new(e)))
+    }
+}
+
+// ── Parsing ──
+
+fn strip_prefix_ci<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &s[prefix.len()..])
+}
+
+fn strip_suffix_ci<'a>(s: &'a str, suffix: &str) -> Option<&'a str> {
+    let split = s.len().checked_sub(suffix.len())?;
+    let tail = s.get(split..)?;
+    tail.eq_ignore_ascii_case(suffix).then(|| &s[..split])
+}
+
+/// Remove approximation markers, returning the remaining text and whether any were found.
+fn strip_circa(s: &str) -> (&str, bool) {
+    const PREFIXES: [&str; 10] = [
+        "approximately ", "approx. ", "approx ", "circa ", "around ", "about ", "ca. ", "ca ", "c. ", "c.",
+    ];
+    for prefix in PREFIXES {
+        if let Some(rest) = strip_prefix_ci(s, prefix) {
+            return (rest.trim_start(), true);
+        }
+    }
+    if let Some(rest) = s.strip_prefix('~') {
+        return (rest.trim_start(), true);
+    }
+    if let Some(rest) = s.strip_suffix('~').or_else(|| s.strip_suffix('?')) {
+        return (rest.trim_end(), true);
+    }
+    (s, false)
+}
+
+/// Remove an era marker, returning the remaining text and `Some(true)` for BCE, `Some(false)` for CE.
+fn strip_era(s: &str) -> (&str, Option<bool>) {
+    const BCE: [&str; 4] = ["b.c.e.", "bce", "b.c.", "bc"];
+    const CE: [&str; 4] = ["c.e.", "ce", "a.d.", "ad"];
+    for suffix in BCE {
+        if let Some(rest) = strip_suffix_ci(s, suffix) {
+            if rest.is_empty() || rest.ends_with(|c: char| c.is_whitespace() || c.is_ascii_digit() || c == 's') {
+                return (rest.trim_end(), Some(true));
+            }
+        }
+    }
+    for suffix in CE {
+        if let Some(rest) = strip_suffix_ci(s, suffix) {
+            if rest.ends_with(|c: char| c.is_whitespace() || c.is_ascii_digit() || c == 's') {
+                return (rest.trim_end(), Some(false));
+            }
+        }
+    }
+    for prefix in ["a.d. ", "ad "] {
+        if let Some(rest) = strip_prefix_ci(s, prefix) {
+            return (rest.trim_start(), Some(false));
+        }
+    }
+    (s, None)
+}
+
+fn parse_date(input: &str) -> Option<HistoricalDate> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let (rest, circa) = strip_circa(trimmed);
+    let (body, era) = strip_era(rest);
+    let body = body.trim();
+
+    let parsed = parse_century(body, era)
+        .or_else(|| parse_decade(body, era))
+        .or_else(|| parse_numeric(body, era))
+        .or_else(|| parse_month_name(body, era))?;
+    Some(parsed.with_circa(circa))
+}
+
+fn to_astronomical(year: i64, bce: bool) -> Option<i32> {
+    let year = if bce {
+        if year < 1 {
+            return None;
+        }
+        1 - year
+    } else {
+        year
+    };
+    i32::try_from(year).ok()
+}
+
+/// "19th century", "5th c. BCE"
+fn parse_century(body: &str, era: Option<bool>) -> Option<HistoricalDate> {
+    let lower = body.to_ascii_lowercase();
+    let (number, unit) = lower.split_once(char::is_whitespace)?;
+    if !matches!(unit.trim(), "century" | "cent." | "cent" | "c." | "c") {
+        return None;
+    }
+    let digits = number
+        .strip_suffix("st")
+        .or_else(|| number.strip_suffix("nd"))
+        .or_else(|| number.strip_suffix("rd"))
+        .or_else(|| number.strip_suffix("th"))?;
+    let n: i32 = digits.parse().ok().filter(|n| *n >= 1)?;
+    // There is no year 0, so the 1st century starts in year 1 as in `period`.
+    let start_year = if era == Some(true) { 1 - n * 100 } else { ((n - 1) * 100).max(1) };
+    let date = HistoricalDate::from_year(start_year)?;
+    Some(HistoricalDate {
+        precision: DatePrecision::Century,
+        ..date
+    })
+}
+
+/// "1850s", "1850's", "450s BCE"
+fn parse_decade(body: &str, era: Option<bool>) -> Option<HistoricalDate> {
+    let digits = body.strip_suffix("'s").or_else(|| body.strip_suffix('s'))?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let label: i32 = digits.parse().ok()?;
+    if label % 10 != 0 {
+        return None;
+    }
+    // The 450s BCE run from 459 BCE to 450 BCE.
+    let start_year = if era == Some(true) { 1 - (label + 9) } else { label.max(1) };
+    let date = HistoricalDate::from_year(start_year)?;
+    Some(HistoricalDate {
+        precision: DatePrecision::Decade,
+        ..date
+    })
+}
+
+/// ISO-like forms: "1905", "-0499", "+1789-07-14T00:00:00Z", "1789/07/14", "2024-01-01 10:00:00".
+fn parse_numeric(body: &str, era: Option<bool>) -> Option<HistoricalDate> {
+    let (negative, unsigned) = match body.as_bytes().first()? {
+        b'-' => (true, &body[1..]),
+        b'+' => (false, &body[1..]),
+        _ => (false, body),
+    };
+    if negative && era.is_some() {
+        return None;
+    }
+
+    let (date_part, time_part) = match unsigned.find(['T', 't', ' ']) {
+        Some(idx) => (&unsigned[..idx], Some(unsigned[idx + 1..].trim())),
+        None => (unsigned, None),
+    };
+
+    let separator = if date_part.contains('/') { '/' } else { '-' };
+    let pieces: Vec<&str> = date_part.split(separator).collect();
+    if pieces.is_empty() || pieces.len() > 3 {
+        return None;
+    }
+    if pieces.iter().any(|p| p.is_empty() || !p.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    if pieces[0].len() > 6 || pieces.iter().skip(1).any(|p| p.len() > 2) {
+        return None;
+    }
+
+    let raw_year: i64 = pieces[0].parse().ok()?;
+    let year = if negative {
+        i32::try_from(-raw_year).ok()?
+    } else {
+        to_astronomical(raw_year, era == Some(true))?
+    };
+    let month: Option<u32> = pieces.get(1).map(|m| m.parse()).transpose().ok()?;
+    let day: Option<u32> = pieces.get(2).map(|d| d.parse()).transpose().ok()?;
+
+    let time = match time_part {
+        Some(t) if !t.is_empty() => {
+            day?;
+            Some(parse_time(t)?)
+        }
+        _ => None,
+    };
+
+    HistoricalDate::from_parts(year, month, day, time)
+}
+
+fn parse_time(raw: &str) -> Option<NaiveTime> {
+    // Drop any UTC designator or offset; dates are stored as wall-clock time.
+    let without_zone = raw.trim_end_matches(['Z', 'z']);
+    let without_zone = match without_zone.rfind(['+', '-']) {
+        Some(idx) if idx > 0 => &without_zone[..idx],
+        _ => without_zone,
+    };
+    let without_fraction = without_zone.split('.').next().unwrap_or(without_zone);
+    NaiveTime::parse_from_str(without_fraction, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(without_fraction, "%H:%M"))
+        .ok()
+}
+
+/// "July 14, 1789", "14 July 1789", "Jul 1789", "15 March 44 BC"
+fn parse_month_name(body: &str, era: Option<bool>) -> Option<HistoricalDate> {
+    let tokens: Vec<&str> = body
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+        .collect();
+    if tokens.len() < 2 || tokens.len() > 3 {
+        return None;
+    }
+
+    let mut month = None;
+    let mut numbers = Vec::new();
+    for token in &tokens {
+        let word = token.trim_end_matches('.').to_ascii_lowercase();
+        if let Some(idx) = MONTH_NAMES
+            .iter()
+            .position(|name| word.len() >= 3 && name.starts_with(word.as_str()))
+        {
+            if month.replace(idx as u32 + 1).is_some() {
+                return None;
+            }
+        } else {
+            let digits = word
+                .trim_end_matches("st")
+                .trim_end_matches("nd")
+                .trim_end_matches("rd")
+                .trim_end_matches("th");
+            numbers.push(digits.parse::<i64>().ok()?);
+        }
+    }
+
+    let month = month?;
+    let (day, year) = match numbers.as_slice() {
+        [year] => (None, *year),
+        [day, year] => (Some(u32::try_from(*day).ok()?), *year),
+        _ => return None,
+    };
+    HistoricalDate::from_parts(to_astronomical(year, era == Some(true))?, Some(month), day, None)
+}
+
+// ── Storage ──
+
+/// Fill `start_sort_key` / `end_sort_key` for events that lack them and rewrite their
+/// dates into canonical form. Runs inside the caller's transaction.
+///
+/// Start dates that cannot be understood are left as written, without a sort key, and
+/// logged. Such events are left out of `fetch_timeline_events` and listed with their
+/// stored text by `fetch_undated_events` until a new date is set. An unreadable end
+/// date is dropped and its original text kept in the event's `source`.
+pub fn backfill_sort_keys(conn: &Connection) -> AppResult<u32> {
+    struct PendingRow {
+        id: String,
+        title: String,
+        start_date: String,
+        end_date: Option<String>,
+        source: Option<String>,
+    }
+
+    let rows: Vec<PendingRow> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, title, start_date, end_date, source FROM events WHERE start_sort_key IS NULL",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(PendingRow {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    start_date: row.get(2)?,
+                    end_date: row.get(3)?,
+                    source: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+
+    let mut updated = 0u32;
+    for row in rows {
+        let Ok(start) = HistoricalDate::parse(&row.start_date) else {
+            tracing::warn!(
+                "Event {} ({:?}) has an unrecognized date {:?}; left without a sort key",
+                row.id,
+                row.title,
+                row.start_date
+            );
+            continue;
+        };
+        let (end, source) = match HistoricalDate::parse_optional(row.end_date.as_deref()) {
+            Ok(end) => (end, row.source),
+            Err(_) => {
+                tracing::warn!("Event {} had an unrecognized end date; kept it in source", row.id);
+                let mut parts: Vec<String> = row.source.into_iter().filter(|s| !s.is_empty()).collect();
+                parts.push(format!("Original end date: {}", row.end_date.as_deref().unwrap_or_default()));
+                (None, Some(parts.join("\n")))
+            }
+        };
+
+        conn.execute(
+            "UPDATE events SET start_date = ?1, end_date = ?2, start_sort_key = ?3, end_sort_key = ?4, source = ?5 WHERE id = ?6",
+            rusqlite::params![
+                start,
+                end,
+                start.sort_key(),
+                end.map(|e| e.sort_key()),
+                source,
+                row.id
+            ],
+        )?;
+        updated += 1;
+    }
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_test_db;
+    use rusqlite::params;
+
+    fn canonical(input: &str) -> String {
+        HistoricalDate::parse(input).expect(input).to_string()
+    }
+
+    #[test]
+    fn test_parse_iso_forms() {
+        assert_eq!(canonical("1905"), "1905");
+        assert_eq!(canonical("1789-07"), "1789-07");
+        assert_eq!(canonical("1789-07-14"), "1789-07-14");
+        assert_eq!(canonical("1969-07-20T20:17:40Z"), "1969-07-20T20:17:40");
+        assert_eq!(canonical("2024-01-01 10:00"), "2024-01-01T10:00:00");
+        assert_eq!(canonical("+1789-07-14T00:00:00Z"), "1789-07-14T00:00:00");
+        assert_eq!(canonical("1789/07/14"), "1789-07-14");
+    }
+
+    #[test]
+    fn test_parse_bce_and_circa() {
+        assert_eq!(canonical("c. 500 BCE"), "c. 500 BCE");
+        assert_eq!(canonical("ca. 500 bc"), "c. 500 BCE");
+        assert_eq!(canonical("-0499"), "500 BCE");
+        assert_eq!(canonical("15 March 44 BC"), "0044-03-15 BCE");
+        assert_eq!(canonical("AD 79"), "79");
+        assert_eq!(canonical("~1850"), "c. 1850");
+
+        let date = HistoricalDate::parse("500 BCE").unwrap();
+        assert_eq!(date.year(), -499);
+        assert!(date.is_bce());
+        assert_eq!(date.precision, DatePrecision::Year);
+    }
+
+    #[test]
+    fn test_parse_decades_and_centuries() {
+        let decade = HistoricalDate::parse("1850s").unwrap();
+        assert_eq!(decade.precision, DatePrecision::Decade);
+        assert_eq!(decade.year(), 1850);
+        assert_eq!(decade.to_string(), "1850s");
+        assert_eq!(canonical("450s BCE"), "450s BCE");
+
+        let century = HistoricalDate::parse("19th century").unwrap();
+        assert_eq!(century.year(), 1800);
+        assert_eq!(century.to_string(), "19th century");
+        assert_eq!(canonical("5th century BCE"), "5th century BCE");
+        assert_eq!(HistoricalDate::parse("5th century BCE").unwrap().year(), -499);
+    }
+
+    #[test]
+    fn test_parse_month_names() {
+        assert_eq!(canonical("July 14, 1789"), "1789-07-14");
+        assert_eq!(canonical("14 Jul 1789"), "1789-07-14");
+        assert_eq!(canonical("July 1789"), "1789-07");
+    }
+
+    #[test]
+    fn test_parse_rejects_garbage() {
+        assert!(HistoricalDate::parse("").is_err());
+        assert!(HistoricalDate::parse("not-a-date").is_err());
+        assert!(HistoricalDate::parse("1789-13-01").is_err());
+        assert!(HistoricalDate::parse("-500 BCE").is_err());
+    }
+
+    #[test]
+    fn test_canonical_round_trip() {
+        for input in [
+            "1905", "1789-07", "1789-07-14", "1969-07-20T20:17:40", "500 BCE", "c. 500 BCE",
+            "0044-03-15 BCE", "1850s", "c. 1850s", "450s BCE", "19th century", "1st century BCE",
+            "1st century", "0s",
+        ] {
+            assert_eq!(canonical(input), input);
+        }
+    }
+
+    #[test]
+    fn test_period() {
+        let period = |input: &str, precision| HistoricalDate::parse(input).unwrap().period(precision).unwrap().to_string();
+        assert_eq!(period("1789-07-14", DatePrecision::Year), "1789");
+        assert_eq!(period("1789-07-14", DatePrecision::Decade), "1780s");
+        assert_eq!(period("1789-07-14", DatePrecision::Century), "18th century");
+        assert_eq!(period("1800", DatePrecision::Century), "19th century");
+        assert_eq!(period("c. 455 BCE", DatePrecision::Decade), "450s BCE");
+        assert_eq!(period("450 BCE", DatePrecision::Decade), "450s BCE");
+        assert_eq!(period("500 BCE", DatePrecision::Century), "5th century BCE");
+        assert_eq!(period("401 BCE", DatePrecision::Century), "5th century BCE");
+        assert_eq!(period("44 BCE", DatePrecision::Year), "44 BCE");
+        assert_eq!(period("0005", DatePrecision::Decade), "0s");
+        assert_eq!(period("0050", DatePrecision::Century), "1st century");
+
+        let zeros = HistoricalDate::parse("0s").unwrap();
+        assert_eq!((zeros.year(), zeros.end().year()), (1, 10));
+    }
+
+    #[test]
+    fn test_sort_keys_order_across_eras() {
+        let mut inputs = vec!["1905", "c. 500 BCE", "1850s", "1789-07-14", "19th century", "44 BCE"];
+        inputs.sort_by(|a, b| {
+            let ka = HistoricalDate::parse(a).unwrap().sort_key();
+            let kb = HistoricalDate::parse(b).unwrap().sort_key();
+            ka.total_cmp(&kb)
+        });
+        assert_eq!(inputs, vec!["c. 500 BCE", "44 BCE", "1789-07-14", "19th century", "1850s", "1905"]);
+
+        assert_eq!(HistoricalDate::parse("1970-01-01").unwrap().sort_key(), 0.0);
+        assert_eq!(HistoricalDate::parse("1970").unwrap().end_key(), 365.0);
+    }
+
+    #[test]
+    fn test_circa_widens_bounds() {
+        let exact = HistoricalDate::parse("1850").unwrap().bounds();
+        let circa = HistoricalDate::parse("c. 1850").unwrap().bounds();
+        assert!(circa.0 < exact.0);
+        assert!(circa.1 > exact.1);
+    }
+
+    #[test]
+    fn test_backfill_sort_keys() {
+        let conn = init_test_db().unwrap();
+        conn.execute("INSERT INTO timelines (id, title) VALUES ('tl', 'T')", []).unwrap();
+        conn.execute("INSERT INTO tracks (id, timeline_id, name) VALUES ('tr', 'tl', 'Track')", []).unwrap();
+        conn.execute(
+            "INSERT INTO events (id, timeline_id, track_id, title, start_date, end_date, created_at)
+             VALUES ('e1', 'tl', 'tr', 'Known', 'July 14, 1789', NULL, '2024-01-01 00:00:00')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO events (id, timeline_id, track_id, title, start_date, end_date, source, created_at)
+             VALUES ('e2', 'tl', 'tr', 'Unknown', 'sometime', NULL, NULL, '2024-03-05 12:00:00'),
+                    ('e3', 'tl', 'tr', 'Open end', '1850', 'later', 'Book', '2024-03-05 12:00:00')",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(backfill_sort_keys(&conn).unwrap(), 2);
+
+        let (start, key): (String, f64) = conn
+            .query_row("SELECT start_date, start_sort_key FROM events WHERE id = 'e1'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(start, "1789-07-14");
+        assert_eq!(key, HistoricalDate::parse("1789-07-14").unwrap().sort_key());
+
+        let (start, key): (String, Option<f64>) = conn
+            .query_row("SELECT start_date, start_sort_key FROM events WHERE id = ?1", params!["e2"], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((start.as_str(), key), ("sometime", None));
+
+        let (end, source): (Option<String>, String) = conn
+            .query_row("SELECT end_date, source FROM events WHERE id = ?1", params!["e3"], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(end, None);
+        assert_eq!(source, "Book\nOriginal end date: later");
+
+        // Already keyed rows are left alone, and undated ones are not invented.
+        assert_eq!(backfill_sort_keys(&conn).unwrap(), 0);
+
+        // The undated event does not break the rest of the timeline
+        #[cfg(feature = "desktop")]
+        {
+            let listed = crate::commands::events::fetch_timeline_events(&conn, "tl").unwrap();
+            assert_eq!(listed.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), vec!["e1", "e3"]);
+            let undated = crate::commands::events::fetch_undated_events(&conn, "tl").unwrap();
+            assert_eq!(
+                undated.iter().map(|e| (e.id.as_str(), e.start_date.as_str())).collect::<Vec<_>>(),
+                vec![("e2", "sometime")]
+            );
+            let err = crate::commands::events::fetch_event(&conn, "e2").unwrap_err();
+            assert!(matches!(err, AppError::Validation(ref msg) if msg.contains("'sometime'")));
+        }
+    }
+}
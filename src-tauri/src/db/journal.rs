@@ -0,0 +1,465 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "desktop")]
+use rusqlite::types::Value as SqlValue;
+use rusqlite::types::ValueRef;
+#[cfg(feature = "desktop")]
+use rusqlite::OptionalExtension;
+use rusqlite::{Connection, ToSql};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::{AppError, AppResult};
+
+/// Number of journal entries kept per timeline; older ones are pruned.
+const MAX_ENTRIES_PER_TIMELINE: i64 = 200;
+
+/// Tables covered by the journal, declared parent-first so that restoring rows
+/// in this order satisfies foreign keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalTable {
+    Timelines,
+    Tracks,
+    Events,
+    Connections,
+}
+
+impl JournalTable {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Timelines => "timelines",
+            Self::Tracks => "tracks",
+            Self::Events => "events",
+            Self::Connections => "connections",
+        }
+    }
+}
+
+type RowData = Map<String, Value>;
+
+/// Full copies of the rows a command is about to touch (or just touched).
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    rows: BTreeMap<(JournalTable, String), RowData>,
+}
+
+impl Snapshot {
+    /// Events with the given ids plus every connection attached to them.
+    #[cfg(feature = "desktop")]
+    pub fn events(conn: &Connection, ids: &[String]) -> AppResult<Self> {
+        let mut snapshot = Self::default();
+        if ids.is_empty() {
+            return Ok(snapshot);
+        }
+        let list = placeholders(ids.len());
+        let params = as_params(ids);
+        snapshot.capture(conn, JournalTable::Events, &format!("id IN ({list})"), &params)?;
+        snapshot.capture(
+            conn,
+            JournalTable::Connections,
+            &format!("source_event_id IN ({list}) OR target_event_id IN ({list})"),
+            &params,
+        )?;
+        Ok(snapshot)
+    }
+
+    /// Tracks with the given ids plus their events and connections, which a
+    /// track delete cascades to.
+    #[cfg(feature = "desktop")]
+    pub fn tracks(conn: &Connection, ids: &[String]) -> AppResult<Self> {
+        let mut snapshot = Self::default();
+        if ids.is_empty() {
+            return Ok(snapshot);
+        }
+        let list = placeholders(ids.len());
+        let params = as_params(ids);
+        let track_events = format!("SELECT id FROM events WHERE track_id IN ({list})");
+        snapshot.capture(conn, JournalTable::Tracks, &format!("id IN ({list})"), &params)?;
+        snapshot.capture(conn, JournalTable::Events, &format!("track_id IN ({list})"), &params)?;
+        snapshot.capture(
+            conn,
+            JournalTable::Connections,
+            &format!("source_event_id IN ({track_events}) OR target_event_id IN ({track_events})"),
+            &params,
+        )?;
+        Ok(snapshot)
+    }
+
+    /// A timeline and everything that belongs to it.
+    pub fn timeline(conn: &Connection, id: &str) -> AppResult<Self> {
+        let mut snapshot = Self::default();
+        let params: [&dyn ToSql; 1] = [&id];
+        snapshot.capture(conn, JournalTable::Timelines, "id = ?1", &params)?;
+        for table in [JournalTable::Tracks, JournalTable::Events, JournalTable::Connections] {
+            snapshot.capture(conn, table, "timeline_id = ?1", &params)?;
+        }
+        Ok(snapshot)
+    }
+
+    /// Just the timeline row, for edits that do not touch its contents.
+    #[cfg(feature = "desktop")]
+    pub fn timeline_details(conn: &Connection, id: &str) -> AppResult<Self> {
+        let mut snapshot = Self::default();
+        snapshot.capture(conn, JournalTable::Timelines, "id = ?1", &[&id])?;
+        Ok(snapshot)
+    }
+
+    /// Connections with the given ids.
+    #[cfg(feature = "desktop")]
+    pub fn connections(conn: &Connection, ids: &[String]) -> AppResult<Self> {
+        let mut snapshot = Self::default();
+        if ids.is_empty() {
+            return Ok(snapshot);
+        }
+        let filter = format!("id IN ({})", placeholders(ids.len()));
+        snapshot.capture(conn, JournalTable::Connections, &filter, &as_params(ids))?;
+        Ok(snapshot)
+    }
+
+    fn capture(
+        &mut self,
+        conn: &Connection,
+        table: JournalTable,
+        filter: &str,
+        params: &[&dyn ToSql],
+    ) -> AppResult<()> {
+        let mut stmt = conn.prepare(&format!("SELECT * FROM {} WHERE {filter}", table.name()))?;
+        let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+        let mut rows = stmt.query(params)?;
+        while let Some(row) = rows.next()? {
+            let mut data = RowData::new();
+            for (i, column) in columns.iter().enumerate() {
+                data.insert(column.clone(), json_from_sql(row.get_ref(i)?));
+            }
+            let id = data.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
+            self.rows.insert((table, id), data);
+        }
+        Ok(())
+    }
+}
+
+/// One row as it was before and after a journaled command.
+#[derive(Debug, Serialize, Deserialize)]
+struct RowChange {
+    table: JournalTable,
+    id: String,
+    before: Option<RowData>,
+    after: Option<RowData>,
+}
+
+impl RowChange {
+    /// The state this row should end up in when undoing or redoing.
+    #[cfg(feature = "desktop")]
+    fn target(&self, undoing: bool) -> Option<&RowData> {
+        if undoing { self.before.as_ref() } else { self.after.as_ref() }
+    }
+}
+
+/// Record the difference between two snapshots as a single undoable step.
+///
+/// Unchanged rows are dropped; if nothing changed no entry is written. A step
+/// that touches several timelines is filed as one entry per timeline, so each
+/// can be undone from its own history. Recording a new step discards the
+/// affected timelines' redo history.
+pub fn record(conn: &Connection, label: &str, before: Snapshot, after: Snapshot) -> AppResult<()> {
+    let mut before = before.rows;
+    let mut after = after.rows;
+    let keys: BTreeSet<(JournalTable, String)> = before.keys().chain(after.keys()).cloned().collect();
+
+    let mut by_timeline: BTreeMap<String, Vec<RowChange>> = BTreeMap::new();
+    for key in keys {
+        let old = before.remove(&key);
+        let new = after.remove(&key);
+        if old == new {
+            continue;
+        }
+        let change = RowChange { table: key.0, id: key.1, before: old, after: new };
+        if let Some(timeline_id) = change_timeline_id(&change) {
+            by_timeline.entry(timeline_id).or_default().push(change);
+        }
+    }
+
+    for (timeline_id, changes) in by_timeline {
+        let payload = serde_json::to_string(&changes).map_err(|e| AppError::Internal(e.to_string()))?;
+        conn.execute("DELETE FROM journal WHERE timeline_id = ?1 AND undone = 1", [&timeline_id])?;
+        conn.execute(
+            "INSERT INTO journal (timeline_id, label, changes) VALUES (?1, ?2, ?3)",
+            rusqlite::params![timeline_id, label, payload],
+        )?;
+        conn.execute(
+            "DELETE FROM journal WHERE timeline_id = ?1 AND id NOT IN (
+                 SELECT id FROM journal WHERE timeline_id = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            rusqlite::params![timeline_id, MAX_ENTRIES_PER_TIMELINE],
+        )?;
+    }
+    Ok(())
+}
+
+/// Revert the most recent step for a timeline. Returns its label, or `None`
+/// when there is nothing to undo.
+#[cfg(feature = "desktop")]
+pub fn undo(conn: &Connection, timeline_id: &str) -> AppResult<Option<String>> {
+    step(
+        conn,
+        "SELECT id, label, changes FROM journal WHERE timeline_id = ?1 AND undone = 0 ORDER BY id DESC LIMIT 1",
+        timeline_id,
+        true,
+    )
+}
+
+/// Re-apply the most recently undone step for a timeline.
+#[cfg(feature = "desktop")]
+pub fn redo(conn: &Connection, timeline_id: &str) -> AppResult<Option<String>> {
+    step(
+        conn,
+        "SELECT id, label, changes FROM journal WHERE timeline_id = ?1 AND undone = 1 ORDER BY id ASC LIMIT 1",
+        timeline_id,
+        false,
+    )
+}
+
+/// Labels of the steps `undo` and `redo` would apply next.
+#[cfg(feature = "desktop")]
+pub fn pending_labels(conn: &Connection, timeline_id: &str) -> AppResult<(Option<String>, Option<String>)> {
+    let undo_label = conn
+        .query_row(
+            "SELECT label FROM journal WHERE timeline_id = ?1 AND undone = 0 ORDER BY id DESC LIMIT 1",
+            [timeline_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let redo_label = conn
+        .query_row(
+            "SELECT label FROM journal WHERE timeline_id = ?1 AND undone = 1 ORDER BY id ASC LIMIT 1",
+            [timeline_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok((undo_label, redo_label))
+}
+
+#[cfg(feature = "desktop")]
+fn step(conn: &Connection, query: &str, timeline_id: &str, undoing: bool) -> AppResult<Option<String>> {
+    let tx = conn.unchecked_transaction()?;
+    let entry: Option<(i64, String, String)> = tx
+        .query_row(query, [timeline_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .optional()?;
+    let Some((entry_id, label, payload)) = entry else {
+        return Ok(None);
+    };
+
+    let changes: Vec<RowChange> =
+        serde_json::from_str(&payload).map_err(|e| AppError::Internal(e.to_string()))?;
+    // Events rewritten in place keep their current state as a revision, as edits do
+    let rewritten: Vec<String> = changes
+        .iter()
+        .filter(|c| c.table == JournalTable::Events && c.target(undoing).is_some())
+        .map(|c| c.id.clone())
+        .collect();
+    crate::commands::revisions::record_revisions(&tx, &rewritten)?;
+
+    // Children first for deletes, parents first for restores.
+    for change in changes.iter().rev().filter(|c| c.target(undoing).is_none()) {
+        tx.execute(&format!("DELETE FROM {} WHERE id = ?1", change.table.name()), [&change.id])?;
+    }
+    for change in &changes {
+        if let Some(row) = change.target(undoing) {
+            upsert(&tx, change.table, row)?;
+        }
+    }
+
+    tx.execute(
+        "UPDATE journal SET undone = ?1 WHERE id = ?2",
+        rusqlite::params![undoing, entry_id],
+    )?;
+    tx.commit()?;
+    Ok(Some(label))
+}
+
+#[cfg(feature = "desktop")]
+fn upsert(conn: &Connection, table: JournalTable, row: &RowData) -> AppResult<()> {
+    let columns: Vec<&str> = row.keys().map(String::as_str).collect();
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|c| **c != "id")
+        .map(|c| format!("{c} = excluded.{c}"))
+        .collect();
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT(id) DO UPDATE SET {}",
+        table.name(),
+        columns.join(", "),
+        placeholders(columns.len()),
+        updates.join(", ")
+    );
+    let values: Vec<SqlValue> = row.values().map(sql_from_json).collect();
+    conn.execute(&sql, rusqlite::params_from_iter(values))?;
+    Ok(())
+}
+
+fn change_timeline_id(change: &RowChange) -> Option<String> {
+    let row = change.before.as_ref().or(change.after.as_ref())?;
+    let key = if change.table == JournalTable::Timelines { "id" } else { "timeline_id" };
+    row.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+#[cfg(feature = "desktop")]
+fn placeholders(count: usize) -> String {
+    (1..=count).map(|i| format!("?{i}")).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(feature = "desktop")]
+fn as_params(ids: &[String]) -> Vec<&dyn ToSql> {
+    ids.iter().map(|id| id as &dyn ToSql).collect()
+}
+
+fn json_from_sql(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null | ValueRef::Blob(_) => Value::Null,
+        ValueRef::Integer(n) => Value::from(n),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f).map_or(Value::Null, Value::Number),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+    }
+}
+
+#[cfg(feature = "desktop")]
+fn sql_from_json(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+#[cfg(all(test, feature = "desktop"))]
+mod tests {
+    use super::*;
+    use crate::db::init_test_db;
+    use rusqlite::params;
+
+    fn seed(conn: &Connection) {
+        conn.execute("INSERT INTO timelines (id, title) VALUES ('tl', 'Test')", []).unwrap();
+        conn.execute("INSERT INTO tracks (id, timeline_id, name) VALUES ('tr', 'tl', 'Track')", []).unwrap();
+        for id in ["e1", "e2"] {
+            conn.execute(
+                "INSERT INTO events (id, timeline_id, track_id, title, start_date) VALUES (?1, 'tl', 'tr', ?1, '2024-01-01')",
+                [id],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO connections (id, timeline_id, source_event_id, target_event_id) VALUES ('c1', 'tl', 'e1', 'e2')",
+            [],
+        )
+        .unwrap();
+    }
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_undo_redo_track_cascade() {
+        let conn = init_test_db().unwrap();
+        seed(&conn);
+        let ids = vec!["tr".to_string()];
+
+        let before = Snapshot::tracks(&conn, &ids).unwrap();
+        conn.execute("DELETE FROM tracks WHERE id = 'tr'", []).unwrap();
+        let after = Snapshot::tracks(&conn, &ids).unwrap();
+        record(&conn, "Delete track", before, after).unwrap();
+        assert_eq!(count(&conn, "events"), 0);
+
+        assert_eq!(undo(&conn, "tl").unwrap().as_deref(), Some("Delete track"));
+        assert_eq!(count(&conn, "tracks"), 1);
+        assert_eq!(count(&conn, "events"), 2);
+        assert_eq!(count(&conn, "connections"), 1);
+
+        assert_eq!(redo(&conn, "tl").unwrap().as_deref(), Some("Delete track"));
+        assert_eq!(count(&conn, "events"), 0);
+        assert_eq!(redo(&conn, "tl").unwrap(), None);
+    }
+
+    #[test]
+    fn test_undo_update_and_redo_invalidation() {
+        let conn = init_test_db().unwrap();
+        seed(&conn);
+        let ids = vec!["e1".to_string()];
+
+        let before = Snapshot::events(&conn, &ids).unwrap();
+        conn.execute("UPDATE events SET title = 'Renamed', importance = 5 WHERE id = 'e1'", []).unwrap();
+        let after = Snapshot::events(&conn, &ids).unwrap();
+        record(&conn, "Update event", before, after).unwrap();
+
+        undo(&conn, "tl").unwrap();
+        let (title, importance): (String, i32) = conn
+            .query_row("SELECT title, importance FROM events WHERE id = 'e1'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((title.as_str(), importance), ("e1", 3));
+        assert_eq!(pending_labels(&conn, "tl").unwrap(), (None, Some("Update event".to_string())));
+
+        // A new step discards the redo history
+        let before = Snapshot::events(&conn, &ids).unwrap();
+        conn.execute("UPDATE events SET title = ?1 WHERE id = 'e1'", params!["Other"]).unwrap();
+        let after = Snapshot::events(&conn, &ids).unwrap();
+        record(&conn, "Rename", before, after).unwrap();
+        assert_eq!(pending_labels(&conn, "tl").unwrap(), (Some("Rename".to_string()), None));
+    }
+
+    #[test]
+    fn test_multi_timeline_step_undoes_per_timeline() {
+        let conn = init_test_db().unwrap();
+        seed(&conn);
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl2', 'Other');
+             INSERT INTO tracks (id, timeline_id, name) VALUES ('tr2', 'tl2', 'Track');
+             INSERT INTO events (id, timeline_id, track_id, title, start_date) VALUES ('f1', 'tl2', 'tr2', 'f1', '2024-01-01');",
+        )
+        .unwrap();
+        let ids = vec!["e1".to_string(), "f1".to_string()];
+
+        let before = Snapshot::events(&conn, &ids).unwrap();
+        conn.execute("UPDATE events SET importance = 5", []).unwrap();
+        let after = Snapshot::events(&conn, &ids).unwrap();
+        record(&conn, "Bulk update", before, after).unwrap();
+
+        let importance = |id: &str| -> i32 {
+            conn.query_row("SELECT importance FROM events WHERE id = ?1", [id], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(undo(&conn, "tl2").unwrap().as_deref(), Some("Bulk update"));
+        assert_eq!((importance("e1"), importance("f1")), (5, 3));
+        assert_eq!(undo(&conn, "tl").unwrap().as_deref(), Some("Bulk update"));
+        assert_eq!((importance("e1"), importance("f1")), (3, 3));
+    }
+
+    #[test]
+    fn test_undo_timeline_delete_survives_reconnect() {
+        let dir = std::env::temp_dir().join(format!("chronomap-journal-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.db");
+        {
+            let conn = crate::db::init_db(&path).unwrap();
+            seed(&conn);
+            let before = Snapshot::timeline(&conn, "tl").unwrap();
+            conn.execute("DELETE FROM timelines WHERE id = 'tl'", []).unwrap();
+            let after = Snapshot::timeline(&conn, "tl").unwrap();
+            record(&conn, "Delete timeline", before, after).unwrap();
+        }
+        let conn = crate::db::init_db(&path).unwrap();
+        assert_eq!(count(&conn, "timelines"), 0);
+        undo(&conn, "tl").unwrap();
+        assert_eq!(count(&conn, "timelines"), 1);
+        assert_eq!(count(&conn, "events"), 2);
+        assert_eq!(count(&conn, "connections"), 1);
+        drop(conn);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
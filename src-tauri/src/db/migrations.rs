@@ -5,13 +5,28 @@ use crate::error::AppResult;
 const MIGRATION_001: &str = include_str!("../migrations/001_initial.sql");
 const MIGRATION_002: &str = include_str!("../migrations/002_fts.sql");
 const MIGRATION_003: &str = include_str!("../migrations/003_templates.sql");
+const MIGRATION_004: &str = include_str!("../migrations/004_historical_dates.sql");
+const MIGRATION_005: &str = include_str!("../migrations/005_journal.sql");
+const MIGRATION_006: &str = include_str!("../migrations/006_event_revisions.sql");
 
-const MIGRATIONS: &[(&str, &str)] = &[
-    ("001_initial", MIGRATION_001),
-    ("002_fts", MIGRATION_002),
-    ("003_templates", MIGRATION_003),
+/// Rust code run after a migration's SQL, for changes SQL alone cannot make.
+type Hook = fn(&Connection) -> AppResult<()>;
+
+const MIGRATIONS: &[(&str, &str, Option<Hook>)] = &[
+    ("001_initial", MIGRATION_001, None),
+    ("002_fts", MIGRATION_002, None),
+    ("003_templates", MIGRATION_003, None),
+    ("004_historical_dates", MIGRATION_004, Some(backfill_dates)),
+    ("005_journal", MIGRATION_005, None),
+    ("006_event_revisions", MIGRATION_006, None),
 ];
 
+/// Sort keys can only be computed in Rust, so existing rows are filled here.
+fn backfill_dates(conn: &Connection) -> AppResult<()> {
+    super::dates::backfill_sort_keys(conn)?;
+    Ok(())
+}
+
 pub fn run_migrations(conn: &Connection) -> AppResult<()> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS _migrations (
@@ -19,8 +34,11 @@ pub fn run_migrations(conn: &Connection) -> AppResult<()> {
             applied_at TEXT NOT NULL DEFAULT (datetime('now'))
         );",
     )?;
+    apply_migrations(conn, MIGRATIONS)
+}
 
-    for (name, sql) in MIGRATIONS {
+fn apply_migrations(conn: &Connection, migrations: &[(&str, &str, Option<Hook>)]) -> AppResult<()> {
+    for (name, sql, hook) in migrations {
         let already_applied: bool = conn.query_row(
             "SELECT EXISTS(SELECT 1 FROM _migrations WHERE name = ?1)",
             [name],
@@ -28,8 +46,14 @@ pub fn run_migrations(conn: &Connection) -> AppResult<()> {
         )?;
 
         if !already_applied {
-            conn.execute_batch(sql)?;
-            conn.execute("INSERT INTO _migrations (name) VALUES (?1)", [name])?;
+            // SQL, hook and bookkeeping land together, so a failed hook can be retried
+            let tx = conn.unchecked_transaction()?;
+            tx.execute_batch(sql)?;
+            if let Some(hook) = hook {
+                hook(&tx)?;
+            }
+            tx.execute("INSERT INTO _migrations (name) VALUES (?1)", [name])?;
+            tx.commit()?;
             tracing::info!("Applied migration: {name}");
         }
     }
@@ -40,6 +64,7 @@ pub fn run_migrations(conn: &Connection) -> AppResult<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::AppError;
 
     #[test]
     fn test_migrations_idempotent() {
@@ -74,5 +99,29 @@ mod tests {
         assert!(tables.contains(&"events".to_string()));
         assert!(tables.contains(&"connections".to_string()));
         assert!(tables.contains(&"settings".to_string()));
+        assert!(tables.contains(&"journal".to_string()));
+        assert!(tables.contains(&"event_revisions".to_string()));
+    }
+
+    #[test]
+    fn test_failed_hook_rolls_back_migration() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        let failing: &[(&str, &str, Option<Hook>)] = &[(
+            "900_test",
+            "ALTER TABLE events ADD COLUMN extra TEXT;",
+            Some(|_| Err(AppError::Internal("backfill failed".to_string()))),
+        )];
+        assert!(apply_migrations(&conn, failing).is_err());
+
+        // Neither the column nor the record survive, so the next start retries cleanly
+        let passing: &[(&str, &str, Option<Hook>)] =
+            &[("900_test", "ALTER TABLE events ADD COLUMN extra TEXT;", Some(|_| Ok(())))];
+        apply_migrations(&conn, passing).unwrap();
+        let applied: bool = conn
+            .query_row("SELECT EXISTS(SELECT 1 FROM _migrations WHERE name = '900_test')", [], |row| row.get(0))
+            .unwrap();
+        assert!(applied);
     }
 }
@@ -1,3 +1,5 @@
+pub mod dates;
+pub mod journal;
 pub mod migrations;
 pub mod models;
 
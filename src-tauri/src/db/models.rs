@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "desktop")]
+use super::dates::HistoricalDate;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Timeline {
@@ -10,6 +13,7 @@ pub struct Timeline {
     pub updated_at: String,
 }
 
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Track {
@@ -22,6 +26,7 @@ pub struct Track {
     pub created_at: String,
 }
 
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Event {
@@ -30,8 +35,8 @@ pub struct Event {
     pub track_id: String,
     pub title: String,
     pub description: String,
-    pub start_date: String,
-    pub end_date: Option<String>,
+    pub start_date: HistoricalDate,
+    pub end_date: Option<HistoricalDate>,
     pub event_type: String,
     pub importance: i32,
     pub color: Option<String>,
@@ -46,6 +51,20 @@ pub struct Event {
     pub updated_at: String,
 }
 
+/// An event whose stored start date cannot be read, so it is left out of
+/// event lists until a new date is set.
+#[cfg(feature = "desktop")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndatedEvent {
+    pub id: String,
+    pub track_id: String,
+    pub title: String,
+    /// The start date as stored.
+    pub start_date: String,
+}
+
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Connection {
@@ -59,6 +78,7 @@ pub struct Connection {
     pub created_at: String,
 }
 
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Setting {
@@ -77,7 +97,51 @@ pub struct Template {
     pub created_at: String,
 }
 
+/// State of an event before one of its updates. `changed_by`/`changed_at`
+/// describe the update that replaced this state.
+#[cfg(feature = "desktop")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRevision {
+    pub id: String,
+    pub event_id: String,
+    pub revision: i64,
+    pub track_id: String,
+    pub title: String,
+    pub description: String,
+    pub start_date: HistoricalDate,
+    pub end_date: Option<HistoricalDate>,
+    pub event_type: String,
+    pub importance: i32,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub image_path: Option<String>,
+    pub external_link: Option<String>,
+    pub tags: String,
+    pub source: Option<String>,
+    pub changed_by: Option<String>,
+    pub changed_at: String,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDiff {
+    pub field: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+#[cfg(feature = "desktop")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStatus {
+    pub undo_label: Option<String>,
+    pub redo_label: Option<String>,
+}
+
 // Input DTOs (what the frontend sends)
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateTimeline {
@@ -85,6 +149,7 @@ pub struct CreateTimeline {
     pub description: Option<String>,
 }
 
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateTimeline {
@@ -93,6 +158,7 @@ pub struct UpdateTimeline {
     pub description: Option<String>,
 }
 
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateTrack {
@@ -101,6 +167,7 @@ pub struct CreateTrack {
     pub color: Option<String>,
 }
 
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateTrack {
@@ -110,6 +177,7 @@ pub struct UpdateTrack {
     pub visible: Option<bool>,
 }
 
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateEvent {
@@ -129,6 +197,7 @@ pub struct CreateEvent {
     pub ai_confidence: Option<f64>,
 }
 
+#[cfg(feature = "desktop")]
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateEvent {
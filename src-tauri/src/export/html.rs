@@ -0,0 +1,89 @@
+use rusqlite::Connection;
+
+use super::layout::LayoutOptions;
+use super::svg::{escape_xml, generate_svg};
+use crate::commands::export::export_timeline;
+use crate::error::{AppError, AppResult};
+
+const VIEWER_CSS: &str = include_str!("viewer/viewer.css");
+const VIEWER_JS: &str = include_str!("viewer/viewer.js");
+
+/// Serialize the timeline for a `<script>` block. `<` is escaped so titles
+/// such as `</script>` cannot end the block early.
+fn embedded_json(value: &impl serde::Serialize) -> AppResult<String> {
+    let json = serde_json::to_string(value).map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(json.replace('<', "\\u003c"))
+}
+
+/// Generate a single self-contained HTML page for the timeline. The drawing
+/// is the same SVG `generate_svg` lays out; the inlined script adds pan and
+/// zoom, track toggles, search and a details panel fed by the embedded JSON.
+pub fn generate_html(conn: &Connection, timeline_id: &str) -> AppResult<String> {
+    let timeline = export_timeline(conn, timeline_id)?;
+    let svg = generate_svg(conn, timeline_id, &LayoutOptions::default())?;
+    let data = embedded_json(&timeline)?;
+    let title = escape_xml(&timeline.title);
+
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="generator" content="ChronoMap">
+<title>{title}</title>
+<style>
+{VIEWER_CSS}</style>
+</head>
+<body>
+<header>
+<h1>{title}</h1>
+<input id="search" type="search" placeholder="Search events" autocomplete="off">
+<span id="search-count"></span>
+<button id="reset-view" type="button" title="Reset zoom">Reset</button>
+</header>
+<main>
+<nav id="tracks"></nav>
+<div id="stage">
+{svg}
+</div>
+<aside id="details" hidden></aside>
+</main>
+<script type="application/json" id="timeline-data">{data}</script>
+<script>
+{VIEWER_JS}</script>
+</body>
+</html>
+"#
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_test_db;
+
+    #[test]
+    fn test_generate_html() {
+        let conn = init_test_db().unwrap();
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Moon & Back');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('tr', 'tl', 'Missions', '#3b82f6', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, description, start_date, importance, tags)
+             VALUES ('e1', 'tl', 'tr', 'Apollo 11', 'First **landing**. </script><b>', '1969-07-20', 5, 'nasa');",
+        )
+        .unwrap();
+
+        let html = generate_html(&conn, "tl").unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Moon &amp; Back</title>"));
+        // The SVG drawing is inlined with hooks for the viewer script
+        assert!(html.contains(r#"<g class="event" data-event-id="e1" data-track-id="tr">"#));
+        // Embedded data cannot close the script block
+        assert_eq!(html.matches("</script>").count(), 2);
+        assert!(html.contains(r#""description":"First **landing**. \u003c/script>\u003cb>""#));
+        // No external resources
+        assert!(!html.contains("src=\"http"));
+        assert!(!html.contains("<link"));
+    }
+}
@@ -0,0 +1,862 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::Connection;
+use serde::Deserialize;
+
+use crate::db::dates::HistoricalDate;
+use crate::error::{AppError, AppResult};
+
+/// Scale used when auto-fitting, matching the canvas at zoom 1.
+const DEFAULT_PIXELS_PER_DAY: f64 = 0.5;
+/// Auto-fit keeps the drawing between these widths, stretching short timelines
+/// and compressing long ones.
+const AUTO_MIN_WIDTH: f64 = 800.0;
+pub const AUTO_MAX_WIDTH: f64 = 4000.0;
+/// Minimum horizontal distance between axis labels.
+const MIN_TICK_SPACING: f64 = 90.0;
+/// Space for the timeline title above the tracks.
+const HEADER_HEIGHT: f64 = 40.0;
+const AXIS_HEIGHT: f64 = 40.0;
+const PADDING: f64 = 20.0;
+
+/// Space for the track name at the top of each track.
+const TRACK_HEADER: f64 = 18.0;
+const MIN_TRACK_HEIGHT: f64 = 60.0;
+/// Marker radius and bar height at the highest importance; rows are sized
+/// for these so marks of any importance line up.
+const MAX_MARKER_RADIUS: f64 = 8.0;
+const MAX_BAR_HEIGHT: f64 = 20.0;
+const LABEL_FONT_SIZE: f64 = 10.0;
+/// Height of one row of labels.
+const LABEL_ROW: f64 = 13.0;
+/// Label row plus bar plus spacing.
+const RANGE_ROW: f64 = LABEL_ROW + MAX_BAR_HEIGHT + 5.0;
+/// Minimum horizontal space between two labels or bars sharing a row.
+const GAP: f64 = 6.0;
+/// Point labels are stacked at most this many rows high; beyond that the
+/// least crowded row is reused.
+const MAX_LABEL_LEVELS: usize = 4;
+
+/// Parse a stored date (any precision, BCE or circa) to days since Unix epoch.
+fn date_to_epoch_days(date_str: &str) -> Option<f64> {
+    HistoricalDate::parse(date_str).ok().map(|d| d.sort_key())
+}
+
+/// Earliest and latest plausible epoch days for a stored date, widened for circa dates.
+fn date_bounds(date_str: &str) -> Option<(f64, f64)> {
+    HistoricalDate::parse(date_str).ok().map(|d| d.bounds())
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// Colors of the parts of a scene that do not come from tracks or events.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub background: &'static str,
+    pub title: &'static str,
+    pub text: &'static str,
+    pub muted: &'static str,
+    pub axis: &'static str,
+    pub connection: &'static str,
+    pub lane_opacity: f64,
+}
+
+impl Theme {
+    fn palette(self) -> Palette {
+        match self {
+            Theme::Light => Palette {
+                background: "#ffffff",
+                title: "#1f2937",
+                text: "#1f2937",
+                muted: "#6b7280",
+                axis: "#d1d5db",
+                connection: "#9ca3af",
+                lane_opacity: 0.1,
+            },
+            Theme::Dark => Palette {
+                background: "#111827",
+                title: "#f9fafb",
+                text: "#e5e7eb",
+                muted: "#9ca3af",
+                axis: "#4b5563",
+                connection: "#6b7280",
+                lane_opacity: 0.18,
+            },
+        }
+    }
+}
+
+/// What to draw and how large, shared by every exporter. The defaults draw
+/// every event, auto-fitted.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LayoutOptions {
+    /// Width of the drawing in pixels; `None` picks a scale from the time span.
+    pub width: Option<f64>,
+    /// Start of the time window to draw, as a stored date. Defaults to the earliest event.
+    pub start: Option<String>,
+    /// End of the time window to draw. Defaults to the latest event.
+    pub end: Option<String>,
+    pub theme: Theme,
+    pub font_family: String,
+    /// Leave out tracks that are hidden in the app.
+    pub visible_tracks_only: bool,
+    pub min_importance: i32,
+    /// Only draw events carrying at least one of these tags; empty draws all.
+    pub tags: Vec<String>,
+    /// Leave the background unpainted. Ignored when printing to paper.
+    pub transparent_background: bool,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            width: None,
+            start: None,
+            end: None,
+            theme: Theme::Light,
+            font_family: "sans-serif".to_string(),
+            visible_tracks_only: false,
+            min_importance: 1,
+            tags: Vec::new(),
+            transparent_background: false,
+        }
+    }
+}
+
+impl LayoutOptions {
+    /// The requested window in epoch days, rejecting unparseable or reversed dates.
+    fn window(&self) -> AppResult<(Option<f64>, Option<f64>)> {
+        let start = HistoricalDate::parse_optional(self.start.as_deref())?.map(|d| d.bounds().0);
+        let end = HistoricalDate::parse_optional(self.end.as_deref())?.map(|d| d.bounds().1);
+        if let (Some(s), Some(e)) = (start, end) {
+            if s >= e {
+                return Err(AppError::Validation("Export window must end after it starts".to_string()));
+            }
+        }
+        Ok((start, end))
+    }
+}
+
+/// Epoch day of a calendar date, if chrono can represent it.
+fn day_of(date: NaiveDate) -> f64 {
+    HistoricalDate::from_naive_date(date).sort_key()
+}
+
+fn date_of(epoch_days: f64) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(1970, 1, 1)?.checked_add_signed(Duration::try_days(epoch_days.floor() as i64)?)
+}
+
+/// Labelled axis ticks between `min_days` and `max_days`, at the finest of
+/// day, month or "round" year steps that keeps them `min_gap` days apart.
+pub fn axis_ticks(min_days: f64, max_days: f64, min_gap: f64) -> Vec<(f64, String)> {
+    let (Some(first), Some(last)) = (date_of(min_days), date_of(max_days)) else {
+        return Vec::new();
+    };
+    let mut ticks = Vec::new();
+
+    if let Some(step) = [1, 2, 7, 14].into_iter().find(|&s| s as f64 >= min_gap) {
+        let mut date = first;
+        while date <= last {
+            let day = day_of(date);
+            if day >= min_days {
+                ticks.push((day, HistoricalDate::from_naive_date(date).to_string()));
+            }
+            date = match date.checked_add_signed(Duration::days(step)) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+    } else if let Some(step) = [1, 3, 6].into_iter().find(|&s| s as f64 * 30.4 >= min_gap) {
+        let mut month = first.year() * 12 + first.month0() as i32;
+        month -= month.rem_euclid(step);
+        while let Some(date) = NaiveDate::from_ymd_opt(month.div_euclid(12), month.rem_euclid(12) as u32 + 1, 1) {
+            if date > last {
+                break;
+            }
+            let day = day_of(date);
+            if day >= min_days {
+                if let Some(label) = HistoricalDate::from_year_month(date.year(), date.month()) {
+                    ticks.push((day, label.to_string()));
+                }
+            }
+            month += step;
+        }
+    } else {
+        let step = (0..30u32)
+            .map(|i| [1, 2, 5][i as usize % 3] * 10i64.pow(i / 3))
+            .find(|&s| s as f64 * 365.25 >= min_gap)
+            .unwrap_or(1_000_000)
+            .min(1_000_000) as i32;
+        // Tick k is year k * step, counted back from 1 BCE for k < 0, so
+        // years BCE are round as written (5000 BCE, not 5001 BCE). There is
+        // no year zero, so k = 0 is skipped.
+        let year_of = |k: i32| if k < 0 { 1 + k * step } else { k * step };
+        let mut k = match first.year() {
+            year if year > 0 => year / step,
+            year => -((step - year) / step),
+        };
+        loop {
+            if k == 0 {
+                k = 1;
+            }
+            let year = year_of(k);
+            let Some(date) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+                break;
+            };
+            if date > last {
+                break;
+            }
+            let day = day_of(date);
+            if day >= min_days {
+                if let Some(label) = HistoricalDate::from_year(year) {
+                    ticks.push((day, label.to_string()));
+                }
+            }
+            k += 1;
+        }
+    }
+    ticks
+}
+
+/// Rough width of a label, good enough to keep labels apart without font metrics.
+pub fn text_width(text: &str, font_size: f64) -> f64 {
+    text.chars().count() as f64 * font_size * 0.58
+}
+
+/// Marker radius for a point event of the given importance (1-5), growing
+/// with importance as on the canvas.
+fn marker_radius(importance: i32) -> f64 {
+    3.0 + importance.clamp(1, 5) as f64
+}
+
+fn bar_height(importance: i32) -> f64 {
+    10.0 + 2.0 * importance.clamp(1, 5) as f64
+}
+
+/// One event to place. Points have no `end_x`; ranges span `x..end_x`.
+#[derive(Debug, Clone, Copy)]
+pub struct MarkInput {
+    pub x: f64,
+    pub end_x: Option<f64>,
+    /// Marker radius for points, bar height for ranges.
+    pub size: f64,
+    pub label_width: f64,
+}
+
+/// Where an event ended up, relative to the top of its track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedMark {
+    /// Anchor of the mark: the marker center, or the left middle of a bar.
+    /// Marks of the same row share their `y` whatever their size.
+    pub x: f64,
+    pub y: f64,
+    /// Bar width for ranges; `None` for point markers.
+    pub bar_width: Option<f64>,
+    /// Center and baseline of the label.
+    pub label_x: f64,
+    pub label_y: f64,
+    /// Set when a point label was lifted away from its marker: the line from
+    /// the top of the marker up to the label.
+    pub leader: Option<(f64, f64)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackLayout {
+    pub height: f64,
+    /// One entry per input, in input order.
+    pub marks: Vec<PlacedMark>,
+}
+
+/// Assign each span the lowest row in which it does not overlap any span
+/// already placed there. Returns the row per span (input order) and the row count.
+pub fn assign_rows(spans: &[(f64, f64)]) -> (Vec<usize>, usize) {
+    let mut order: Vec<usize> = (0..spans.len()).collect();
+    order.sort_by(|&a, &b| spans[a].0.total_cmp(&spans[b].0));
+
+    let mut row_ends: Vec<f64> = Vec::new();
+    let mut rows = vec![0; spans.len()];
+    for i in order {
+        let (start, end) = spans[i];
+        let row = match row_ends.iter().position(|&e| start >= e + GAP) {
+            Some(row) => row,
+            None => {
+                row_ends.push(f64::NEG_INFINITY);
+                row_ends.len() - 1
+            }
+        };
+        row_ends[row] = end;
+        rows[i] = row;
+    }
+    (rows, row_ends.len())
+}
+
+/// Lay out the events of one track without overlapping marks or labels.
+///
+/// From top to bottom a track holds its name, the staggered labels of point
+/// events, the row of point markers, then one sub-lane per set of
+/// non-overlapping ranges. The track grows as tall as that needs.
+pub fn layout_track(items: &[MarkInput]) -> TrackLayout {
+    let points: Vec<usize> = (0..items.len()).filter(|&i| items[i].end_x.is_none()).collect();
+    let ranges: Vec<usize> = (0..items.len()).filter(|&i| items[i].end_x.is_some()).collect();
+
+    // Point labels: lowest level that is free, otherwise the least crowded one
+    let mut order = points.clone();
+    order.sort_by(|&a, &b| items[a].x.total_cmp(&items[b].x));
+    let mut level_ends: Vec<f64> = Vec::new();
+    let mut levels = vec![0; items.len()];
+    for &i in &order {
+        let half = items[i].label_width / 2.0;
+        let (left, right) = (items[i].x - half, items[i].x + half);
+        let level = match level_ends.iter().position(|&e| left >= e + GAP) {
+            Some(level) => level,
+            None if level_ends.len() < MAX_LABEL_LEVELS => {
+                level_ends.push(f64::NEG_INFINITY);
+                level_ends.len() - 1
+            }
+            None => (0..level_ends.len())
+                .min_by(|&a, &b| level_ends[a].total_cmp(&level_ends[b]))
+                .unwrap_or(0),
+        };
+        level_ends[level] = level_ends[level].max(right);
+        levels[i] = level;
+    }
+
+    let mut marks = vec![
+        PlacedMark { x: 0.0, y: 0.0, bar_width: None, label_x: 0.0, label_y: 0.0, leader: None };
+        items.len()
+    ];
+    let mut cursor = TRACK_HEADER;
+
+    if !points.is_empty() {
+        let marker_y = cursor + level_ends.len() as f64 * LABEL_ROW + MAX_MARKER_RADIUS;
+        for &i in &points {
+            let label_y = marker_y - MAX_MARKER_RADIUS - 4.0 - levels[i] as f64 * LABEL_ROW;
+            marks[i] = PlacedMark {
+                x: items[i].x,
+                y: marker_y,
+                bar_width: None,
+                label_x: items[i].x,
+                label_y,
+                leader: (levels[i] > 0).then_some((marker_y - items[i].size, label_y + 2.0)),
+            };
+        }
+        cursor = marker_y + MAX_MARKER_RADIUS + 6.0;
+    }
+
+    if !ranges.is_empty() {
+        // A range claims the wider of its bar and its centered label
+        let spans: Vec<(f64, f64)> = ranges
+            .iter()
+            .map(|&i| {
+                let end = items[i].end_x.unwrap_or(items[i].x);
+                let center = (items[i].x + end) / 2.0;
+                let half = items[i].label_width / 2.0;
+                (items[i].x.min(center - half), end.max(center + half))
+            })
+            .collect();
+        let (rows, row_count) = assign_rows(&spans);
+        for (&i, &row) in ranges.iter().zip(&rows) {
+            let end = items[i].end_x.unwrap_or(items[i].x);
+            let bar_width = (end - items[i].x).max(4.0);
+            let top = cursor + row as f64 * RANGE_ROW;
+            let y = top + LABEL_ROW + MAX_BAR_HEIGHT / 2.0;
+            marks[i] = PlacedMark {
+                x: items[i].x,
+                y,
+                bar_width: Some(bar_width),
+                label_x: items[i].x + bar_width / 2.0,
+                label_y: y - items[i].size / 2.0 - 3.0,
+                leader: None,
+            };
+        }
+        cursor += row_count as f64 * RANGE_ROW;
+    }
+
+    TrackLayout {
+        height: (cursor + 6.0).max(MIN_TRACK_HEIGHT),
+        marks,
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Start,
+    Middle,
+}
+
+/// A piece of text; `y` is the baseline.
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub x: f64,
+    pub y: f64,
+    pub text: String,
+    pub size: f64,
+    pub anchor: Anchor,
+    pub bold: bool,
+}
+
+impl Label {
+    fn new(x: f64, y: f64, text: &str, size: f64) -> Self {
+        Self { x, y, text: text.to_string(), size, anchor: Anchor::Middle, bold: false }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Circle { cx: f64, cy: f64, r: f64 },
+    /// Milestones; `r` is the distance from the center to each corner.
+    Diamond { cx: f64, cy: f64, r: f64 },
+    Bar { x: f64, y: f64, width: f64, height: f64 },
+}
+
+/// The background band of a track and its name.
+#[derive(Debug, Clone)]
+pub struct Lane {
+    pub track_id: String,
+    pub color: String,
+    pub y: f64,
+    pub height: f64,
+    pub label: Label,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventMark {
+    pub event_id: String,
+    pub track_id: String,
+    pub color: String,
+    pub shape: Shape,
+    pub label: Label,
+    /// Vertical line from the marker up to a lifted label, as (x, y1, y2).
+    pub leader: Option<(f64, f64, f64)>,
+}
+
+/// A quadratic curve from the source event to the target event.
+#[derive(Debug, Clone)]
+pub struct ConnectionPath {
+    pub source_id: String,
+    pub target_id: String,
+    pub from: (f64, f64),
+    pub control: (f64, f64),
+    pub to: (f64, f64),
+    pub label: Option<Label>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Axis {
+    pub y: f64,
+    pub x1: f64,
+    pub x2: f64,
+    /// Tick position and its label.
+    pub ticks: Vec<(f64, Label)>,
+}
+
+/// A fully positioned drawing of a timeline, in pixels with y pointing down.
+/// Exporters only translate it into their output format.
+#[derive(Debug, Clone)]
+pub struct Scene {
+    pub width: f64,
+    pub height: f64,
+    pub palette: Palette,
+    pub font_family: String,
+    pub title: Label,
+    pub lanes: Vec<Lane>,
+    pub events: Vec<EventMark>,
+    pub connections: Vec<ConnectionPath>,
+    pub axis: Axis,
+}
+
+struct TrackRow {
+    id: String,
+    name: String,
+    color: String,
+}
+
+struct EventRow {
+    id: String,
+    title: String,
+    start_date: String,
+    end_date: Option<String>,
+    event_type: String,
+    track_id: String,
+    importance: i32,
+    tags: String,
+    color: Option<String>,
+}
+
+struct ConnectionRow {
+    source_event_id: String,
+    target_event_id: String,
+    label: Option<String>,
+}
+
+/// Read a timeline and lay it out as a scene according to `options`.
+pub fn build_scene(conn: &Connection, timeline_id: &str, options: &LayoutOptions) -> AppResult<Scene> {
+    if let Some(w) = options.width {
+        if !w.is_finite() || w < 200.0 {
+            return Err(AppError::Validation("Export width must be at least 200 pixels".to_string()));
+        }
+    }
+    let (window_start, window_end) = options.window()?;
+
+    let title: String = conn
+        .query_row(
+            "SELECT title FROM timelines WHERE id = ?1",
+            [timeline_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| AppError::NotFound(format!("Timeline {timeline_id} not found")))?;
+
+    let mut track_stmt = conn.prepare(
+        "SELECT id, name, color FROM tracks WHERE timeline_id = ?1 AND (visible = 1 OR ?2 = 0) ORDER BY sort_order",
+    )?;
+    let tracks: Vec<TrackRow> = track_stmt
+        .query_map(rusqlite::params![timeline_id, options.visible_tracks_only], |row| {
+            Ok(TrackRow {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let track_index: HashMap<&str, usize> = tracks
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (t.id.as_str(), i))
+        .collect();
+
+    let mut event_stmt = conn.prepare(
+        "SELECT id, title, start_date, end_date, event_type, track_id, importance, tags, NULLIF(TRIM(color), '')
+         FROM events WHERE timeline_id = ?1 ORDER BY start_sort_key, start_date",
+    )?;
+    let events: Vec<EventRow> = event_stmt
+        .query_map([timeline_id], |row| {
+            Ok(EventRow {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                start_date: row.get(2)?,
+                end_date: row.get(3)?,
+                event_type: row.get(4)?,
+                track_id: row.get(5)?,
+                importance: row.get(6)?,
+                tags: row.get(7)?,
+                color: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Apply the filters: shown track, importance, tags and overlap with the window
+    let wanted_tags: HashSet<String> = options.tags.iter().map(|t| t.trim().to_lowercase()).collect();
+    let events: Vec<EventRow> = events
+        .into_iter()
+        .filter(|e| {
+            if options.visible_tracks_only && !track_index.contains_key(e.track_id.as_str()) {
+                return false;
+            }
+            if e.importance < options.min_importance {
+                return false;
+            }
+            if !wanted_tags.is_empty()
+                && !e.tags.split(',').any(|t| wanted_tags.contains(&t.trim().to_lowercase()))
+            {
+                return false;
+            }
+            let Some((start, _)) = date_bounds(&e.start_date) else {
+                return true;
+            };
+            let end = e.end_date.as_deref().and_then(date_bounds).map_or(start, |b| b.1);
+            window_start.is_none_or(|w| end >= w) && window_end.is_none_or(|w| start <= w)
+        })
+        .collect();
+
+    let mut conn_stmt = conn.prepare(
+        "SELECT source_event_id, target_event_id, label FROM connections WHERE timeline_id = ?1",
+    )?;
+    let connections: Vec<ConnectionRow> = conn_stmt
+        .query_map([timeline_id], |row| {
+            Ok(ConnectionRow {
+                source_event_id: row.get(0)?,
+                target_event_id: row.get(1)?,
+                label: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Time span: the window, or every start and end widened for precision and circa margins
+    let bounds: Vec<(f64, f64)> = events
+        .iter()
+        .flat_map(|e| std::iter::once(&e.start_date).chain(e.end_date.as_ref()))
+        .filter_map(|d| date_bounds(d))
+        .collect();
+    let min_days = window_start.unwrap_or_else(|| bounds.iter().map(|b| b.0).fold(f64::INFINITY, f64::min));
+    let max_days = window_end.unwrap_or_else(|| bounds.iter().map(|b| b.1).fold(f64::NEG_INFINITY, f64::max));
+
+    // Handle empty or single-event timelines
+    let (min_days, max_days) = if min_days.is_infinite() || max_days.is_infinite() {
+        let anchor = if min_days.is_finite() { min_days } else if max_days.is_finite() { max_days - 100.0 } else { 0.0 };
+        (anchor, anchor + 100.0)
+    } else if (max_days - min_days).abs() < 1.0 {
+        (min_days - 50.0, max_days + 50.0)
+    } else {
+        (min_days, max_days)
+    };
+
+    let span_days = max_days - min_days;
+    let width = options.width.unwrap_or_else(|| {
+        (span_days * DEFAULT_PIXELS_PER_DAY + PADDING * 2.0).clamp(AUTO_MIN_WIDTH, AUTO_MAX_WIDTH)
+    });
+    let pixels_per_day = (width - PADDING * 2.0) / span_days;
+    let x_of = |days: f64| (days.clamp(min_days, max_days) - min_days) * pixels_per_day + PADDING;
+
+    // Lay out each track; events on unknown tracks join the first one
+    let num_tracks = tracks.len().max(1);
+    let mut track_events: Vec<Vec<(&EventRow, MarkInput)>> = (0..num_tracks).map(|_| Vec::new()).collect();
+    for event in &events {
+        let Some(start_days) = date_to_epoch_days(&event.start_date) else {
+            continue;
+        };
+        let (end_x, size) = match event.event_type.as_str() {
+            "range" | "era" => (Some(x_of(
+                event
+                    .end_date
+                    .as_deref()
+                    .and_then(date_to_epoch_days)
+                    .unwrap_or(start_days + 30.0),
+            )), bar_height(event.importance)),
+            _ => (None, marker_radius(event.importance)),
+        };
+        let track_idx = track_index.get(event.track_id.as_str()).copied().unwrap_or(0);
+        track_events[track_idx].push((
+            event,
+            MarkInput {
+                x: x_of(start_days),
+                end_x,
+                size,
+                label_width: text_width(&event.title, LABEL_FONT_SIZE),
+            },
+        ));
+    }
+
+    let mut lanes = Vec::with_capacity(tracks.len());
+    let mut marks = Vec::with_capacity(events.len());
+    let mut top = HEADER_HEIGHT;
+    for (track_idx, items) in track_events.iter().enumerate() {
+        let layout = layout_track(&items.iter().map(|(_, m)| *m).collect::<Vec<_>>());
+        let color = tracks.get(track_idx).map_or("#6b7280", |t| t.color.as_str());
+        if let Some(track) = tracks.get(track_idx) {
+            lanes.push(Lane {
+                track_id: track.id.clone(),
+                color: track.color.clone(),
+                y: top,
+                height: layout.height,
+                label: Label {
+                    anchor: Anchor::Start,
+                    ..Label::new(5.0, top + 14.0, &track.name, 11.0)
+                },
+            });
+        }
+        for ((event, input), mark) in items.iter().zip(&layout.marks) {
+            let y = top + mark.y;
+            let shape = match mark.bar_width {
+                Some(width) => Shape::Bar { x: mark.x, y: y - input.size / 2.0, width, height: input.size },
+                None if event.event_type == "milestone" => Shape::Diamond { cx: mark.x, cy: y, r: input.size * 1.25 },
+                None => Shape::Circle { cx: mark.x, cy: y, r: input.size },
+            };
+            marks.push(EventMark {
+                event_id: event.id.clone(),
+                track_id: event.track_id.clone(),
+                // An event's own color overrides its track's
+                color: event.color.clone().unwrap_or_else(|| color.to_string()),
+                shape,
+                label: Label::new(mark.label_x, top + mark.label_y, &event.title, LABEL_FONT_SIZE),
+                leader: mark.leader.map(|(y1, y2)| (mark.x, top + y1, top + y2)),
+            });
+        }
+        top += layout.height;
+    }
+
+    // Connections arc above the higher of their two ends
+    let anchors: HashMap<&str, (f64, f64)> = marks
+        .iter()
+        .map(|m| {
+            let anchor = match m.shape {
+                Shape::Circle { cx, cy, .. } | Shape::Diamond { cx, cy, .. } => (cx, cy),
+                Shape::Bar { x, y, height, .. } => (x, y + height / 2.0),
+            };
+            (m.event_id.as_str(), anchor)
+        })
+        .collect();
+    let connections: Vec<ConnectionPath> = connections
+        .iter()
+        .filter_map(|c| {
+            let from = *anchors.get(c.source_event_id.as_str())?;
+            let to = *anchors.get(c.target_event_id.as_str())?;
+            let control = ((from.0 + to.0) / 2.0, from.1.min(to.1) - 30.0);
+            Some(ConnectionPath {
+                source_id: c.source_event_id.clone(),
+                target_id: c.target_event_id.clone(),
+                from,
+                control,
+                to,
+                label: c.label.as_deref().map(|l| Label::new(control.0, control.1 - 4.0, l, 9.0)),
+            })
+        })
+        .collect();
+
+    // Time axis at the bottom, ticked at a calendar step that keeps labels apart
+    let axis_y = top + 10.0;
+    let ticks = axis_ticks(min_days, max_days, MIN_TICK_SPACING / pixels_per_day)
+        .into_iter()
+        .map(|(day, text)| {
+            let x = x_of(day);
+            (x, Label::new(x, axis_y + 18.0, &text, 10.0))
+        })
+        .collect();
+
+    let font_family = match options.font_family.trim() {
+        "" => "sans-serif".to_string(),
+        font => font.to_string(),
+    };
+    Ok(Scene {
+        width,
+        height: (top + AXIS_HEIGHT + PADDING * 2.0).max(200.0),
+        palette: options.theme.palette(),
+        font_family,
+        title: Label {
+            bold: true,
+            ..Label::new(width / 2.0, HEADER_HEIGHT / 2.0 + 6.0, &title, 18.0)
+        },
+        lanes,
+        events: marks,
+        connections,
+        axis: Axis { y: axis_y, x1: PADDING, x2: width - PADDING, ticks },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_to_epoch_days() {
+        // 1970-01-01 should be day 0
+        assert_eq!(date_to_epoch_days("1970-01-01"), Some(0.0));
+        // 1970-01-02 should be day 1
+        assert_eq!(date_to_epoch_days("1970-01-02"), Some(1.0));
+        // Invalid date
+        assert_eq!(date_to_epoch_days("not-a-date"), None);
+        // ISO datetime
+        assert!(date_to_epoch_days("2000-01-01T12:00:00").is_some());
+    }
+
+    #[test]
+    fn test_bce_ticks_are_round_years() {
+        let days = |date: &str| date_to_epoch_days(date).unwrap();
+        let ticks = axis_ticks(days("1000 BCE"), days("1000"), 400.0 * 365.25);
+        let labels: Vec<&str> = ticks.iter().map(|t| t.1.as_str()).collect();
+        assert_eq!(labels, vec!["1000 BCE", "500 BCE", "500", "1000"]);
+    }
+
+    #[test]
+    fn test_overlapping_ranges_get_sub_lanes() {
+        let (rows, count) = assign_rows(&[(0.0, 100.0), (50.0, 150.0), (120.0, 200.0), (300.0, 310.0)]);
+        assert_eq!(rows, vec![0, 1, 0, 0]);
+        assert_eq!(count, 2);
+
+        let layout = layout_track(&[
+            MarkInput { x: 0.0, end_x: Some(100.0), size: 16.0, label_width: 20.0 },
+            MarkInput { x: 50.0, end_x: Some(150.0), size: 16.0, label_width: 20.0 },
+        ]);
+        let (a, b) = (layout.marks[0], layout.marks[1]);
+        assert!(b.y - a.y >= MAX_BAR_HEIGHT);
+        assert!(layout.height > MIN_TRACK_HEIGHT);
+    }
+
+    #[test]
+    fn test_point_labels_are_staggered() {
+        let item = |x| MarkInput { x, end_x: None, size: 6.0, label_width: 60.0 };
+        let layout = layout_track(&[item(100.0), item(110.0), item(300.0)]);
+        let marks = &layout.marks;
+
+        // All markers share one row; the crowded label moves up with a leader line
+        assert_eq!(marks[0].y, marks[1].y);
+        assert_eq!(marks[0].y, marks[2].y);
+        assert!(marks[1].label_y < marks[0].label_y);
+        assert!(marks[0].leader.is_none());
+        assert_eq!(marks[1].leader, Some((marks[1].y - 6.0, marks[1].label_y + 2.0)));
+        // The far-away label stays next to its marker
+        assert_eq!(marks[2].label_y, marks[0].label_y);
+        // Labels never rise into the track name
+        assert!(marks[1].label_y - LABEL_FONT_SIZE >= TRACK_HEADER - 1.0);
+    }
+
+    #[test]
+    fn test_build_scene() {
+        let conn = crate::db::init_test_db().unwrap();
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Scene');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('t1', 'tl', 'One', '#ef4444', 0),
+                                                                                   ('t2', 'tl', 'Two', '#3b82f6', 1);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, end_date, event_type, importance, tags)
+             VALUES ('a', 'tl', 't1', 'Point', '1900', NULL, 'point', 3, ''),
+                    ('b', 'tl', 't2', 'Range', '1910', '1920', 'range', 3, '');
+             INSERT INTO connections (id, timeline_id, source_event_id, target_event_id, connection_type, label)
+             VALUES ('c', 'tl', 'a', 'b', 'caused', 'led to');",
+        )
+        .unwrap();
+
+        let scene = build_scene(&conn, "tl", &LayoutOptions { width: Some(1000.0), ..Default::default() }).unwrap();
+        assert_eq!(scene.width, 1000.0);
+        assert_eq!(scene.title.text, "Scene");
+
+        // Lanes stack without gaps below the title
+        assert_eq!(scene.lanes.len(), 2);
+        assert_eq!(scene.lanes[0].y, HEADER_HEIGHT);
+        assert_eq!(scene.lanes[1].y, scene.lanes[0].y + scene.lanes[0].height);
+        assert!(scene.axis.y > scene.lanes[1].y + scene.lanes[1].height);
+
+        // The connection runs between the two marks it links
+        let Shape::Circle { cx, cy, .. } = scene.events[0].shape else { panic!("expected a marker") };
+        let Shape::Bar { x, y, height, .. } = scene.events[1].shape else { panic!("expected a bar") };
+        let path = &scene.connections[0];
+        assert_eq!(path.from, (cx, cy));
+        assert_eq!(path.to, (x, y + height / 2.0));
+        assert_eq!(path.label.as_ref().map(|l| l.text.as_str()), Some("led to"));
+        assert!(x > cx && x < scene.width - PADDING);
+    }
+
+    #[test]
+    fn test_importance_and_event_color_style_marks() {
+        let conn = crate::db::init_test_db().unwrap();
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Styles');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('t1', 'tl', 'One', '#ef4444', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, event_type, importance, color, tags)
+             VALUES ('minor', 'tl', 't1', 'Minor', '1900', 'point', 1, NULL, ''),
+                    ('major', 'tl', 't1', 'Major', '1950', 'point', 5, '#10b981', ''),
+                    ('mile', 'tl', 't1', 'Milestone', '2000', 'milestone', 3, '', '');",
+        )
+        .unwrap();
+
+        let scene = build_scene(&conn, "tl", &LayoutOptions::default()).unwrap();
+        let Shape::Circle { cy: minor_y, r: minor, .. } = scene.events[0].shape else { panic!("expected a marker") };
+        let Shape::Circle { cy: major_y, r: major, .. } = scene.events[1].shape else { panic!("expected a marker") };
+        assert!(major > minor);
+        assert_eq!(major_y, minor_y);
+        assert!(matches!(scene.events[2].shape, Shape::Diamond { .. }));
+
+        // An event color overrides the track color; a blank one does not
+        assert_eq!(scene.events[0].color, "#ef4444");
+        assert_eq!(scene.events[1].color, "#10b981");
+        assert_eq!(scene.events[2].color, "#ef4444");
+    }
+}
@@ -1,2 +1,7 @@
+#[cfg(feature = "desktop")]
+pub mod html;
+pub mod layout;
 pub mod pdf;
+pub mod pdf_outline;
+pub mod png;
 pub mod svg;
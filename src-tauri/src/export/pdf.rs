@@ -1,31 +1,146 @@
 use std::collections::HashMap;
 
-use chrono::Timelike;
-use rusqlite::Connection;
-
+use printpdf::path::{PaintMode, WindingOrder};
+use printpdf::{
+    Actions, BorderArray, Color, ColorArray, HighlightingMode, IndirectFontRef, Line, LinkAnnotation,
+    PdfDocument, PdfDocumentReference, PdfLayerReference, Point, Polygon, Rect, Rgb,
+};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Deserialize;
+
+use super::layout::{build_scene, text_width, Anchor, EventMark, Label, LayoutOptions, Scene, Shape};
+use super::pdf_outline::{finish, Destination, OutlineItem, EVENT_LINK_PREFIX};
+use crate::commands::export::{export_timeline, ExportedEvent};
+use crate::db::dates::{DatePrecision, HistoricalDate};
 use crate::error::{AppError, AppResult};
 
-/// Parse a date string (YYYY-MM-DD or ISO datetime) to days since Unix epoch.
-fn date_to_epoch_days(date_str: &str) -> Option<f64> {
-    if let Ok(nd) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-        let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?;
-        return Some((nd - epoch).num_days() as f64);
-    }
-    if let Ok(ndt) = chrono::NaiveDateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S") {
-        let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?;
-        let days = (ndt.date() - epoch).num_days() as f64;
-        let frac = ndt.time().num_seconds_from_midnight() as f64 / 86400.0;
-        return Some(days + frac);
-    }
-    if let Some(stripped) = date_str.get(..19) {
-        if let Ok(ndt) = chrono::NaiveDateTime::parse_from_str(stripped, "%Y-%m-%dT%H:%M:%S") {
-            let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?;
-            let days = (ndt.date() - epoch).num_days() as f64;
-            let frac = ndt.time().num_seconds_from_midnight() as f64 / 86400.0;
-            return Some(days + frac);
+/// Space for the running header on timeline pages.
+const PAGE_HEADER: f64 = 8.0;
+/// Millimetres per scene pixel at full size (one pixel per point).
+const MM_PER_PX: f64 = 0.3528;
+/// Event cards in the appendix flow down two columns per page.
+const CARD_COLUMNS: usize = 2;
+const CARD_COLUMN_GAP: f64 = 8.0;
+const CARD_GAP: f64 = 4.0;
+const CARD_PADDING: f64 = 3.0;
+/// Room left of the card text for the event's color stripe.
+const CARD_INDENT: f64 = 4.0;
+const AI_BADGE_COLOR: &str = "#7c3aed";
+
+/// Bundled so Latin, Greek and Cyrillic text renders without configuration;
+/// scripts DejaVu lacks (CJK) need a font set in `pdf_font_path`.
+pub const FALLBACK_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+pub const FALLBACK_FONT_BOLD: &[u8] = include_bytes!("../../fonts/DejaVuSans-Bold.ttf");
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageSize {
+    A4,
+    A3,
+    #[default]
+    Letter,
+}
+
+impl PageSize {
+    /// Portrait width and height in millimetres.
+    fn dimensions(self) -> (f64, f64) {
+        match self {
+            PageSize::A4 => (210.0, 297.0),
+            PageSize::A3 => (297.0, 420.0),
+            PageSize::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    Portrait,
+    #[default]
+    Landscape,
+}
+
+/// Page geometry in millimetres.
+#[derive(Debug, Clone, Copy)]
+struct Page {
+    width: f64,
+    height: f64,
+    margin: f64,
+}
+
+impl Page {
+    fn usable_width(&self) -> f64 {
+        self.width - self.margin * 2.0
+    }
+
+    /// Top of the area below the running header.
+    fn content_top(&self) -> f64 {
+        self.height - self.margin - PAGE_HEADER
+    }
+
+    fn usable_height(&self) -> f64 {
+        self.content_top() - self.margin
+    }
+
+    fn card_column_width(&self) -> f64 {
+        (self.usable_width() - CARD_COLUMN_GAP * (CARD_COLUMNS - 1) as f64) / CARD_COLUMNS as f64
+    }
+}
+
+/// How the outline groups events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutlineGrouping {
+    #[default]
+    Year,
+    Track,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PdfOptions {
+    /// Add a card per event with its full details, linked from the timeline
+    /// pages.
+    pub appendix: bool,
+    pub outline: OutlineGrouping,
+    pub page_size: PageSize,
+    pub orientation: Orientation,
+    /// Margin on every side, in millimetres.
+    pub margin: f64,
+    /// Shrink the whole timeline onto one page instead of splitting it into
+    /// page-wide strips.
+    pub fit_to_page: bool,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            appendix: false,
+            outline: OutlineGrouping::default(),
+            page_size: PageSize::default(),
+            orientation: Orientation::default(),
+            margin: 20.0,
+            fit_to_page: false,
         }
     }
-    None
+}
+
+impl PdfOptions {
+    fn page(&self) -> AppResult<Page> {
+        let (short, long) = self.page_size.dimensions();
+        let (width, height) = match self.orientation {
+            Orientation::Portrait => (short, long),
+            Orientation::Landscape => (long, short),
+        };
+        let page = Page { width, height, margin: self.margin };
+        if !(0.0..=50.0).contains(&self.margin) || page.usable_height() < 60.0 {
+            return Err(AppError::Validation(format!(
+                "Margin must be between 0 and 50 mm, got {}",
+                self.margin
+            )));
+        }
+        Ok(page)
+    }
 }
 
 /// Parse a hex color like "#ef4444" to (r, g, b) as f32 0.0-1.0.
@@ -40,18 +155,17 @@ fn hex_to_rgb(hex: &str) -> (f32, f32, f32) {
     (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
 }
 
-struct TrackInfo {
-    id: String,
-    name: String,
-    color: String,
+/// `color` drawn at `opacity` over `background`, since PDF fills here are opaque.
+fn blend(background: &str, color: &str, opacity: f64) -> Color {
+    let (br, bg, bb) = hex_to_rgb(background);
+    let (r, g, b) = hex_to_rgb(color);
+    let a = opacity as f32;
+    Color::Rgb(Rgb::new(br + (r - br) * a, bg + (g - bg) * a, bb + (b - bb) * a, None))
 }
 
-struct EventInfo {
-    title: String,
-    start_date: String,
-    end_date: Option<String>,
-    event_type: String,
-    track_id: String,
+fn rgb(hex: &str) -> Color {
+    let (r, g, b) = hex_to_rgb(hex);
+    Color::Rgb(Rgb::new(r, g, b, None))
 }
 
 /// Shorthand to create Mm from f64.
@@ -59,115 +173,525 @@ fn mm(v: f64) -> printpdf::Mm {
     printpdf::Mm(v as f32)
 }
 
-/// Generate a PDF as bytes for the given timeline.
-pub fn generate_pdf(conn: &Connection, timeline_id: &str) -> AppResult<Vec<u8>> {
-    use printpdf::path::{PaintMode, WindingOrder};
-    use printpdf::*;
+/// Scene bounds (x1, y1, x2, y2) of an event's shape and label together.
+fn mark_bounds(mark: &EventMark) -> (f64, f64, f64, f64) {
+    let (x1, y1, x2, y2) = match mark.shape {
+        Shape::Bar { x, y, width, height } => (x, y, x + width, y + height),
+        Shape::Circle { cx, cy, r } | Shape::Diamond { cx, cy, r } => (cx - r, cy - r, cx + r, cy + r),
+    };
+    let label = &mark.label;
+    let width = text_width(&label.text, label.size);
+    let lx = match label.anchor {
+        Anchor::Start => label.x,
+        Anchor::Middle => label.x - width / 2.0,
+    };
+    (x1.min(lx), y1.min(label.y - label.size), x2.max(lx + width), y2.max(label.y))
+}
 
-    // Fetch timeline metadata
-    let (title, description): (String, String) = conn
-        .query_row(
-            "SELECT title, description FROM timelines WHERE id = ?1",
-            [timeline_id],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
-        .map_err(|_| AppError::NotFound(format!("Timeline {timeline_id} not found")))?;
-
-    // Fetch tracks
-    let mut track_stmt = conn.prepare(
-        "SELECT id, name, color FROM tracks WHERE timeline_id = ?1 ORDER BY sort_order",
-    )?;
-    let tracks: Vec<TrackInfo> = track_stmt
-        .query_map([timeline_id], |row| {
-            Ok(TrackInfo {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                color: row.get(2)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
+/// Greedy word wrap of `text` at `size` points into lines at most `width`
+/// millimetres wide. Words longer than a line are broken; line breaks in the
+/// text are kept.
+fn wrap(text: &str, size: f64, width: f64) -> Vec<String> {
+    let fits = |line: &str| text_width(line, size) * MM_PER_PX <= width;
+    let mut lines = Vec::new();
+    for paragraph in text.trim().lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{line} {word}") };
+            if fits(&candidate) {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for ch in word.chars() {
+                line.push(ch);
+                if !fits(&line) && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, ch.to_string()));
+                }
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
 
-    let _track_index: HashMap<String, usize> = tracks
-        .iter()
-        .enumerate()
-        .map(|(i, t)| (t.id.clone(), i))
-        .collect();
+/// Write one line of text with its baseline at `(x, y)` in page millimetres.
+fn write_line(layer: &PdfLayerReference, font: &IndirectFontRef, size: f64, color: Color, x: f64, y: f64, text: &str) {
+    layer.begin_text_section();
+    layer.set_font(font, size as f32);
+    layer.set_fill_color(color);
+    layer.set_text_cursor(mm(x), mm(y));
+    layer.write_text(text, font);
+    layer.end_text_section();
+}
+
+fn line_height(size: f64) -> f64 {
+    size * MM_PER_PX * 1.3
+}
+
+/// Maps one horizontal strip of a scene onto a page.
+struct Strip<'a> {
+    layer: PdfLayerReference,
+    page: Page,
+    font: &'a IndirectFontRef,
+    font_bold: &'a IndirectFontRef,
+    /// Scene x shown at the left margin.
+    x0: f64,
+    scale: f64,
+    /// Whether event marks link to their appendix cards.
+    link_events: bool,
+}
+
+impl Strip<'_> {
+    fn x(&self, x: f64) -> f64 {
+        self.page.margin + (x - self.x0) * self.scale
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        self.page.content_top() - y * self.scale
+    }
+
+    fn point(&self, (x, y): (f64, f64)) -> Point {
+        Point::new(mm(self.x(x)), mm(self.y(y)))
+    }
+
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64, color: Color) {
+        self.layer.set_fill_color(color);
+        self.layer.add_rect(Rect::new(
+            mm(self.x(x)),
+            mm(self.y(y + height)),
+            mm(self.x(x + width)),
+            mm(self.y(y)),
+        ));
+    }
 
-    // Fetch events
-    let mut event_stmt = conn.prepare(
-        "SELECT title, start_date, end_date, event_type, track_id FROM events WHERE timeline_id = ?1 ORDER BY start_date",
-    )?;
-    let events: Vec<EventInfo> = event_stmt
-        .query_map([timeline_id], |row| {
-            Ok(EventInfo {
-                title: row.get(0)?,
-                start_date: row.get(1)?,
-                end_date: row.get(2)?,
-                event_type: row.get(3)?,
-                track_id: row.get(4)?,
+    fn fill_polygon(&self, points: &[(f64, f64)], color: Color) {
+        self.layer.set_fill_color(color);
+        self.layer.add_polygon(Polygon {
+            rings: vec![points.iter().map(|&p| (self.point(p), false)).collect()],
+            mode: PaintMode::Fill,
+            winding_order: WindingOrder::NonZero,
+        });
+    }
+
+    fn circle(&self, cx: f64, cy: f64, r: f64, color: Color) {
+        let points: Vec<(f64, f64)> = (0..16)
+            .map(|i| {
+                let angle = std::f64::consts::TAU * i as f64 / 16.0;
+                (cx + r * angle.cos(), cy + r * angle.sin())
             })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
+            .collect();
+        self.fill_polygon(&points, color);
+    }
 
-    let event_count = events.len();
+    fn line(&self, from: (f64, f64), to: (f64, f64), width: f64, color: Color) {
+        self.layer.set_outline_color(color);
+        self.layer.set_outline_thickness((width * self.scale / MM_PER_PX) as f32);
+        self.layer.add_line(Line {
+            points: vec![(self.point(from), false), (self.point(to), false)],
+            is_closed: false,
+        });
+    }
 
-    // Calculate date range
-    let epoch_days: Vec<f64> = events
-        .iter()
-        .filter_map(|e| date_to_epoch_days(&e.start_date))
-        .collect();
+    /// A quadratic curve, written as the equivalent cubic Bézier.
+    fn curve(&self, from: (f64, f64), control: (f64, f64), to: (f64, f64), width: f64, color: Color) {
+        let c1 = (from.0 + (control.0 - from.0) * 2.0 / 3.0, from.1 + (control.1 - from.1) * 2.0 / 3.0);
+        let c2 = (to.0 + (control.0 - to.0) * 2.0 / 3.0, to.1 + (control.1 - to.1) * 2.0 / 3.0);
+        self.layer.set_outline_color(color);
+        self.layer.set_outline_thickness((width * self.scale / MM_PER_PX) as f32);
+        self.layer.add_line(Line {
+            points: vec![
+                (self.point(from), true),
+                (self.point(c1), true),
+                (self.point(c2), false),
+                (self.point(to), false),
+            ],
+            is_closed: false,
+        });
+    }
 
-    let min_days = epoch_days.iter().copied().fold(f64::INFINITY, f64::min);
-    let max_days = epoch_days.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    /// Make the part of a scene rectangle that is on this page jump to the
+    /// event's card when clicked.
+    fn link(&self, event_id: &str, (x1, y1, x2, y2): (f64, f64, f64, f64)) {
+        let left = self.x(x1).max(self.page.margin);
+        let right = self.x(x2).min(self.page.width - self.page.margin);
+        if right <= left {
+            return;
+        }
+        self.layer.add_link_annotation(LinkAnnotation::new(
+            Rect::new(mm(left), mm(self.y(y2)), mm(right), mm(self.y(y1))),
+            Some(BorderArray::Solid([0.0, 0.0, 0.0])),
+            Some(ColorArray::Transparent),
+            Actions::uri(format!("{EVENT_LINK_PREFIX}{event_id}")),
+            Some(HighlightingMode::Invert),
+        ));
+    }
 
-    let end_days: Vec<f64> = events
-        .iter()
-        .filter_map(|e| e.end_date.as_ref().and_then(|d| date_to_epoch_days(d)))
-        .collect();
-    let max_days = end_days.iter().copied().fold(max_days, f64::max);
-
-    let (min_days, max_days) = if min_days.is_infinite() || max_days.is_infinite() {
-        (0.0, 100.0)
-    } else if (max_days - min_days).abs() < 1.0 {
-        (min_days - 50.0, max_days + 50.0)
-    } else {
-        (min_days, max_days)
-    };
+    fn text(&self, label: &Label, color: Color) {
+        let x = match label.anchor {
+            Anchor::Start => label.x,
+            Anchor::Middle => label.x - text_width(&label.text, label.size) / 2.0,
+        };
+        let font = if label.bold { self.font_bold } else { self.font };
+        self.layer.begin_text_section();
+        self.layer.set_font(font, (label.size * self.scale / MM_PER_PX) as f32);
+        self.layer.set_fill_color(color);
+        self.layer.set_text_cursor(mm(self.x(x)), mm(self.y(label.y)));
+        self.layer.write_text(&label.text, font);
+        self.layer.end_text_section();
+    }
+
+    /// Draw the part of the scene between `x0` and `x0 + width / scale`,
+    /// clipped to the page margins.
+    fn draw(&self, scene: &Scene) {
+        let palette = scene.palette;
+        let page = self.page;
+        let x1 = self.x0 + page.usable_width() / self.scale;
+        let visible = |from: f64, to: f64| to >= self.x0 && from <= x1;
+
+        self.layer.save_graphics_state();
+        self.layer.add_rect(
+            Rect::new(mm(page.margin), mm(page.margin), mm(page.width - page.margin), mm(page.content_top()))
+                .with_mode(PaintMode::Clip),
+        );
+        self.fill_rect(self.x0, 0.0, x1 - self.x0, scene.height, rgb(palette.background));
+
+        for lane in &scene.lanes {
+            self.fill_rect(
+                self.x0,
+                lane.y,
+                x1 - self.x0,
+                lane.height,
+                blend(palette.background, &lane.color, palette.lane_opacity),
+            );
+            // Repeat the track name at the left of every page
+            let name = Label { x: self.x0 + lane.label.x, ..lane.label.clone() };
+            self.text(&name, rgb(palette.muted));
+        }
+
+        for mark in &scene.events {
+            match mark.shape {
+                Shape::Bar { x, y, width, height } => {
+                    if !visible(x, x + width) {
+                        continue;
+                    }
+                    self.fill_rect(x, y, width, height, blend(palette.background, &mark.color, 0.7));
+                }
+                Shape::Circle { cx, cy, r } => {
+                    if !visible(cx - r, cx + r) {
+                        continue;
+                    }
+                    self.circle(cx, cy, r, rgb(&mark.color));
+                }
+                Shape::Diamond { cx, cy, r } => {
+                    if !visible(cx - r, cx + r) {
+                        continue;
+                    }
+                    self.fill_polygon(&[(cx, cy - r), (cx + r, cy), (cx, cy + r), (cx - r, cy)], rgb(&mark.color));
+                }
+            }
+            if let Some((x, y1, y2)) = mark.leader {
+                self.line((x, y1), (x, y2), 0.75, rgb(palette.muted));
+            }
+            self.text(&mark.label, rgb(palette.text));
+            if self.link_events {
+                self.link(&mark.event_id, mark_bounds(mark));
+            }
+        }
+
+        for path in &scene.connections {
+            if !visible(path.from.0.min(path.to.0), path.from.0.max(path.to.0)) {
+                continue;
+            }
+            self.curve(path.from, path.control, path.to, 1.5, rgb(palette.connection));
+            // Arrowhead along the curve's final direction
+            let (dx, dy) = (path.to.0 - path.control.0, path.to.1 - path.control.1);
+            let len = dx.hypot(dy).max(f64::EPSILON);
+            let (ux, uy) = (dx / len, dy / len);
+            let base = (path.to.0 - ux * 8.0, path.to.1 - uy * 8.0);
+            self.fill_polygon(
+                &[path.to, (base.0 - uy * 3.0, base.1 + ux * 3.0), (base.0 + uy * 3.0, base.1 - ux * 3.0)],
+                rgb(palette.connection),
+            );
+            if let Some(ref label) = path.label {
+                self.text(label, rgb(palette.connection));
+            }
+        }
+
+        let axis = &scene.axis;
+        self.line((axis.x1, axis.y), (axis.x2, axis.y), 1.0, rgb(palette.axis));
+        for (tx, label) in &axis.ticks {
+            if !visible(*tx, *tx) {
+                continue;
+            }
+            self.line((*tx, axis.y - 4.0), (*tx, axis.y + 4.0), 1.0, rgb(palette.axis));
+            self.text(label, rgb(palette.muted));
+        }
+        self.layer.restore_graphics_state();
+    }
+}
+
+struct CardLine {
+    text: String,
+    size: f64,
+    bold: bool,
+    color: &'static str,
+}
+
+/// Everything the appendix prints about one event, wrapped to a column.
+struct Card<'a> {
+    event: &'a ExportedEvent,
+    color: String,
+    /// "AI 85%" for AI-generated events.
+    badge: Option<String>,
+    lines: Vec<CardLine>,
+}
+
+fn badge_width(text: &str) -> f64 {
+    text_width(text, 7.0) * MM_PER_PX + 3.0
+}
+
+impl<'a> Card<'a> {
+    fn new(event: &'a ExportedEvent, color: String, width: f64) -> Self {
+        let text_width = width - CARD_INDENT - CARD_PADDING;
+        let badge = event.ai_generated.then(|| match event.ai_confidence {
+            Some(confidence) => format!("AI {}%", (confidence * 100.0).round() as i64),
+            None => "AI".to_string(),
+        });
+        let title_width = text_width - badge.as_deref().map_or(0.0, |b| badge_width(b) + 2.0);
+
+        let mut lines = Vec::new();
+        let mut push = |text: &str, size: f64, bold: bool, color: &'static str, width: f64| {
+            lines.extend(wrap(text, size, width).into_iter().map(|text| CardLine { text, size, bold, color }));
+        };
+        push(&event.title, 11.0, true, "#111827", title_width);
+        let dates = match &event.end_date {
+            Some(end) => format!("{} to {end}", event.start_date),
+            None => event.start_date.clone(),
+        };
+        push(
+            &format!("{dates} | {} | Importance {}/5", event.track_name, event.importance),
+            8.0,
+            false,
+            "#6b7280",
+            text_width,
+        );
+        if !event.description.trim().is_empty() {
+            push(&event.description, 9.0, false, "#1f2937", text_width);
+        }
+        if let Some(source) = event.source.as_deref().filter(|s| !s.trim().is_empty()) {
+            push(&format!("Source: {source}"), 8.0, false, "#6b7280", text_width);
+        }
+        let tags: Vec<&str> = event.tags.split(',').map(str::trim).filter(|t| !t.is_empty()).collect();
+        if !tags.is_empty() {
+            push(&format!("Tags: {}", tags.join(", ")), 8.0, false, "#6b7280", text_width);
+        }
+
+        Self { event, color, badge, lines }
+    }
+
+    fn height(&self) -> f64 {
+        CARD_PADDING * 2.0 + self.lines.iter().map(|l| line_height(l.size)).sum::<f64>()
+    }
+
+    /// Drop trailing lines of a card taller than `max_height`, marking the cut.
+    fn fit(&mut self, max_height: f64) {
+        if self.height() <= max_height {
+            return;
+        }
+        while self.height() > max_height && self.lines.len() > 1 {
+            self.lines.pop();
+        }
+        if let Some(last) = self.lines.last_mut() {
+            last.text.push_str("...");
+        }
+    }
+
+    fn draw(&self, layer: &PdfLayerReference, font: &IndirectFontRef, font_bold: &IndirectFontRef, x: f64, top: f64, width: f64) {
+        let bottom = top - self.height();
+        layer.set_fill_color(rgb("#f3f4f6"));
+        layer.add_rect(Rect::new(mm(x), mm(bottom), mm(x + width), mm(top)));
+        layer.set_fill_color(rgb(&self.color));
+        layer.add_rect(Rect::new(mm(x), mm(bottom), mm(x + 1.5), mm(top)));
+
+        let mut y = top - CARD_PADDING;
+        for line in &self.lines {
+            y -= line_height(line.size);
+            let font = if line.bold { font_bold } else { font };
+            let baseline = y + line.size * MM_PER_PX * 0.3;
+            write_line(layer, font, line.size, rgb(line.color), x + CARD_INDENT, baseline, &line.text);
+        }
+
+        if let Some(ref badge) = self.badge {
+            let (w, h) = (badge_width(badge), line_height(7.0) + 1.0);
+            let (bx, by) = (x + width - CARD_PADDING - w, top - CARD_PADDING - h);
+            layer.set_fill_color(rgb(AI_BADGE_COLOR));
+            layer.add_rect(Rect::new(mm(bx), mm(by), mm(bx + w), mm(by + h)));
+            write_line(layer, font_bold, 7.0, rgb("#ffffff"), bx + 1.5, by + 1.3, badge);
+        }
+    }
+}
+
+/// Flow the cards down the columns of as many pages as they need, starting
+/// at document page `first_page`. Returns where each event's card landed.
+fn draw_appendix(
+    doc: &PdfDocumentReference,
+    font: &IndirectFontRef,
+    font_bold: &IndirectFontRef,
+    title: &str,
+    cards: &mut [Card],
+    page: Page,
+    first_page: usize,
+) -> HashMap<String, Destination> {
+    let top = page.content_top();
+    let column_width = page.card_column_width();
+
+    let mut destinations = HashMap::new();
+    let mut pages: Vec<PdfLayerReference> = Vec::new();
+    let mut column = CARD_COLUMNS - 1;
+    let mut y = top;
+    for card in cards.iter_mut() {
+        card.fit(page.usable_height());
+        let height = card.height();
+        if pages.is_empty() || y - height < page.margin {
+            column += 1;
+            y = top;
+            if column == CARD_COLUMNS {
+                column = 0;
+                let (page_index, layer_index) =
+                    doc.add_page(mm(page.width), mm(page.height), format!("Details-{}", pages.len() + 1));
+                let layer = doc.get_page(page_index).get_layer(layer_index);
+                let header = format!("{title} - Event details");
+                write_line(&layer, font, 8.0, rgb("#666666"), page.margin, page.height - page.margin, &header);
+                pages.push(layer);
+            }
+        }
+        let x = page.margin + column as f64 * (column_width + CARD_COLUMN_GAP);
+        if let Some(layer) = pages.last() {
+            card.draw(layer, font, font_bold, x, y, column_width);
+        }
+        destinations.insert(card.event.id.clone(), Destination { page: first_page + pages.len() - 1, top: y });
+        y -= height + CARD_GAP;
+    }
+    destinations
+}
+
+/// The year an event starts in, as the outline shows it ("1815", "44 BCE").
+fn year_label(date: &str) -> String {
+    HistoricalDate::parse(date)
+        .ok()
+        .and_then(|d| d.period(DatePrecision::Year))
+        .map(|d| d.to_string())
+        .unwrap_or_else(|| date.to_string())
+}
+
+/// One outline entry per year or track, holding an entry per event that
+/// jumps to `destinations`. `events` are in chronological order.
+fn outline_groups(
+    events: &[&ExportedEvent],
+    track_order: &HashMap<&str, i32>,
+    grouping: OutlineGrouping,
+    destinations: &HashMap<String, Destination>,
+) -> Vec<OutlineItem> {
+    let mut ordered = events.to_vec();
+    if grouping == OutlineGrouping::Track {
+        // Stable, so each track keeps its events in date order
+        ordered.sort_by_key(|e| (track_order.get(e.track_id.as_str()).copied().unwrap_or(i32::MAX), e.track_id.as_str()));
+    }
+
+    let mut groups: Vec<OutlineItem> = Vec::new();
+    for event in ordered {
+        let Some(&dest) = destinations.get(&event.id) else { continue };
+        let key = match grouping {
+            OutlineGrouping::Year => year_label(&event.start_date),
+            OutlineGrouping::Track => event.track_name.clone(),
+        };
+        let item = OutlineItem::new(event.title.as_str(), dest);
+        match groups.last_mut() {
+            Some(group) if group.title == key => group.children.push(item),
+            _ => {
+                let mut group = OutlineItem::new(key, dest);
+                group.children.push(item);
+                groups.push(group);
+            }
+        }
+    }
+    groups
+}
+
+/// Regular and bold fonts: the TrueType font at the `pdf_font_path` setting
+/// for both when one is set, the bundled DejaVu Sans otherwise.
+fn load_fonts(conn: &Connection, doc: &PdfDocumentReference) -> AppResult<(IndirectFontRef, IndirectFontRef)> {
+    let path: Option<String> = conn
+        .query_row(
+            "SELECT NULLIF(TRIM(value), '') FROM settings WHERE key = 'pdf_font_path'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+
+    match path {
+        Some(path) => {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| AppError::Validation(format!("Cannot read PDF font '{path}': {e}")))?;
+            let font = doc
+                .add_external_font(bytes.as_slice())
+                .map_err(|e| AppError::Validation(format!("'{path}' is not a usable TrueType font: {e}")))?;
+            Ok((font.clone(), font))
+        }
+        None => {
+            let add = |bytes: &[u8]| {
+                doc.add_external_font(bytes)
+                    .map_err(|e| AppError::Internal(format!("Failed to add font: {e}")))
+            };
+            Ok((add(FALLBACK_FONT)?, add(FALLBACK_FONT_BOLD)?))
+        }
+    }
+}
+
+/// Generate a PDF as bytes for the given timeline: a cover page, then the
+/// same scene the SVG export draws, split into page-wide strips or shrunk
+/// onto one page, and optionally a card per event. The outline lists the
+/// events by year or track.
+pub fn generate_pdf(conn: &Connection, timeline_id: &str, options: &PdfOptions) -> AppResult<Vec<u8>> {
+    let page = options.page()?;
+    let timeline = export_timeline(conn, timeline_id)?;
+    // Tracks hidden in the app stay out of printed reports
+    let layout = LayoutOptions { visible_tracks_only: true, ..Default::default() };
+    let scene = build_scene(conn, timeline_id, &layout)?;
 
     // Date range strings for cover page
-    let min_date_str = events
+    let min_date_str = timeline
+        .events
         .first()
         .map(|e| e.start_date.clone())
         .unwrap_or_else(|| "N/A".to_string());
-    let max_date_str = events
+    let max_date_str = timeline
+        .events
         .last()
-        .and_then(|e| e.end_date.clone().or(Some(e.start_date.clone())))
+        .map(|e| e.end_date.clone().unwrap_or_else(|| e.start_date.clone()))
         .unwrap_or_else(|| "N/A".to_string());
 
-    // Landscape Letter: 792 x 612 points = 279.4 x 215.9 mm
-    let page_width = mm(279.4);
-    let page_height = mm(215.9);
-
     // Create document with cover page
     let (doc, cover_page_idx, cover_layer_idx) =
-        PdfDocument::new(&title, page_width, page_height, "Cover");
-
-    let font = doc
-        .add_builtin_font(BuiltinFont::Helvetica)
-        .map_err(|e| AppError::Internal(format!("Failed to add font: {e}")))?;
-    let font_bold = doc
-        .add_builtin_font(BuiltinFont::HelveticaBold)
-        .map_err(|e| AppError::Internal(format!("Failed to add bold font: {e}")))?;
+        PdfDocument::new(&timeline.title, mm(page.width), mm(page.height), "Cover");
+    let (font, font_bold) = load_fonts(conn, &doc)?;
 
     // ── Cover page ──
     let cover_layer = doc.get_page(cover_page_idx).get_layer(cover_layer_idx);
+    let cover_x = page.margin + 30.0;
+    let cover_y = page.height * 0.65;
 
-    // Title centered
+    // Title
     cover_layer.begin_text_section();
     cover_layer.set_font(&font_bold, 24.0);
     cover_layer.set_fill_color(Color::Rgb(Rgb::new(0.1, 0.1, 0.1, None)));
-    cover_layer.set_text_cursor(mm(50.0), mm(140.0));
-    cover_layer.write_text(&title, &font_bold);
+    cover_layer.set_text_cursor(mm(cover_x), mm(cover_y));
+    cover_layer.write_text(&timeline.title, &font_bold);
     cover_layer.end_text_section();
 
     // Date range
@@ -175,241 +699,111 @@ pub fn generate_pdf(conn: &Connection, timeline_id: &str) -> AppResult<Vec<u8>>
     cover_layer.begin_text_section();
     cover_layer.set_font(&font, 12.0);
     cover_layer.set_fill_color(Color::Rgb(Rgb::new(0.4, 0.4, 0.4, None)));
-    cover_layer.set_text_cursor(mm(50.0), mm(125.0));
+    cover_layer.set_text_cursor(mm(cover_x), mm(cover_y - 15.0));
     cover_layer.write_text(&date_range_text, &font);
     cover_layer.end_text_section();
 
     // Event count
-    let count_text = format!("{event_count} events across {} tracks", tracks.len());
+    let count_text = format!(
+        "{} events across {} tracks",
+        timeline.events.len(),
+        timeline.tracks.len()
+    );
     cover_layer.begin_text_section();
     cover_layer.set_font(&font, 10.0);
-    cover_layer.set_text_cursor(mm(50.0), mm(115.0));
+    cover_layer.set_text_cursor(mm(cover_x), mm(cover_y - 25.0));
     cover_layer.write_text(&count_text, &font);
     cover_layer.end_text_section();
 
     // Description
-    if !description.is_empty() {
-        let desc_display = if description.len() > 200 {
-            format!("{}...", &description[..200])
+    if !timeline.description.is_empty() {
+        let desc_display = if timeline.description.chars().count() > 200 {
+            format!("{}...", timeline.description.chars().take(200).collect::<String>())
         } else {
-            description.clone()
+            timeline.description.clone()
         };
         cover_layer.begin_text_section();
         cover_layer.set_font(&font, 9.0);
         cover_layer.set_fill_color(Color::Rgb(Rgb::new(0.3, 0.3, 0.3, None)));
-        cover_layer.set_text_cursor(mm(50.0), mm(100.0));
+        cover_layer.set_text_cursor(mm(cover_x), mm(cover_y - 40.0));
         cover_layer.write_text(&desc_display, &font);
         cover_layer.end_text_section();
     }
 
-    // ── Timeline visualization pages ──
-    let margin = 20.0_f64; // mm
-    let usable_width = 279.4 - margin * 2.0;
-    let track_lane_height = 18.0_f64; // mm per track
-    let header_space = 15.0_f64;
-
-    let pixels_per_day = 0.15_f64; // mm per day
-    let total_width_mm = (max_days - min_days) * pixels_per_day;
-    let num_pages = ((total_width_mm / usable_width).ceil() as usize).max(1);
+    // ── Timeline pages ──
+    // Full size where the tracks fit the page height, shrunk where they do
+    // not, or shrunk until the whole width fits when fitting to one page
+    let mut scale = (page.usable_height() / scene.height).min(MM_PER_PX);
+    if options.fit_to_page {
+        scale = scale.min(page.usable_width() / scene.width);
+    }
+    let strip_width = page.usable_width() / scale;
+    let num_pages = ((scene.width / strip_width).ceil() as usize).max(1);
 
     for page_idx in 0..num_pages {
         let (page_index, layer_index) =
-            doc.add_page(page_width, page_height, format!("Timeline-{}", page_idx + 1));
+            doc.add_page(mm(page.width), mm(page.height), format!("Timeline-{}", page_idx + 1));
         let layer = doc.get_page(page_index).get_layer(layer_index);
 
-        let page_start_day = min_days + (page_idx as f64 * usable_width / pixels_per_day);
-        let page_end_day = page_start_day + usable_width / pixels_per_day;
-
         // Page header
-        let page_label = format!("Page {} of {}", page_idx + 1, num_pages);
+        let page_label = format!("{} - Page {} of {}", timeline.title, page_idx + 1, num_pages);
         layer.begin_text_section();
         layer.set_font(&font, 8.0);
         layer.set_fill_color(Color::Rgb(Rgb::new(0.4, 0.4, 0.4, None)));
-        layer.set_text_cursor(mm(margin), mm(215.9 - 10.0));
+        layer.set_text_cursor(mm(page.margin), mm(page.height - page.margin));
         layer.write_text(&page_label, &font);
         layer.end_text_section();
 
-        // Track lanes and events
-        for (track_idx, track) in tracks.iter().enumerate() {
-            let lane_y_top = 215.9 - header_space - (track_idx as f64) * track_lane_height;
-            let lane_y_bottom = lane_y_top - track_lane_height;
-            let lane_center_y = (lane_y_top + lane_y_bottom) / 2.0;
-
-            // Track lane background (light tint)
-            let (r, g, b) = hex_to_rgb(&track.color);
-            let light_r = 1.0 - (1.0 - r) * 0.15;
-            let light_g = 1.0 - (1.0 - g) * 0.15;
-            let light_b = 1.0 - (1.0 - b) * 0.15;
-
-            layer.set_fill_color(Color::Rgb(Rgb::new(light_r, light_g, light_b, None)));
-            let lane_rect = Polygon {
-                rings: vec![vec![
-                    (Point::new(mm(margin), mm(lane_y_bottom)), false),
-                    (Point::new(mm(margin), mm(lane_y_top)), false),
-                    (Point::new(mm(279.4 - margin), mm(lane_y_top)), false),
-                    (Point::new(mm(279.4 - margin), mm(lane_y_bottom)), false),
-                ]],
-                mode: PaintMode::Fill,
-                winding_order: WindingOrder::NonZero,
-            };
-            layer.add_polygon(lane_rect);
-
-            // Track name label
-            layer.begin_text_section();
-            layer.set_font(&font, 7.0);
-            layer.set_fill_color(Color::Rgb(Rgb::new(0.3, 0.3, 0.3, None)));
-            layer.set_text_cursor(mm(margin + 1.0), mm(lane_y_top - 4.0));
-            layer.write_text(&track.name, &font);
-            layer.end_text_section();
-
-            // Draw events in this track on this page
-            for event in &events {
-                if event.track_id != track.id {
-                    continue;
-                }
-                let start_days = match date_to_epoch_days(&event.start_date) {
-                    Some(d) => d,
-                    None => continue,
-                };
-
-                let end_days_val = event
-                    .end_date
-                    .as_ref()
-                    .and_then(|d| date_to_epoch_days(d))
-                    .unwrap_or(start_days);
-
-                if end_days_val < page_start_day || start_days > page_end_day {
-                    continue;
-                }
-
-                let x_start = margin
-                    + ((start_days - page_start_day) * pixels_per_day)
-                        .max(0.0)
-                        .min(usable_width);
-
-                // Truncate label
-                let label = if event.title.len() > 30 {
-                    format!("{}...", &event.title[..27])
-                } else {
-                    event.title.clone()
-                };
-
-                match event.event_type.as_str() {
-                    "range" | "era" => {
-                        let x_end = margin
-                            + ((end_days_val - page_start_day) * pixels_per_day)
-                                .max(0.0)
-                                .min(usable_width);
-                        let bar_w = (x_end - x_start).max(1.0);
-                        let bar_h = 5.0_f64;
-                        let bar_y = lane_center_y - bar_h / 2.0;
-
-                        layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
-                        let bar = Polygon {
-                            rings: vec![vec![
-                                (Point::new(mm(x_start), mm(bar_y)), false),
-                                (Point::new(mm(x_start), mm(bar_y + bar_h)), false),
-                                (Point::new(mm(x_start + bar_w), mm(bar_y + bar_h)), false),
-                                (Point::new(mm(x_start + bar_w), mm(bar_y)), false),
-                            ]],
-                            mode: PaintMode::Fill,
-                            winding_order: WindingOrder::NonZero,
-                        };
-                        layer.add_polygon(bar);
-
-                        // Label above bar
-                        layer.begin_text_section();
-                        layer.set_font(&font, 6.0);
-                        layer.set_fill_color(Color::Rgb(Rgb::new(0.1, 0.1, 0.1, None)));
-                        layer.set_text_cursor(mm(x_start), mm(bar_y + bar_h + 1.0));
-                        layer.write_text(&label, &font);
-                        layer.end_text_section();
-                    }
-                    _ => {
-                        // Point/milestone: small filled circle (polygon approximation)
-                        let cx = x_start;
-                        let cy = lane_center_y;
-                        let dot_r = 1.5_f64;
-                        let segments = 12;
-                        let circle_points: Vec<(Point, bool)> = (0..segments)
-                            .map(|i| {
-                                let angle = 2.0
-                                    * std::f64::consts::PI
-                                    * (i as f64)
-                                    / (segments as f64);
-                                let px = cx + dot_r * angle.cos();
-                                let py = cy + dot_r * angle.sin();
-                                (Point::new(mm(px), mm(py)), false)
-                            })
-                            .collect();
-
-                        layer.set_fill_color(Color::Rgb(Rgb::new(r, g, b, None)));
-                        let dot = Polygon {
-                            rings: vec![circle_points],
-                            mode: PaintMode::Fill,
-                            winding_order: WindingOrder::NonZero,
-                        };
-                        layer.add_polygon(dot);
-
-                        // Label above dot
-                        layer.begin_text_section();
-                        layer.set_font(&font, 6.0);
-                        layer.set_fill_color(Color::Rgb(Rgb::new(0.1, 0.1, 0.1, None)));
-                        layer.set_text_cursor(mm(cx - 2.0), mm(cy + dot_r + 1.5));
-                        layer.write_text(&label, &font);
-                        layer.end_text_section();
-                    }
-                }
-            }
-        }
+        let strip = Strip {
+            layer,
+            page,
+            font: &font,
+            font_bold: &font_bold,
+            x0: page_idx as f64 * strip_width,
+            scale,
+            link_events: options.appendix,
+        };
+        strip.draw(&scene);
+    }
 
-        // Time axis at the bottom
-        let axis_y = 215.9
-            - header_space
-            - (tracks.len().max(1) as f64) * track_lane_height
-            - 5.0;
+    // Each event is found on the strip page holding its left edge, or on its
+    // card when there is an appendix
+    let mut destinations: HashMap<String, Destination> = scene
+        .events
+        .iter()
+        .map(|mark| {
+            let strip = (mark_bounds(mark).0.max(0.0) / strip_width) as usize;
+            let dest = Destination { page: 1 + strip.min(num_pages - 1), top: page.height };
+            (mark.event_id.clone(), dest)
+        })
+        .collect();
 
-        layer.set_outline_color(Color::Rgb(Rgb::new(0.7, 0.7, 0.7, None)));
-        layer.set_outline_thickness(0.5);
-        let axis_line = Line {
-            points: vec![
-                (Point::new(mm(margin), mm(axis_y)), false),
-                (Point::new(mm(279.4 - margin), mm(axis_y)), false),
-            ],
-            is_closed: false,
-        };
-        layer.add_line(axis_line);
-
-        // Tick marks
-        let tick_interval_days = 365.0;
-        let mut tick_day = (page_start_day / tick_interval_days).ceil() * tick_interval_days;
-        while tick_day <= page_end_day {
-            let tx = margin + (tick_day - page_start_day) * pixels_per_day;
-            if tx >= margin && tx <= 279.4 - margin {
-                let tick = Line {
-                    points: vec![
-                        (Point::new(mm(tx), mm(axis_y - 1.5)), false),
-                        (Point::new(mm(tx), mm(axis_y + 1.5)), false),
-                    ],
-                    is_closed: false,
-                };
-                layer.add_line(tick);
-
-                let approx_year = 1970.0 + tick_day / 365.25;
-                layer.begin_text_section();
-                layer.set_font(&font, 7.0);
-                layer.set_fill_color(Color::Rgb(Rgb::new(0.4, 0.4, 0.4, None)));
-                layer.set_text_cursor(mm(tx - 3.0), mm(axis_y - 4.0));
-                layer.write_text(format!("{}", approx_year as i64), &font);
-                layer.end_text_section();
-            }
-            tick_day += tick_interval_days;
-        }
+    // Hidden tracks are left out of the strips, so of the appendix too
+    let colors: HashMap<&str, &str> =
+        scene.events.iter().map(|m| (m.event_id.as_str(), m.color.as_str())).collect();
+    let events: Vec<&ExportedEvent> =
+        timeline.events.iter().filter(|e| colors.contains_key(e.id.as_str())).collect();
+
+    if options.appendix && !events.is_empty() {
+        let mut cards: Vec<Card> = events
+            .iter()
+            .map(|e| Card::new(e, colors[e.id.as_str()].to_string(), page.card_column_width()))
+            .collect();
+        destinations = draw_appendix(&doc, &font, &font_bold, &timeline.title, &mut cards, page, 1 + num_pages);
     }
 
-    // Save to bytes
-    doc.save_to_bytes()
-        .map_err(|e| AppError::Internal(format!("PDF generation error: {e}")))
+    let track_order: HashMap<&str, i32> =
+        timeline.tracks.iter().map(|t| (t.id.as_str(), t.sort_order)).collect();
+    let mut outline = vec![
+        OutlineItem::new("Cover", Destination { page: 0, top: page.height }),
+        OutlineItem::new("Timeline", Destination { page: 1, top: page.height }),
+    ];
+    outline.extend(outline_groups(&events, &track_order, options.outline, &destinations));
+
+    let bytes = doc
+        .save_to_bytes()
+        .map_err(|e| AppError::Internal(format!("PDF generation error: {e}")))?;
+    finish(&bytes, &outline, &destinations)
 }
 
 #[cfg(test)]
@@ -476,7 +870,7 @@ mod tests {
         let conn = init_test_db().expect("init test db");
         let tl_id = setup_test_data(&conn);
 
-        let pdf_bytes = generate_pdf(&conn, &tl_id).expect("generate pdf");
+        let pdf_bytes = generate_pdf(&conn, &tl_id, &PdfOptions::default()).expect("generate pdf");
 
         assert!(pdf_bytes.len() > 100, "PDF should have substantial content");
         assert!(
@@ -488,7 +882,7 @@ mod tests {
     #[test]
     fn test_generate_pdf_not_found() {
         let conn = init_test_db().expect("init test db");
-        let result = generate_pdf(&conn, "nonexistent-id");
+        let result = generate_pdf(&conn, "nonexistent-id", &PdfOptions::default());
         assert!(result.is_err());
     }
 
@@ -502,7 +896,206 @@ mod tests {
         )
         .expect("insert timeline");
 
-        let pdf_bytes = generate_pdf(&conn, &tl_id).expect("generate pdf");
+        let pdf_bytes = generate_pdf(&conn, &tl_id, &PdfOptions::default()).expect("generate pdf");
         assert!(pdf_bytes.starts_with(b"%PDF"));
     }
+
+    #[test]
+    fn test_generate_pdf_paginates_the_scene() {
+        let conn = init_test_db().expect("init test db");
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Millennium');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('tr', 'tl', 'All', '#ef4444', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, importance, tags)
+             VALUES ('a', 'tl', 'tr', 'Start', '1000', 3, ''), ('b', 'tl', 'tr', 'End', '2000', 3, '');
+             INSERT INTO connections (id, timeline_id, source_event_id, target_event_id, connection_type)
+             VALUES ('c', 'tl', 'a', 'b', 'preceded');",
+        )
+        .expect("insert data");
+
+        let scene = build_scene(&conn, "tl", &LayoutOptions::default()).expect("build scene");
+        let strip_width = PdfOptions::default().page().unwrap().usable_width() / MM_PER_PX;
+        let expected_pages = 1 + (scene.width / strip_width).ceil() as usize;
+
+        let pdf_bytes = generate_pdf(&conn, "tl", &PdfOptions::default()).expect("generate pdf");
+        let text = String::from_utf8_lossy(&pdf_bytes);
+        let pages = text.matches("/Type/Page").count() - text.matches("/Type/Pages").count();
+        assert_eq!(pages, expected_pages);
+        assert!(pages > 2);
+    }
+
+    #[test]
+    fn test_generate_pdf_skips_hidden_tracks() {
+        let conn = init_test_db().expect("init test db");
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Hidden');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order, visible)
+             VALUES ('shown', 'tl', 'Shown', '#ef4444', 0, 1), ('hidden', 'tl', 'Hidden', '#3b82f6', 1, 0);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, importance, tags)
+             VALUES ('a', 'tl', 'shown', 'Start', '1900', 3, ''), ('b', 'tl', 'shown', 'End', '1901', 3, ''),
+                    ('far', 'tl', 'hidden', 'Far away', '1000', 3, '');",
+        )
+        .expect("insert data");
+
+        // The hidden event would stretch the strip across many more pages
+        let strip_width = PdfOptions::default().page().unwrap().usable_width() / MM_PER_PX;
+        let pages_for = |options: &LayoutOptions| {
+            let scene = build_scene(&conn, "tl", options).expect("build scene");
+            1 + (scene.width / strip_width).ceil() as usize
+        };
+        let expected_pages = pages_for(&LayoutOptions { visible_tracks_only: true, ..Default::default() });
+        assert!(expected_pages < pages_for(&LayoutOptions::default()));
+
+        let pdf_bytes = generate_pdf(&conn, "tl", &PdfOptions::default()).expect("generate pdf");
+        let text = String::from_utf8_lossy(&pdf_bytes);
+        let pages = text.matches("/Type/Page").count() - text.matches("/Type/Pages").count();
+        assert_eq!(pages, expected_pages);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let lines = wrap("The quick brown fox jumps over the lazy dog", 9.0, 30.0);
+        assert!(lines.len() > 1);
+        assert_eq!(lines.join(" "), "The quick brown fox jumps over the lazy dog");
+        assert!(lines.iter().all(|l| text_width(l, 9.0) * MM_PER_PX <= 30.0));
+
+        // Overlong words are broken, paragraphs kept
+        let lines = wrap(&"x".repeat(100), 9.0, 30.0);
+        assert!(lines.len() > 1);
+        assert_eq!(lines.concat(), "x".repeat(100));
+        assert_eq!(wrap("one\n\ntwo", 9.0, 30.0), vec!["one", "", "two"]);
+    }
+
+    fn outline_titles(doc: &printpdf::lopdf::Document) -> Vec<String> {
+        let decode = |object: &printpdf::lopdf::Object| {
+            let bytes = object.as_str().expect("title");
+            let units: Vec<u16> = bytes[2..].chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        };
+        let root = doc.catalog().unwrap().get(b"Outlines").unwrap().as_reference().unwrap();
+        let mut next = doc.get_dictionary(root).unwrap().get(b"First").ok().map(|o| o.as_reference().unwrap());
+        let mut titles = Vec::new();
+        while let Some(id) = next {
+            let item = doc.get_dictionary(id).unwrap();
+            titles.push(decode(item.get(b"Title").unwrap()));
+            next = item.get(b"Next").ok().map(|o| o.as_reference().unwrap());
+        }
+        titles
+    }
+
+    #[test]
+    fn test_generate_pdf_appendix_and_outline() {
+        let conn = init_test_db().expect("init test db");
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Report');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('t1', 'tl', 'Politics', '#ef4444', 0),
+                                                                                   ('t2', 'tl', 'Science', '#3b82f6', 1);
+             INSERT INTO events (id, timeline_id, track_id, title, description, start_date, importance, tags, source, ai_generated, ai_confidence)
+             VALUES ('a', 'tl', 't2', 'Discovery', 'Long description', '1900', 3, 'lab', 'Journal', 1, 0.85),
+                    ('b', 'tl', 't1', 'Election', '', '1901', 3, '', NULL, 0, NULL),
+                    ('c', 'tl', 't2', 'Patent', '', '1901', 3, '', NULL, 0, NULL);",
+        )
+        .expect("insert data");
+
+        let plain = generate_pdf(&conn, "tl", &PdfOptions::default()).expect("generate pdf");
+        let doc = printpdf::lopdf::Document::load_mem(&plain).expect("parse pdf");
+        assert_eq!(outline_titles(&doc), vec!["Cover", "Timeline", "1900", "1901"]);
+
+        let options = PdfOptions { appendix: true, outline: OutlineGrouping::Track, ..Default::default() };
+        let bytes = generate_pdf(&conn, "tl", &options).expect("generate pdf");
+        let doc = printpdf::lopdf::Document::load_mem(&bytes).expect("parse pdf");
+        // One more page for the cards
+        assert_eq!(doc.get_pages().len(), printpdf::lopdf::Document::load_mem(&plain).unwrap().get_pages().len() + 1);
+        assert_eq!(outline_titles(&doc), vec!["Cover", "Timeline", "Politics", "Science"]);
+
+        // Every event on the strips links to a card on the last page
+        let last_page = *doc.get_pages().values().last().unwrap();
+        let links: Vec<_> = doc
+            .objects
+            .values()
+            .filter_map(|o| o.as_dict().ok())
+            .filter(|d| d.get(b"Subtype").and_then(|s| s.as_name()).ok() == Some(b"Link".as_slice()))
+            .collect();
+        assert_eq!(links.len(), 3);
+        for link in links {
+            assert!(link.get(b"A").is_err());
+            let dest = link.get(b"Dest").unwrap().as_array().unwrap();
+            assert_eq!(dest[0].as_reference().unwrap(), last_page);
+        }
+    }
+
+    #[test]
+    fn test_generate_pdf_page_size_and_fit() {
+        let conn = init_test_db().expect("init test db");
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Millennium');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('tr', 'tl', 'All', '#ef4444', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, importance, tags)
+             VALUES ('a', 'tl', 'tr', 'Start', '1000', 3, ''), ('b', 'tl', 'tr', 'End', '2000', 3, '');",
+        )
+        .expect("insert data");
+
+        let options = PdfOptions {
+            page_size: PageSize::A4,
+            orientation: Orientation::Portrait,
+            margin: 10.0,
+            fit_to_page: true,
+            ..Default::default()
+        };
+        let bytes = generate_pdf(&conn, "tl", &options).expect("generate pdf");
+        let doc = printpdf::lopdf::Document::load_mem(&bytes).expect("parse pdf");
+        // The cover and a single timeline page
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 2);
+        let media_box: Vec<f32> = doc
+            .get_dictionary(pages[&1])
+            .unwrap()
+            .get(b"MediaBox")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_float().unwrap())
+            .collect();
+        assert!((media_box[2] - 595.3).abs() < 1.0 && (media_box[3] - 841.9).abs() < 1.0);
+
+        let tight = PdfOptions { margin: 120.0, ..Default::default() };
+        assert!(matches!(generate_pdf(&conn, "tl", &tight), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_generate_pdf_fonts() {
+        let conn = init_test_db().expect("init test db");
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Ἀθῆναι и Москва');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('tr', 'tl', 'Städte', '#ef4444', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, importance, tags)
+             VALUES ('a', 'tl', 'tr', 'Ελευθερία', '1821', 3, '');",
+        )
+        .expect("insert data");
+
+        // The bundled regular and bold fonts are embedded by default
+        let bytes = generate_pdf(&conn, "tl", &PdfOptions::default()).expect("generate pdf");
+        let text = String::from_utf8_lossy(&bytes);
+        assert_eq!(text.matches("/FontFile2").count(), 2);
+        assert!(text.contains("DejaVuSans-Bold"));
+
+        // A configured font replaces it; an unreadable one is reported
+        let path = std::env::temp_dir().join(format!("chronomap-font-{}.ttf", uuid::Uuid::new_v4()));
+        std::fs::write(&path, FALLBACK_FONT_BOLD).expect("write font");
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES ('pdf_font_path', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = ?1",
+            [path.to_string_lossy()],
+        )
+        .expect("set font");
+        let bytes = generate_pdf(&conn, "tl", &PdfOptions::default()).expect("generate pdf");
+        let text = String::from_utf8_lossy(&bytes);
+        assert_eq!(text.matches("/FontFile2").count(), 1);
+        assert!(text.contains("DejaVuSans-Bold"));
+        std::fs::remove_file(&path).ok();
+
+        let result = generate_pdf(&conn, "tl", &PdfOptions::default());
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
 }
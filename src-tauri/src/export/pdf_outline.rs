@@ -0,0 +1,141 @@
+use std::collections::{BTreeMap, HashMap};
+
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, StringFormat};
+
+use crate::error::{AppError, AppResult};
+
+/// URI prefix for links to an event's card while the document is built.
+/// printpdf only writes URI links, so `finish` turns these into in-document
+/// destinations once the pages exist.
+pub const EVENT_LINK_PREFIX: &str = "chronomap-event:";
+
+const PT_PER_MM: f64 = 72.0 / 25.4;
+
+/// A place in the document: a page index and the height, in millimetres from
+/// the bottom of the page, a viewer should scroll to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Destination {
+    pub page: usize,
+    pub top: f64,
+}
+
+/// One entry of the outline a viewer shows as bookmarks.
+#[derive(Debug, Clone)]
+pub struct OutlineItem {
+    pub title: String,
+    pub destination: Destination,
+    pub children: Vec<OutlineItem>,
+}
+
+impl OutlineItem {
+    pub fn new(title: impl Into<String>, destination: Destination) -> Self {
+        Self { title: title.into(), destination, children: Vec::new() }
+    }
+}
+
+fn pdf_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Internal(format!("PDF outline error: {e}"))
+}
+
+/// A text string in UTF-16BE with a byte order mark, so titles in any script
+/// survive in the outline.
+fn text_string(text: &str) -> Object {
+    let mut bytes = vec![0xFE, 0xFF];
+    bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+fn destination(pages: &BTreeMap<u32, ObjectId>, dest: Destination) -> AppResult<Object> {
+    let page = pages
+        .get(&(dest.page as u32 + 1))
+        .ok_or_else(|| pdf_error(format!("no page {}", dest.page + 1)))?;
+    Ok(Object::Array(vec![
+        Object::Reference(*page),
+        Object::Name(b"XYZ".to_vec()),
+        Object::Null,
+        Object::Real((dest.top * PT_PER_MM) as f32),
+        Object::Null,
+    ]))
+}
+
+/// Write `items` as the children of `parent`, returning the first and last
+/// child ids and the number of entries open below `parent`.
+fn add_items(
+    doc: &mut Document,
+    pages: &BTreeMap<u32, ObjectId>,
+    parent: ObjectId,
+    items: &[OutlineItem],
+) -> AppResult<(ObjectId, ObjectId, i64)> {
+    let ids: Vec<ObjectId> = items.iter().map(|_| doc.new_object_id()).collect();
+    let mut count = 0;
+    for (i, item) in items.iter().enumerate() {
+        let mut dict = Dictionary::new();
+        dict.set("Title", text_string(&item.title));
+        dict.set("Parent", Object::Reference(parent));
+        dict.set("Dest", destination(pages, item.destination)?);
+        if i > 0 {
+            dict.set("Prev", Object::Reference(ids[i - 1]));
+        }
+        if let Some(next) = ids.get(i + 1) {
+            dict.set("Next", Object::Reference(*next));
+        }
+        if !item.children.is_empty() {
+            // Child entries start collapsed
+            let (first, last, _) = add_items(doc, pages, ids[i], &item.children)?;
+            dict.set("First", Object::Reference(first));
+            dict.set("Last", Object::Reference(last));
+            dict.set("Count", Object::Integer(-(item.children.len() as i64)));
+        }
+        doc.objects.insert(ids[i], Object::Dictionary(dict));
+        count += 1;
+    }
+    Ok((ids[0], ids[ids.len() - 1], count))
+}
+
+/// Give a saved printpdf document its outline and point event links at
+/// their destinations.
+pub fn finish(
+    bytes: &[u8],
+    outline: &[OutlineItem],
+    links: &HashMap<String, Destination>,
+) -> AppResult<Vec<u8>> {
+    let mut doc = Document::load_mem(bytes).map_err(pdf_error)?;
+    let pages = doc.get_pages();
+
+    for object in doc.objects.values_mut() {
+        let Object::Dictionary(annotation) = object else { continue };
+        let Ok(event_id) = annotation
+            .get(b"A")
+            .and_then(Object::as_dict)
+            .and_then(|action| action.get(b"URI"))
+            .and_then(Object::as_str)
+            .map(|uri| String::from_utf8_lossy(uri).into_owned())
+        else {
+            continue;
+        };
+        let Some(event_id) = event_id.strip_prefix(EVENT_LINK_PREFIX) else { continue };
+        if let Some(dest) = links.get(event_id) {
+            annotation.remove(b"A");
+            annotation.set("Dest", destination(&pages, *dest)?);
+        }
+    }
+
+    if !outline.is_empty() {
+        let root = doc.new_object_id();
+        let (first, last, count) = add_items(&mut doc, &pages, root, outline)?;
+        let mut dict = Dictionary::new();
+        dict.set("Type", Object::Name(b"Outlines".to_vec()));
+        dict.set("First", Object::Reference(first));
+        dict.set("Last", Object::Reference(last));
+        dict.set("Count", Object::Integer(count));
+        doc.objects.insert(root, Object::Dictionary(dict));
+
+        let catalog = doc.catalog_mut().map_err(pdf_error)?;
+        catalog.set("Outlines", Object::Reference(root));
+        catalog.set("PageMode", Object::Name(b"UseOutlines".to_vec()));
+    }
+
+    let mut out = Vec::new();
+    doc.save_to(&mut out).map_err(pdf_error)?;
+    Ok(out)
+}
@@ -0,0 +1,213 @@
+use std::sync::{Arc, OnceLock};
+
+use resvg::usvg::fontdb::Database;
+use resvg::{tiny_skia, usvg};
+use rusqlite::Connection;
+use serde::Deserialize;
+
+use super::layout::LayoutOptions;
+use super::pdf::{FALLBACK_FONT, FALLBACK_FONT_BOLD};
+use super::svg::generate_svg;
+use crate::error::{AppError, AppResult};
+
+/// SVG pixels are CSS pixels: 96 to the inch.
+const SVG_DPI: f64 = 96.0;
+const MAX_DPI: f64 = 1200.0;
+/// Keeps the RGBA buffer under about 200 MB.
+const MAX_PIXELS: u64 = 50_000_000;
+const METRES_PER_INCH: f64 = 0.0254;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PngOptions {
+    #[serde(flatten)]
+    pub layout: LayoutOptions,
+    /// Print resolution; 96 renders one image pixel per SVG pixel.
+    pub dpi: Option<f64>,
+    /// Width of the image in pixels, as an alternative to `dpi`. The height
+    /// follows the drawing's aspect ratio.
+    pub pixel_width: Option<u32>,
+}
+
+impl PngOptions {
+    /// Image pixels per SVG pixel for a drawing `svg_width` pixels wide.
+    fn scale(&self, svg_width: f64) -> AppResult<f64> {
+        match (self.dpi, self.pixel_width) {
+            (Some(_), Some(_)) => Err(AppError::Validation(
+                "Give either a DPI or a pixel width for PNG export, not both".to_string(),
+            )),
+            (Some(dpi), None) if dpi > 0.0 && dpi <= MAX_DPI => Ok(dpi / SVG_DPI),
+            (Some(dpi), None) => Err(AppError::Validation(format!(
+                "DPI must be between 1 and {MAX_DPI}, got {dpi}"
+            ))),
+            (None, Some(0)) => Err(AppError::Validation("Pixel width must be positive".to_string())),
+            (None, Some(width)) => Ok(width as f64 / svg_width),
+            (None, None) => Ok(1.0),
+        }
+    }
+}
+
+/// The bundled fonts, plus the system's when `system_fonts` is set. Scanning
+/// system fonts is slow, so each database is built once per process.
+fn font_database(system_fonts: bool) -> Arc<Database> {
+    static BUNDLED: OnceLock<Arc<Database>> = OnceLock::new();
+    static WITH_SYSTEM: OnceLock<Arc<Database>> = OnceLock::new();
+    let build = || {
+        let mut fontdb = Database::new();
+        if system_fonts {
+            fontdb.load_system_fonts();
+        }
+        fontdb.load_font_data(FALLBACK_FONT.to_vec());
+        fontdb.load_font_data(FALLBACK_FONT_BOLD.to_vec());
+        fontdb.set_sans_serif_family("DejaVu Sans");
+        Arc::new(fontdb)
+    };
+    let cell = if system_fonts { &WITH_SYSTEM } else { &BUNDLED };
+    cell.get_or_init(build).clone()
+}
+
+/// CRC-32 as used by PNG chunks.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Insert a pHYs chunk recording `dpi` right after the IHDR chunk, so print
+/// and layout tools size the image correctly.
+fn with_dpi(png: Vec<u8>, dpi: f64) -> Vec<u8> {
+    // 8-byte signature, then IHDR: length, type, 13 bytes of data and CRC.
+    const IHDR_END: usize = 8 + 4 + 4 + 13 + 4;
+    let per_metre = (dpi / METRES_PER_INCH).round() as u32;
+    let mut chunk = b"pHYs".to_vec();
+    chunk.extend_from_slice(&per_metre.to_be_bytes());
+    chunk.extend_from_slice(&per_metre.to_be_bytes());
+    chunk.push(1); // unit: metre
+
+    let mut out = Vec::with_capacity(png.len() + 4 + chunk.len() + 4);
+    out.extend_from_slice(&png[..IHDR_END]);
+    out.extend_from_slice(&(chunk.len() as u32 - 4).to_be_bytes());
+    out.extend_from_slice(&chunk);
+    out.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    out.extend_from_slice(&png[IHDR_END..]);
+    out
+}
+
+/// Rasterize the timeline's SVG export to PNG bytes. The bundled DejaVu Sans
+/// stands in for the generic sans-serif family, so the same timeline renders
+/// the same on every machine; system fonts are only loaded when another
+/// family is chosen. With `dpi`, the image records its print resolution.
+pub fn generate_png(conn: &Connection, timeline_id: &str, options: &PngOptions) -> AppResult<Vec<u8>> {
+    let svg = generate_svg(conn, timeline_id, &options.layout)?;
+
+    let family = options.layout.font_family.trim();
+    let system_fonts = !(family.is_empty() || family.eq_ignore_ascii_case("sans-serif"));
+    let usvg_options = usvg::Options { fontdb: font_database(system_fonts), ..Default::default() };
+
+    let tree = usvg::Tree::from_str(&svg, &usvg_options)
+        .map_err(|e| AppError::Internal(format!("Failed to parse SVG: {e}")))?;
+    let size = tree.size();
+    let scale = options.scale(size.width() as f64)?;
+    let width = (size.width() as f64 * scale).round().max(1.0) as u32;
+    let height = (size.height() as f64 * scale).round().max(1.0) as u32;
+    if width as u64 * height as u64 > MAX_PIXELS {
+        return Err(AppError::Validation(format!(
+            "A {width}x{height} image is too large; lower the DPI or pixel width"
+        )));
+    }
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| AppError::Internal(format!("Cannot allocate a {width}x{height} image")))?;
+    let transform = tiny_skia::Transform::from_scale(scale as f32, scale as f32);
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    let png = pixmap
+        .encode_png()
+        .map_err(|e| AppError::Internal(format!("PNG encoding error: {e}")))?;
+    Ok(match options.dpi {
+        Some(dpi) => with_dpi(png, dpi),
+        None => png,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_test_db;
+
+    fn setup(conn: &Connection) {
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Raster');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('tr', 'tl', 'Main', '#ef4444', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, importance, tags)
+             VALUES ('a', 'tl', 'tr', 'Start', '1900', 3, ''), ('b', 'tl', 'tr', 'End', '1950', 3, '');",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_generate_png_size() {
+        let conn = init_test_db().unwrap();
+        setup(&conn);
+        let layout = LayoutOptions { width: Some(1000.0), ..Default::default() };
+
+        let bytes = generate_png(&conn, "tl", &PngOptions { layout: layout.clone(), ..Default::default() }).unwrap();
+        let image = tiny_skia::Pixmap::decode_png(&bytes).unwrap();
+        assert_eq!(image.width(), 1000);
+        let base_height = image.height();
+
+        let print = PngOptions { layout: layout.clone(), dpi: Some(192.0), ..Default::default() };
+        let image = tiny_skia::Pixmap::decode_png(&generate_png(&conn, "tl", &print).unwrap()).unwrap();
+        assert_eq!((image.width(), image.height()), (2000, base_height * 2));
+
+        let slide = PngOptions { layout: layout.clone(), pixel_width: Some(500), ..Default::default() };
+        let image = tiny_skia::Pixmap::decode_png(&generate_png(&conn, "tl", &slide).unwrap()).unwrap();
+        assert_eq!(image.width(), 500);
+
+        let both = PngOptions { layout, dpi: Some(300.0), pixel_width: Some(500) };
+        assert!(matches!(generate_png(&conn, "tl", &both), Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_generate_png_records_dpi() {
+        let conn = init_test_db().unwrap();
+        setup(&conn);
+        let phys = |png: &[u8]| png.windows(4).position(|w| w == b"pHYs").map(|i| png[i + 4..i + 13].to_vec());
+
+        let plain = generate_png(&conn, "tl", &PngOptions::default()).unwrap();
+        assert_eq!(phys(&plain), None);
+
+        let print = generate_png(&conn, "tl", &PngOptions { dpi: Some(300.0), ..Default::default() }).unwrap();
+        // 300 DPI is 11811 pixels per metre on both axes.
+        let mut expected = 11811u32.to_be_bytes().repeat(2);
+        expected.push(1);
+        assert_eq!(phys(&print), Some(expected));
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert!(tiny_skia::Pixmap::decode_png(&print).is_ok());
+    }
+
+    #[test]
+    fn test_default_font_skips_system_fonts() {
+        assert_eq!(font_database(false).len(), 2);
+        assert!(Arc::ptr_eq(&font_database(false), &font_database(false)));
+    }
+
+    #[test]
+    fn test_generate_png_transparent_background() {
+        let conn = init_test_db().unwrap();
+        setup(&conn);
+
+        let opaque = generate_png(&conn, "tl", &PngOptions::default()).unwrap();
+        let corner = tiny_skia::Pixmap::decode_png(&opaque).unwrap().pixel(0, 0).unwrap();
+        assert_eq!(corner.alpha(), 255);
+
+        let layout = LayoutOptions { transparent_background: true, ..Default::default() };
+        let clear = generate_png(&conn, "tl", &PngOptions { layout, ..Default::default() }).unwrap();
+        let corner = tiny_skia::Pixmap::decode_png(&clear).unwrap().pixel(0, 0).unwrap();
+        assert_eq!(corner.alpha(), 0);
+    }
+}
@@ -1,366 +1,183 @@
-use std::collections::HashMap;
 use std::fmt::Write;
 
-use chrono::Timelike;
 use rusqlite::Connection;
 
-use crate::error::{AppError, AppResult};
+use super::layout::{build_scene, Anchor, Label, LayoutOptions, Shape};
+use crate::error::AppResult;
 
 /// Escape text for safe inclusion in XML/SVG content.
-fn escape_xml(s: &str) -> String {
+pub fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
         .replace('"', "&quot;")
 }
 
-/// Parse a date string (YYYY-MM-DD or ISO datetime) to days since Unix epoch.
-fn date_to_epoch_days(date_str: &str) -> Option<f64> {
-    // Try YYYY-MM-DD first
-    if let Ok(nd) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-        let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?;
-        return Some((nd - epoch).num_days() as f64);
-    }
-    // Try ISO datetime
-    if let Ok(ndt) = chrono::NaiveDateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S") {
-        let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?;
-        let days = (ndt.date() - epoch).num_days() as f64;
-        let frac = ndt.time().num_seconds_from_midnight() as f64 / 86400.0;
-        return Some(days + frac);
-    }
-    // Try with timezone suffix (strip it)
-    if let Some(stripped) = date_str.get(..19) {
-        if let Ok(ndt) = chrono::NaiveDateTime::parse_from_str(stripped, "%Y-%m-%dT%H:%M:%S") {
-            let epoch = chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?;
-            let days = (ndt.date() - epoch).num_days() as f64;
-            let frac = ndt.time().num_seconds_from_midnight() as f64 / 86400.0;
-            return Some(days + frac);
-        }
-    }
-    None
-}
-
-struct TrackInfo {
-    id: String,
-    name: String,
-    color: String,
-}
-
-struct EventInfo {
-    id: String,
-    title: String,
-    start_date: String,
-    end_date: Option<String>,
-    event_type: String,
-    track_id: String,
-}
-
-struct ConnectionInfo {
-    source_event_id: String,
-    target_event_id: String,
-    label: Option<String>,
+fn write_label(svg: &mut String, label: &Label, fill: &str) {
+    let anchor = match label.anchor {
+        Anchor::Start => "",
+        Anchor::Middle => r#" text-anchor="middle""#,
+    };
+    let weight = if label.bold { r#" font-weight="bold""# } else { "" };
+    let _ = write!(
+        svg,
+        r##"<text x="{x}" y="{y}" font-size="{size}"{weight}{anchor} fill="{fill}">{t}</text>"##,
+        x = label.x,
+        y = label.y,
+        size = label.size,
+        fill = escape_xml(fill),
+        t = escape_xml(&label.text)
+    );
 }
 
 /// Generate an SVG string for the given timeline.
-pub fn generate_svg(conn: &Connection, timeline_id: &str) -> AppResult<String> {
-    // Fetch timeline title
-    let title: String = conn
-        .query_row(
-            "SELECT title FROM timelines WHERE id = ?1",
-            [timeline_id],
-            |row| row.get(0),
-        )
-        .map_err(|_| AppError::NotFound(format!("Timeline {timeline_id} not found")))?;
-
-    // Fetch tracks
-    let mut track_stmt = conn.prepare(
-        "SELECT id, name, color FROM tracks WHERE timeline_id = ?1 ORDER BY sort_order",
-    )?;
-    let tracks: Vec<TrackInfo> = track_stmt
-        .query_map([timeline_id], |row| {
-            Ok(TrackInfo {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                color: row.get(2)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-
-    let track_index: HashMap<String, usize> = tracks
-        .iter()
-        .enumerate()
-        .map(|(i, t)| (t.id.clone(), i))
-        .collect();
-
-    // Fetch events
-    let mut event_stmt = conn.prepare(
-        "SELECT id, title, start_date, end_date, event_type, track_id FROM events WHERE timeline_id = ?1 ORDER BY start_date",
-    )?;
-    let events: Vec<EventInfo> = event_stmt
-        .query_map([timeline_id], |row| {
-            Ok(EventInfo {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                start_date: row.get(2)?,
-                end_date: row.get(3)?,
-                event_type: row.get(4)?,
-                track_id: row.get(5)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-
-    // Fetch connections
-    let mut conn_stmt = conn.prepare(
-        "SELECT source_event_id, target_event_id, label FROM connections WHERE timeline_id = ?1",
-    )?;
-    let connections: Vec<ConnectionInfo> = conn_stmt
-        .query_map([timeline_id], |row| {
-            Ok(ConnectionInfo {
-                source_event_id: row.get(0)?,
-                target_event_id: row.get(1)?,
-                label: row.get(2)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
-
-    // Calculate date range
-    let pixels_per_day: f64 = 0.5;
-    let track_height: f64 = 60.0;
-    let header_height: f64 = 40.0;
-    let axis_height: f64 = 40.0;
-    let padding: f64 = 20.0;
-
-    let epoch_days: Vec<f64> = events
-        .iter()
-        .filter_map(|e| date_to_epoch_days(&e.start_date))
-        .collect();
-
-    let min_days = epoch_days
-        .iter()
-        .copied()
-        .fold(f64::INFINITY, f64::min);
-    let max_days = epoch_days
-        .iter()
-        .copied()
-        .fold(f64::NEG_INFINITY, f64::max);
-
-    // Also consider end dates
-    let end_epoch_days: Vec<f64> = events
-        .iter()
-        .filter_map(|e| e.end_date.as_ref().and_then(|d| date_to_epoch_days(d)))
-        .collect();
-    let max_days = end_epoch_days
-        .iter()
-        .copied()
-        .fold(max_days, f64::max);
-
-    // Handle empty or single-event timelines
-    let (min_days, max_days) = if min_days.is_infinite() || max_days.is_infinite() {
-        (0.0, 100.0)
-    } else if (max_days - min_days).abs() < 1.0 {
-        (min_days - 50.0, max_days + 50.0)
-    } else {
-        (min_days, max_days)
-    };
-
-    let content_width = (max_days - min_days) * pixels_per_day + padding * 2.0;
-    let num_tracks = if tracks.is_empty() { 1 } else { tracks.len() };
-    let content_height =
-        header_height + (num_tracks as f64) * track_height + axis_height + padding * 2.0;
-
-    let width = content_width.max(600.0);
-    let height = content_height.max(200.0);
+pub fn generate_svg(conn: &Connection, timeline_id: &str, options: &LayoutOptions) -> AppResult<String> {
+    let scene = build_scene(conn, timeline_id, options)?;
+    let palette = scene.palette;
 
     let mut svg = String::with_capacity(4096);
 
     // SVG header
     let _ = write!(
         svg,
-        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">"##,
-        w = width,
-        h = height
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}" font-family="{font}">"##,
+        w = scene.width,
+        h = scene.height,
+        font = escape_xml(&scene.font_family)
     );
 
     // Background
-    let _ = write!(
-        svg,
-        r##"<rect width="{w}" height="{h}" fill="#ffffff"/>"##,
-        w = width,
-        h = height
-    );
+    if !options.transparent_background {
+        let _ = write!(
+            svg,
+            r##"<rect width="{w}" height="{h}" fill="{bg}"/>"##,
+            w = scene.width,
+            h = scene.height,
+            bg = palette.background
+        );
+    }
 
-    // Title
-    let _ = write!(
-        svg,
-        r##"<text x="{x}" y="{y}" font-family="sans-serif" font-size="18" font-weight="bold" text-anchor="middle" fill="#1f2937">{title}</text>"##,
-        x = width / 2.0,
-        y = header_height / 2.0 + 6.0,
-        title = escape_xml(&title)
-    );
+    write_label(&mut svg, &scene.title, palette.title);
 
     // Track lanes
-    for (i, track) in tracks.iter().enumerate() {
-        let y = header_height + (i as f64) * track_height;
-        // Background rect with low opacity
+    for lane in &scene.lanes {
         let _ = write!(
             svg,
-            r##"<rect x="0" y="{y}" width="{w}" height="{th}" fill="{color}" opacity="0.1"/>"##,
-            y = y,
-            w = width,
-            th = track_height,
-            color = escape_xml(&track.color)
+            r##"<g class="lane" data-track-id="{id}">"##,
+            id = escape_xml(&lane.track_id)
         );
-        // Track label
+        // Background rect with low opacity
         let _ = write!(
             svg,
-            r##"<text x="5" y="{ty}" font-family="sans-serif" font-size="11" fill="#6b7280">{name}</text>"##,
-            ty = y + 14.0,
-            name = escape_xml(&track.name)
+            r##"<rect x="0" y="{y}" width="{w}" height="{th}" fill="{color}" opacity="{o}"/>"##,
+            y = lane.y,
+            w = scene.width,
+            th = lane.height,
+            color = escape_xml(&lane.color),
+            o = palette.lane_opacity
         );
+        write_label(&mut svg, &lane.label, palette.muted);
+        svg.push_str("</g>");
     }
 
-    // Build event position map for connections
-    let mut event_positions: HashMap<String, (f64, f64)> = HashMap::new();
-
-    // Events
-    for event in &events {
-        let start_days = match date_to_epoch_days(&event.start_date) {
-            Some(d) => d,
-            None => continue,
-        };
-        let x = (start_days - min_days) * pixels_per_day + padding;
-        let track_idx = track_index.get(&event.track_id).copied().unwrap_or(0);
-        let y = header_height + (track_idx as f64) * track_height + track_height / 2.0;
-
-        event_positions.insert(event.id.clone(), (x, y));
-
-        let track_color = tracks
-            .get(track_idx)
-            .map(|t| t.color.as_str())
-            .unwrap_or("#6b7280");
-
-        match event.event_type.as_str() {
-            "range" | "era" => {
-                let end_days = event
-                    .end_date
-                    .as_ref()
-                    .and_then(|d| date_to_epoch_days(d))
-                    .unwrap_or(start_days + 30.0);
-                let x2 = (end_days - min_days) * pixels_per_day + padding;
-                let bar_width = (x2 - x).max(4.0);
-                let bar_height = 20.0;
-                let bar_y = y - bar_height / 2.0;
+    // Events, grouped with ids so interactive viewers can hook into the drawing
+    for mark in &scene.events {
+        let _ = write!(
+            svg,
+            r##"<g class="event" data-event-id="{id}" data-track-id="{track}">"##,
+            id = escape_xml(&mark.event_id),
+            track = escape_xml(&mark.track_id)
+        );
+        match mark.shape {
+            Shape::Bar { x, y, width, height } => {
                 // Rounded rect for range/era
                 let _ = write!(
                     svg,
-                    r##"<rect x="{x}" y="{by}" width="{bw}" height="{bh}" rx="4" ry="4" fill="{color}" opacity="0.7"/>"##,
-                    x = x,
-                    by = bar_y,
-                    bw = bar_width,
-                    bh = bar_height,
-                    color = escape_xml(track_color)
-                );
-                // Label centered on bar
-                let label_x = x + bar_width / 2.0;
-                let _ = write!(
-                    svg,
-                    r##"<text x="{lx}" y="{ly}" font-family="sans-serif" font-size="10" text-anchor="middle" fill="#1f2937">{t}</text>"##,
-                    lx = label_x,
-                    ly = bar_y - 4.0,
-                    t = escape_xml(&event.title)
+                    r##"<rect x="{x}" y="{y}" width="{width}" height="{height}" rx="4" ry="4" fill="{color}" opacity="0.7"/>"##,
+                    color = escape_xml(&mark.color)
                 );
             }
-            _ => {
-                // Point/milestone: circle
+            Shape::Diamond { cx, cy, r } => {
                 let _ = write!(
                     svg,
-                    r##"<circle cx="{cx}" cy="{cy}" r="6" fill="{color}"/>"##,
-                    cx = x,
-                    cy = y,
-                    color = escape_xml(track_color)
+                    r##"<path d="M{cx},{top} L{right},{cy} L{cx},{bottom} L{left},{cy} Z" fill="{color}"/>"##,
+                    top = cy - r,
+                    right = cx + r,
+                    bottom = cy + r,
+                    left = cx - r,
+                    color = escape_xml(&mark.color)
                 );
-                // Label
+            }
+            Shape::Circle { cx, cy, r } => {
+                // Point: circle
                 let _ = write!(
                     svg,
-                    r##"<text x="{tx}" y="{ty}" font-family="sans-serif" font-size="10" text-anchor="middle" fill="#1f2937">{t}</text>"##,
-                    tx = x,
-                    ty = y - 10.0,
-                    t = escape_xml(&event.title)
+                    r##"<circle cx="{cx}" cy="{cy}" r="{r}" fill="{color}"/>"##,
+                    color = escape_xml(&mark.color)
                 );
             }
         }
+        // Labels lifted above a crowded row point back at their marker
+        if let Some((x, y1, y2)) = mark.leader {
+            let _ = write!(
+                svg,
+                r##"<line x1="{x}" y1="{y1}" x2="{x}" y2="{y2}" stroke="{stroke}" stroke-width="0.75"/>"##,
+                stroke = palette.muted
+            );
+        }
+        write_label(&mut svg, &mark.label, palette.text);
+        svg.push_str("</g>");
     }
 
     // Connections: curved paths with arrowheads
     let _ = write!(
         svg,
-        r##"<defs><marker id="arrowhead" markerWidth="8" markerHeight="6" refX="8" refY="3" orient="auto"><path d="M0,0 L8,3 L0,6 Z" fill="#9ca3af"/></marker></defs>"##
+        r##"<defs><marker id="arrowhead" markerWidth="8" markerHeight="6" refX="8" refY="3" orient="auto"><path d="M0,0 L8,3 L0,6 Z" fill="{fill}"/></marker></defs>"##,
+        fill = palette.connection
     );
-
-    for conn in &connections {
-        if let (Some(&(x1, y1)), Some(&(x2, y2))) = (
-            event_positions.get(&conn.source_event_id),
-            event_positions.get(&conn.target_event_id),
-        ) {
-            let mid_x = (x1 + x2) / 2.0;
-            let ctrl_y = y1.min(y2) - 30.0;
-            let _ = write!(
-                svg,
-                r##"<path d="M{x1},{y1} Q{mx},{cy} {x2},{y2}" fill="none" stroke="#9ca3af" stroke-width="1.5" marker-end="url(#arrowhead)"/>"##,
-                x1 = x1,
-                y1 = y1,
-                mx = mid_x,
-                cy = ctrl_y,
-                x2 = x2,
-                y2 = y2
-            );
-            // Connection label if present
-            if let Some(ref label) = conn.label {
-                let _ = write!(
-                    svg,
-                    r##"<text x="{lx}" y="{ly}" font-family="sans-serif" font-size="9" text-anchor="middle" fill="#9ca3af">{l}</text>"##,
-                    lx = mid_x,
-                    ly = ctrl_y - 4.0,
-                    l = escape_xml(label)
-                );
-            }
+    for path in &scene.connections {
+        let _ = write!(
+            svg,
+            r##"<g class="connection" data-source="{s}" data-target="{t}">"##,
+            s = escape_xml(&path.source_id),
+            t = escape_xml(&path.target_id)
+        );
+        let _ = write!(
+            svg,
+            r##"<path d="M{x1},{y1} Q{mx},{cy} {x2},{y2}" fill="none" stroke="{stroke}" stroke-width="1.5" marker-end="url(#arrowhead)"/>"##,
+            x1 = path.from.0,
+            y1 = path.from.1,
+            mx = path.control.0,
+            cy = path.control.1,
+            x2 = path.to.0,
+            y2 = path.to.1,
+            stroke = palette.connection
+        );
+        if let Some(ref label) = path.label {
+            write_label(&mut svg, label, palette.connection);
         }
+        svg.push_str("</g>");
     }
 
     // Time axis at bottom
-    let axis_y = header_height + (num_tracks as f64) * track_height + 10.0;
+    let axis = &scene.axis;
     let _ = write!(
         svg,
-        r##"<line x1="{px}" y1="{ay}" x2="{x2}" y2="{ay}" stroke="#d1d5db" stroke-width="1"/>"##,
-        px = padding,
-        ay = axis_y,
-        x2 = width - padding
+        r##"<line x1="{x1}" y1="{ay}" x2="{x2}" y2="{ay}" stroke="{stroke}" stroke-width="1"/>"##,
+        x1 = axis.x1,
+        ay = axis.y,
+        x2 = axis.x2,
+        stroke = palette.axis
     );
-
-    // Axis tick marks (approximately every 365 days)
-    let tick_interval_days = 365.0;
-    let mut tick_day = (min_days / tick_interval_days).ceil() * tick_interval_days;
-    while tick_day <= max_days {
-        let tx = (tick_day - min_days) * pixels_per_day + padding;
-        if tx >= padding && tx <= width - padding {
-            let _ = write!(
-                svg,
-                r##"<line x1="{tx}" y1="{y1}" x2="{tx}" y2="{y2}" stroke="#d1d5db" stroke-width="1"/>"##,
-                tx = tx,
-                y1 = axis_y - 4.0,
-                y2 = axis_y + 4.0
-            );
-            // Approximate year label from epoch days
-            let approx_year = 1970.0 + tick_day / 365.25;
-            let _ = write!(
-                svg,
-                r##"<text x="{tx}" y="{ty}" font-family="sans-serif" font-size="10" text-anchor="middle" fill="#6b7280">{yr}</text>"##,
-                tx = tx,
-                ty = axis_y + 18.0,
-                yr = approx_year as i64
-            );
-        }
-        tick_day += tick_interval_days;
+    for (tx, label) in &axis.ticks {
+        let _ = write!(
+            svg,
+            r##"<line x1="{tx}" y1="{y1}" x2="{tx}" y2="{y2}" stroke="{stroke}" stroke-width="1"/>"##,
+            y1 = axis.y - 4.0,
+            y2 = axis.y + 4.0,
+            stroke = palette.axis
+        );
+        write_label(&mut svg, label, palette.muted);
     }
 
     svg.push_str("</svg>");
@@ -372,6 +189,7 @@ pub fn generate_svg(conn: &Connection, timeline_id: &str) -> AppResult<String> {
 mod tests {
     use super::*;
     use crate::db::init_test_db;
+    use crate::export::layout::{axis_ticks, Theme, AUTO_MAX_WIDTH};
     use rusqlite::params;
 
     fn setup_test_data(conn: &Connection) -> String {
@@ -432,24 +250,12 @@ mod tests {
         assert_eq!(escape_xml("plain"), "plain");
     }
 
-    #[test]
-    fn test_date_to_epoch_days() {
-        // 1970-01-01 should be day 0
-        assert_eq!(date_to_epoch_days("1970-01-01"), Some(0.0));
-        // 1970-01-02 should be day 1
-        assert_eq!(date_to_epoch_days("1970-01-02"), Some(1.0));
-        // Invalid date
-        assert_eq!(date_to_epoch_days("not-a-date"), None);
-        // ISO datetime
-        assert!(date_to_epoch_days("2000-01-01T12:00:00").is_some());
-    }
-
     #[test]
     fn test_generate_svg_basic() {
         let conn = init_test_db().expect("init test db");
         let tl_id = setup_test_data(&conn);
 
-        let svg = generate_svg(&conn, &tl_id).expect("generate svg");
+        let svg = generate_svg(&conn, &tl_id, &LayoutOptions::default()).expect("generate svg");
 
         assert!(svg.starts_with("<svg"));
         assert!(svg.ends_with("</svg>"));
@@ -470,7 +276,7 @@ mod tests {
     #[test]
     fn test_generate_svg_not_found() {
         let conn = init_test_db().expect("init test db");
-        let result = generate_svg(&conn, "nonexistent-id");
+        let result = generate_svg(&conn, "nonexistent-id", &LayoutOptions::default());
         assert!(result.is_err());
     }
 
@@ -484,7 +290,7 @@ mod tests {
         )
         .expect("insert timeline");
 
-        let svg = generate_svg(&conn, &tl_id).expect("generate svg");
+        let svg = generate_svg(&conn, &tl_id, &LayoutOptions::default()).expect("generate svg");
         assert!(svg.starts_with("<svg"));
         assert!(svg.contains("Empty"));
     }
@@ -515,9 +321,114 @@ mod tests {
         )
         .expect("insert event");
 
-        let svg = generate_svg(&conn, &tl_id).expect("generate svg");
+        let svg = generate_svg(&conn, &tl_id, &LayoutOptions::default()).expect("generate svg");
         assert!(svg.contains("War &amp; Peace &lt;1800&gt;"));
         assert!(svg.contains("Event &amp; &lt;Test&gt;"));
         assert!(svg.contains("Track &quot;A&quot;"));
     }
+
+    #[test]
+    fn test_generate_svg_options() {
+        let conn = init_test_db().expect("init test db");
+        let tl_id = setup_test_data(&conn);
+        conn.execute("UPDATE tracks SET visible = 0 WHERE name = 'Science'", [])
+            .expect("hide track");
+
+        let options = LayoutOptions {
+            width: Some(1000.0),
+            theme: Theme::Dark,
+            font_family: "Inter".to_string(),
+            ..Default::default()
+        };
+        let svg = generate_svg(&conn, &tl_id, &options).expect("generate svg");
+        assert!(svg.contains(r#"width="1000" height="#));
+        assert!(svg.contains(r#"font-family="Inter""#));
+        assert!(svg.contains("#111827"));
+        // Hidden tracks are still drawn unless asked otherwise
+        assert!(svg.contains("Relativity"));
+
+        let visible = LayoutOptions { visible_tracks_only: true, ..Default::default() };
+        let svg = generate_svg(&conn, &tl_id, &visible).expect("generate svg");
+        assert!(svg.contains("Revolution"));
+        assert!(!svg.contains("Relativity"));
+        assert!(!svg.contains("Science"));
+        // Its connection lost an end, so it is not drawn either
+        assert!(!svg.contains("inspired"));
+
+        let important = LayoutOptions { min_importance: 4, ..Default::default() };
+        let svg = generate_svg(&conn, &tl_id, &important).expect("generate svg");
+        assert!(svg.contains("Revolution") && !svg.contains("Relativity"));
+
+        let tagged = LayoutOptions { tags: vec!["Science".to_string()], ..Default::default() };
+        let svg = generate_svg(&conn, &tl_id, &tagged).expect("generate svg");
+        assert!(!svg.contains("Revolution") && svg.contains("Relativity"));
+
+        let window = LayoutOptions {
+            start: Some("1900".to_string()),
+            end: Some("1910".to_string()),
+            ..Default::default()
+        };
+        let svg = generate_svg(&conn, &tl_id, &window).expect("generate svg");
+        assert!(!svg.contains("Revolution") && svg.contains("Relativity"));
+        assert!(svg.contains(">1905-07<"));
+
+        let reversed = LayoutOptions {
+            start: Some("1910".to_string()),
+            end: Some("1900".to_string()),
+            ..Default::default()
+        };
+        assert!(generate_svg(&conn, &tl_id, &reversed).is_err());
+    }
+
+    #[test]
+    fn test_auto_fit_and_axis_ticks() {
+        let conn = init_test_db().expect("init test db");
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Deep time');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('tr', 'tl', 'All', '#000000', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, event_type, importance, tags)
+             VALUES ('a', 'tl', 'tr', 'Farming', '8000 BCE', 'point', 3, ''),
+                    ('b', 'tl', 'tr', 'Today', '2024', 'point', 3, '');",
+        )
+        .expect("insert data");
+
+        let svg = generate_svg(&conn, "tl", &LayoutOptions::default()).expect("generate svg");
+        assert!(svg.contains(&format!(r#"width="{AUTO_MAX_WIDTH}""#)));
+        assert!(svg.contains(">5000 BCE<"));
+        assert!(!svg.contains(">5001 BCE<"));
+        assert!(svg.matches("<line").count() < 100);
+
+        // A two-week span is stretched and labelled by day
+        let ticks = axis_ticks(19_723.0, 19_737.0, 2.0);
+        assert_eq!(ticks.first().map(|t| t.1.as_str()), Some("2024-01-01"));
+        assert_eq!(ticks.get(1).map(|t| t.1.as_str()), Some("2024-01-03"));
+    }
+
+    #[test]
+    fn test_overlapping_events_do_not_collide() {
+        let conn = init_test_db().expect("init test db");
+        conn.execute_batch(
+            "INSERT INTO timelines (id, title) VALUES ('tl', 'Busy');
+             INSERT INTO tracks (id, timeline_id, name, color, sort_order) VALUES ('tr', 'tl', 'Wars', '#000000', 0);
+             INSERT INTO events (id, timeline_id, track_id, title, start_date, end_date, event_type, importance, tags)
+             VALUES ('a', 'tl', 'tr', 'Thirty Years War', '1618', '1648', 'range', 3, ''),
+                    ('b', 'tl', 'tr', 'Eighty Years War', '1568', '1648', 'range', 3, ''),
+                    ('c', 'tl', 'tr', 'Defenestration', '1618-05-23', NULL, 'point', 3, ''),
+                    ('d', 'tl', 'tr', 'Bohemian Revolt', '1618-06-01', NULL, 'point', 3, '');",
+        )
+        .expect("insert data");
+
+        let svg = generate_svg(&conn, "tl", &LayoutOptions::default()).expect("generate svg");
+        let bar_ys: Vec<&str> = svg
+            .split(r#"<rect x=""#)
+            .skip(2)
+            .filter_map(|r| r.split(r#"y=""#).nth(1)?.split('"').next())
+            .collect();
+        assert_eq!(bar_ys.len(), 2);
+        assert_ne!(bar_ys[0], bar_ys[1]);
+        // The second crowded point label is lifted and gets a leader line
+        assert_eq!(svg.matches(r#"stroke-width="0.75""#).count(), 1);
+        // The track grew past its minimum height
+        assert!(!svg.contains(r##"height="60" fill="#000000""##));
+    }
 }
@@ -0,0 +1,246 @@
+(function () {
+  "use strict";
+
+  var data = JSON.parse(document.getElementById("timeline-data").textContent);
+  var stage = document.getElementById("stage");
+  var svg = stage.querySelector("svg");
+  var details = document.getElementById("details");
+  var search = document.getElementById("search");
+  var searchCount = document.getElementById("search-count");
+
+  var eventsById = {};
+  data.events.forEach(function (e) { eventsById[e.id] = e; });
+  var tracksById = {};
+  data.tracks.forEach(function (t) { tracksById[t.id] = t; });
+  var hiddenTracks = {};
+
+  // ---- Pan and zoom (by rewriting the viewBox) ----
+
+  var initial = svg.getAttribute("viewBox").split(/\s+/).map(Number);
+  var view = initial.slice();
+  svg.removeAttribute("width");
+  svg.removeAttribute("height");
+
+  function applyView() {
+    svg.setAttribute("viewBox", view.join(" "));
+  }
+
+  function toSvgPoint(clientX, clientY) {
+    var rect = svg.getBoundingClientRect();
+    var scale = Math.max(view[2] / rect.width, view[3] / rect.height);
+    var offsetX = (rect.width * scale - view[2]) / 2;
+    var offsetY = (rect.height * scale - view[3]) / 2;
+    return {
+      x: view[0] + (clientX - rect.left) * scale - offsetX,
+      y: view[1] + (clientY - rect.top) * scale - offsetY,
+      scale: scale
+    };
+  }
+
+  stage.addEventListener("wheel", function (ev) {
+    ev.preventDefault();
+    var factor = ev.deltaY < 0 ? 0.85 : 1 / 0.85;
+    var p = toSvgPoint(ev.clientX, ev.clientY);
+    var width = Math.min(Math.max(view[2] * factor, initial[2] / 500), initial[2] * 4);
+    var k = width / view[2];
+    view = [p.x - (p.x - view[0]) * k, p.y - (p.y - view[1]) * k, view[2] * k, view[3] * k];
+    applyView();
+  }, { passive: false });
+
+  var drag = null;
+  stage.addEventListener("mousedown", function (ev) {
+    if (ev.button !== 0) return;
+    drag = { x: ev.clientX, y: ev.clientY, view: view.slice(), moved: false };
+    stage.classList.add("panning");
+  });
+  window.addEventListener("mousemove", function (ev) {
+    if (!drag || drag.done) return;
+    var scale = toSvgPoint(ev.clientX, ev.clientY).scale;
+    var dx = ev.clientX - drag.x;
+    var dy = ev.clientY - drag.y;
+    if (Math.abs(dx) + Math.abs(dy) > 3) drag.moved = true;
+    view = [drag.view[0] - dx * scale, drag.view[1] - dy * scale, view[2], view[3]];
+    applyView();
+  });
+  window.addEventListener("mouseup", function () {
+    if (!drag) return;
+    drag.done = true;
+    stage.classList.remove("panning");
+    setTimeout(function () { drag = null; }, 0);
+  });
+
+  document.getElementById("reset-view").addEventListener("click", function () {
+    view = initial.slice();
+    applyView();
+  });
+
+  // ---- Track toggles ----
+
+  var nav = document.getElementById("tracks");
+  data.tracks.forEach(function (track) {
+    var label = document.createElement("label");
+    var box = document.createElement("input");
+    box.type = "checkbox";
+    box.checked = true;
+    box.addEventListener("change", function () {
+      hiddenTracks[track.id] = !box.checked;
+      refresh();
+    });
+    var swatch = document.createElement("span");
+    swatch.className = "swatch";
+    swatch.style.background = track.color;
+    label.appendChild(box);
+    label.appendChild(swatch);
+    label.appendChild(document.createTextNode(track.name));
+    nav.appendChild(label);
+  });
+
+  // ---- Search ----
+
+  function matches(event, query) {
+    if (!query) return true;
+    var haystack = [event.title, event.description, event.tags, event.source || ""].join(" ").toLowerCase();
+    return query.split(/\s+/).every(function (word) { return haystack.indexOf(word) !== -1; });
+  }
+
+  search.addEventListener("input", refresh);
+
+  function refresh() {
+    var query = search.value.trim().toLowerCase();
+    var found = 0;
+    svg.querySelectorAll(".lane").forEach(function (g) {
+      g.classList.toggle("hidden", !!hiddenTracks[g.dataset.trackId]);
+    });
+    svg.querySelectorAll(".event").forEach(function (g) {
+      var event = eventsById[g.dataset.eventId];
+      var hidden = !!hiddenTracks[g.dataset.trackId];
+      var hit = !!event && matches(event, query);
+      g.classList.toggle("hidden", hidden);
+      g.classList.toggle("dimmed", !hit);
+      if (hit && !hidden && query) found++;
+    });
+    svg.querySelectorAll(".connection").forEach(function (g) {
+      var source = eventsById[g.dataset.source];
+      var target = eventsById[g.dataset.target];
+      var hidden = !source || !target || hiddenTracks[source.trackId] || hiddenTracks[target.trackId];
+      g.classList.toggle("hidden", !!hidden);
+      g.classList.toggle("dimmed", !!query && !(matches(source, query) && matches(target, query)));
+    });
+    searchCount.textContent = query ? found + (found === 1 ? " match" : " matches") : "";
+  }
+
+  // ---- Details panel ----
+
+  function escapeHtml(s) {
+    return String(s)
+      .replace(/&/g, "&amp;")
+      .replace(/</g, "&lt;")
+      .replace(/>/g, "&gt;")
+      .replace(/"/g, "&quot;");
+  }
+
+  function safeUrl(url) {
+    return /^(https?:|mailto:)/i.test(url) ? url : "#";
+  }
+
+  function inline(text) {
+    return escapeHtml(text)
+      .replace(/`([^`]+)`/g, "<code>$1</code>")
+      .replace(/\*\*([^*]+)\*\*/g, "<strong>$1</strong>")
+      .replace(/(^|[^*])\*([^*]+)\*/g, "$1<em>$2</em>")
+      .replace(/\[([^\]]+)\]\(([^)\s]+)\)/g, function (_, label, url) {
+        return '<a href="' + safeUrl(url) + '" target="_blank" rel="noopener">' + label + "</a>";
+      });
+  }
+
+  // A small Markdown subset: headings, lists, code fences, emphasis and links.
+  function renderMarkdown(source) {
+    var out = [];
+    var paragraph = [];
+    var list = null;
+    var code = null;
+
+    function flush() {
+      if (paragraph.length) {
+        out.push("<p>" + paragraph.map(inline).join("<br>") + "</p>");
+        paragraph = [];
+      }
+      if (list) {
+        out.push("<" + list.tag + ">" + list.items.map(function (i) { return "<li>" + inline(i) + "</li>"; }).join("") + "</" + list.tag + ">");
+        list = null;
+      }
+    }
+
+    source.split(/\r?\n/).forEach(function (line) {
+      if (code !== null) {
+        if (/^```/.test(line)) {
+          out.push("<pre><code>" + escapeHtml(code.join("\n")) + "</code></pre>");
+          code = null;
+        } else {
+          code.push(line);
+        }
+        return;
+      }
+      var m;
+      if (/^```/.test(line)) {
+        flush();
+        code = [];
+      } else if ((m = /^(#{1,6})\s+(.*)$/.exec(line))) {
+        flush();
+        var level = Math.min(m[1].length + 2, 6);
+        out.push("<h" + level + ">" + inline(m[2]) + "</h" + level + ">");
+      } else if ((m = /^\s*([-*+]|\d+[.)])\s+(.*)$/.exec(line))) {
+        var tag = /\d/.test(m[1]) ? "ol" : "ul";
+        if (paragraph.length || (list && list.tag !== tag)) flush();
+        if (!list) list = { tag: tag, items: [] };
+        list.items.push(m[2]);
+      } else if (!line.trim()) {
+        flush();
+      } else {
+        if (list) flush();
+        paragraph.push(line);
+      }
+    });
+    if (code !== null) out.push("<pre><code>" + escapeHtml(code.join("\n")) + "</code></pre>");
+    flush();
+    return out.join("\n");
+  }
+
+  function showDetails(event) {
+    svg.querySelectorAll(".event.selected").forEach(function (g) { g.classList.remove("selected"); });
+    var g = svg.querySelector('.event[data-event-id="' + CSS.escape(event.id) + '"]');
+    if (g) g.classList.add("selected");
+
+    var track = tracksById[event.trackId];
+    var dates = event.endDate ? event.startDate + " — " + event.endDate : event.startDate;
+    var html = '<button class="close" type="button" title="Close">×</button>';
+    html += "<h2>" + escapeHtml(event.title) + "</h2>";
+    html += '<div class="meta">' + escapeHtml(dates);
+    if (track) html += " · " + escapeHtml(track.name);
+    html += " · importance " + event.importance + "/5</div>";
+    var tags = event.tags.split(",").map(function (t) { return t.trim(); }).filter(Boolean);
+    if (tags.length) {
+      html += "<div>" + tags.map(function (t) { return '<span class="tag">' + escapeHtml(t) + "</span>"; }).join("") + "</div>";
+    }
+    if (event.description) html += '<div class="description">' + renderMarkdown(event.description) + "</div>";
+    if (event.source) html += '<p class="meta">Source: ' + inline(event.source) + "</p>";
+    if (event.externalLink) {
+      html += '<p><a href="' + escapeHtml(safeUrl(event.externalLink)) + '" target="_blank" rel="noopener">' + escapeHtml(event.externalLink) + "</a></p>";
+    }
+    details.innerHTML = html;
+    details.hidden = false;
+    details.querySelector(".close").addEventListener("click", function () {
+      details.hidden = true;
+      if (g) g.classList.remove("selected");
+    });
+  }
+
+  svg.addEventListener("click", function (ev) {
+    if (drag && drag.moved) return;
+    var g = ev.target.closest(".event");
+    if (g && eventsById[g.dataset.eventId]) showDetails(eventsById[g.dataset.eventId]);
+  });
+
+  applyView();
+  refresh();
+})();
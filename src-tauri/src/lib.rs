@@ -1,18 +1,23 @@
 mod ai;
+pub mod cli;
 mod commands;
 mod db;
 mod error;
 mod export;
 
+#[cfg(feature = "desktop")]
 use std::sync::Mutex;
 
+#[cfg(feature = "desktop")]
 use tauri::Manager;
 
+#[cfg(feature = "desktop")]
 use commands::{
-    ai as ai_cmd, connections, events, export as export_cmd, import, search, settings, templates,
-    timelines, tracks,
+    ai as ai_cmd, connections, events, export as export_cmd, gedcom, history, ical, import,
+    markdown, merge, revisions, search, settings, templates, timelines, tracks, vault, wikidata,
 };
 
+#[cfg(feature = "desktop")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tracing_subscriber::fmt::init();
@@ -49,29 +54,51 @@ pub fn run() {
             events::create_event,
             events::get_event,
             events::list_events,
+            events::list_undated_events,
             events::update_event,
             events::delete_event,
             events::bulk_delete_events,
             events::bulk_update_events,
+            // Revisions
+            revisions::list_event_revisions,
+            revisions::diff_event_revisions,
+            revisions::restore_event_revision,
             // Connections
             connections::create_connection,
             connections::list_connections,
             connections::update_connection,
             connections::delete_connection,
+            // History
+            history::undo,
+            history::redo,
+            history::get_history_status,
             // Search
             search::search_events,
             // Import
             import::import_json,
+            import::import_timelinejs,
             import::import_csv,
+            import::preview_csv,
+            ical::import_ics,
+            wikidata::import_wikidata_json,
+            gedcom::import_gedcom,
+            markdown::import_markdown,
+            merge::merge_import_json,
             // Export
             export_cmd::export_json,
+            export_cmd::export_timelinejs,
             export_cmd::export_csv,
+            export_cmd::export_ics,
             export_cmd::export_markdown,
+            vault::export_obsidian_vault,
             export_cmd::save_file,
             export_cmd::read_file,
             export_cmd::show_save_dialog,
             export_cmd::show_open_dialog,
+            export_cmd::show_folder_dialog,
             export_cmd::export_svg,
+            export_cmd::export_html,
+            export_cmd::export_png,
             export_cmd::export_pdf,
             // Templates
             templates::list_templates,
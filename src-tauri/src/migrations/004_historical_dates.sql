@@ -0,0 +1,4 @@
+ALTER TABLE events ADD COLUMN start_sort_key REAL;
+ALTER TABLE events ADD COLUMN end_sort_key REAL;
+
+CREATE INDEX IF NOT EXISTS idx_events_sort ON events(timeline_id, start_sort_key);
@@ -0,0 +1,12 @@
+-- Undo/redo journal. Not tied to timelines by foreign key so that a deleted
+-- timeline can still be restored.
+CREATE TABLE IF NOT EXISTS journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timeline_id TEXT NOT NULL,
+    label TEXT NOT NULL,
+    changes TEXT NOT NULL,
+    undone INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_journal_timeline ON journal(timeline_id, undone, id);
@@ -0,0 +1,28 @@
+-- Prior states of events, one row per update. Kept independent of the events
+-- table so that history survives a delete that is later undone.
+CREATE TABLE IF NOT EXISTS event_revisions (
+    id TEXT PRIMARY KEY NOT NULL,
+    event_id TEXT NOT NULL,
+    timeline_id TEXT NOT NULL,
+    revision INTEGER NOT NULL,
+    track_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    start_date TEXT NOT NULL,
+    end_date TEXT,
+    event_type TEXT NOT NULL,
+    importance INTEGER NOT NULL,
+    color TEXT,
+    icon TEXT,
+    image_path TEXT,
+    external_link TEXT,
+    tags TEXT NOT NULL DEFAULT '',
+    source TEXT,
+    changed_by TEXT,
+    changed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (event_id, revision)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_revisions_event ON event_revisions(event_id, revision);
+
+INSERT OR IGNORE INTO settings (key, value) VALUES ('author_name', '');
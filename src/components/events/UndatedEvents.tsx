@@ -0,0 +1,55 @@
+import { useState } from "react";
+import { useEventStore } from "../../stores/event-store";
+import type { UndatedEvent } from "../../lib/types";
+import { Input } from "../common/Input";
+import { Button } from "../common/Button";
+
+/** Events whose stored start date could not be read, each with a field to set a new one. */
+export function UndatedEvents() {
+  const undatedEvents = useEventStore((s) => s.undatedEvents);
+
+  if (undatedEvents.length === 0) return null;
+
+  return (
+    <div className="border-t border-border px-3 py-2 flex flex-col gap-2">
+      <span className="text-xs font-semibold text-danger uppercase tracking-wide">
+        Unreadable dates ({undatedEvents.length})
+      </span>
+      {undatedEvents.map((ev) => (
+        <UndatedEventRow key={ev.id} event={ev} />
+      ))}
+    </div>
+  );
+}
+
+function UndatedEventRow({ event }: { event: UndatedEvent }) {
+  const fixUndatedEvent = useEventStore((s) => s.fixUndatedEvent);
+  const [startDate, setStartDate] = useState("");
+  const [saving, setSaving] = useState(false);
+
+  const save = async () => {
+    setSaving(true);
+    try {
+      await fixUndatedEvent(event.id, startDate.trim());
+    } catch {
+      // The store reports the error
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-1">
+      <Input
+        id={`undated-${event.id}`}
+        label={`${event.title} — "${event.startDate}"`}
+        placeholder="YYYY-MM-DD"
+        value={startDate}
+        onChange={(e) => setStartDate(e.target.value)}
+      />
+      <Button size="sm" onClick={save} disabled={saving || !startDate.trim()}>
+        Set date
+      </Button>
+    </div>
+  );
+}
@@ -8,6 +8,9 @@ import {
   Image,
   FileCode,
   FileOutput,
+  CalendarDays,
+  FolderTree,
+  Globe,
 } from "lucide-react";
 import { Button } from "../common/Button";
 import { cn } from "../../lib/utils";
@@ -17,6 +20,8 @@ import * as cmd from "../../lib/commands";
 import { invoke } from "@tauri-apps/api/core";
 import { PngExportDialog } from "./PngExportDialog";
 import { PdfOptionsDialog } from "./PdfOptionsDialog";
+import { MarkdownOptionsDialog } from "./MarkdownOptionsDialog";
+import { SvgOptionsDialog } from "./SvgOptionsDialog";
 
 async function showSaveDialog(
   defaultName: string,
@@ -36,10 +41,12 @@ export function ExportMenu() {
   const [exporting, setExporting] = useState(false);
   const [pngDialogOpen, setPngDialogOpen] = useState(false);
   const [pdfDialogOpen, setPdfDialogOpen] = useState(false);
+  const [markdownDialogOpen, setMarkdownDialogOpen] = useState(false);
+  const [svgDialogOpen, setSvgDialogOpen] = useState(false);
   const activeTimelineId = useTimelineStore((s) => s.activeTimelineId);
   const addToast = useToastStore((s) => s.addToast);
 
-  const handleExport = async (format: "json" | "csv" | "markdown") => {
+  const handleExport = async (format: "json" | "timelinejs" | "csv" | "ics") => {
     if (!activeTimelineId) return;
     setExporting(true);
     setOpen(false);
@@ -57,17 +64,23 @@ export function ExportMenu() {
           filterLabel = "JSON";
           extension = "json";
           break;
+        case "timelinejs":
+          content = await cmd.exportTimelineJs(activeTimelineId);
+          defaultName = "timeline-timelinejs.json";
+          filterLabel = "TimelineJS JSON";
+          extension = "json";
+          break;
         case "csv":
           content = await cmd.exportCsv(activeTimelineId);
           defaultName = "timeline.csv";
           filterLabel = "CSV";
           extension = "csv";
           break;
-        case "markdown":
-          content = await cmd.exportMarkdown(activeTimelineId);
-          defaultName = "timeline.md";
-          filterLabel = "Markdown";
-          extension = "md";
+        case "ics":
+          content = await cmd.exportIcs(activeTimelineId);
+          defaultName = "timeline.ics";
+          filterLabel = "iCalendar";
+          extension = "ics";
           break;
       }
 
@@ -93,28 +106,55 @@ export function ExportMenu() {
     }
   };
 
-  const handleSvgExport = async () => {
+  const handleVaultExport = async () => {
+    if (!activeTimelineId) return;
+    setOpen(false);
+
+    try {
+      const directory = await invoke<string | null>("show_folder_dialog");
+      if (!directory) return;
+
+      setExporting(true);
+      const report = await cmd.exportObsidianVault(activeTimelineId, directory);
+      addToast({
+        type: "success",
+        title: "Vault exported",
+        description: `${report.notesWritten} notes written to ${report.directory}.`,
+      });
+    } catch (e) {
+      console.error("Vault export failed:", e);
+      addToast({
+        type: "error",
+        title: "Vault export failed",
+        description: String(e),
+      });
+    } finally {
+      setExporting(false);
+    }
+  };
+
+  const handleHtmlExport = async () => {
     if (!activeTimelineId) return;
     setExporting(true);
     setOpen(false);
 
     try {
-      const content = await cmd.exportSvg(activeTimelineId);
-      const filePath = await showSaveDialog("timeline.svg", "SVG", "svg");
+      const content = await cmd.exportHtml(activeTimelineId);
+      const filePath = await showSaveDialog("timeline.html", "HTML", "html");
 
       if (filePath) {
         await cmd.saveFile(filePath, content);
         addToast({
           type: "success",
-          title: "SVG exported",
-          description: "Timeline saved as SVG.",
+          title: "HTML exported",
+          description: "Interactive timeline saved as a single HTML file.",
         });
       }
     } catch (e) {
-      console.error("SVG export failed:", e);
+      console.error("HTML export failed:", e);
       addToast({
         type: "error",
-        title: "SVG export failed",
+        title: "HTML export failed",
         description: String(e),
       });
     } finally {
@@ -149,6 +189,13 @@ export function ExportMenu() {
               <FileJson size={14} className="text-text-secondary" />
               Export as JSON
             </button>
+            <button
+              onClick={() => handleExport("timelinejs")}
+              className="w-full flex items-center gap-2 px-3 py-2 text-xs text-text hover:bg-bg-tertiary transition-colors cursor-pointer"
+            >
+              <FileJson size={14} className="text-text-secondary" />
+              Export as TimelineJS
+            </button>
             <button
               onClick={() => handleExport("csv")}
               className="w-full flex items-center gap-2 px-3 py-2 text-xs text-text hover:bg-bg-tertiary transition-colors cursor-pointer"
@@ -157,12 +204,29 @@ export function ExportMenu() {
               Export as CSV
             </button>
             <button
-              onClick={() => handleExport("markdown")}
+              onClick={() => handleExport("ics")}
+              className="w-full flex items-center gap-2 px-3 py-2 text-xs text-text hover:bg-bg-tertiary transition-colors cursor-pointer"
+            >
+              <CalendarDays size={14} className="text-text-secondary" />
+              Export as iCalendar
+            </button>
+            <button
+              onClick={() => {
+                setOpen(false);
+                setMarkdownDialogOpen(true);
+              }}
               className="w-full flex items-center gap-2 px-3 py-2 text-xs text-text hover:bg-bg-tertiary transition-colors cursor-pointer"
             >
               <FileType size={14} className="text-text-secondary" />
               Export as Markdown
             </button>
+            <button
+              onClick={handleVaultExport}
+              className="w-full flex items-center gap-2 px-3 py-2 text-xs text-text hover:bg-bg-tertiary transition-colors cursor-pointer"
+            >
+              <FolderTree size={14} className="text-text-secondary" />
+              Export as Obsidian vault
+            </button>
 
             {/* Separator */}
             <div className="border-t border-border" />
@@ -179,12 +243,22 @@ export function ExportMenu() {
               Export as PNG
             </button>
             <button
-              onClick={handleSvgExport}
+              onClick={() => {
+                setOpen(false);
+                setSvgDialogOpen(true);
+              }}
               className="w-full flex items-center gap-2 px-3 py-2 text-xs text-text hover:bg-bg-tertiary transition-colors cursor-pointer"
             >
               <FileCode size={14} className="text-text-secondary" />
               Export as SVG
             </button>
+            <button
+              onClick={handleHtmlExport}
+              className="w-full flex items-center gap-2 px-3 py-2 text-xs text-text hover:bg-bg-tertiary transition-colors cursor-pointer"
+            >
+              <Globe size={14} className="text-text-secondary" />
+              Export as interactive HTML
+            </button>
             <button
               onClick={() => {
                 setOpen(false);
@@ -207,6 +281,14 @@ export function ExportMenu() {
         open={pdfDialogOpen}
         onClose={() => setPdfDialogOpen(false)}
       />
+      <SvgOptionsDialog
+        open={svgDialogOpen}
+        onClose={() => setSvgDialogOpen(false)}
+      />
+      <MarkdownOptionsDialog
+        open={markdownDialogOpen}
+        onClose={() => setMarkdownDialogOpen(false)}
+      />
     </>
   );
 }
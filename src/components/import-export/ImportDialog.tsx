@@ -1,13 +1,24 @@
 import { useState, useCallback } from "react";
-import { Upload, FileJson, FileText, ArrowRight } from "lucide-react";
+import { Upload, FileJson, FileText, ArrowRight, GitMerge, Globe, CalendarDays, Users, Newspaper, FileType } from "lucide-react";
 import { Modal } from "../common/Modal";
 import { Button } from "../common/Button";
 import { LoadingSpinner } from "../common/LoadingSpinner";
 import { useTimelineStore } from "../../stores/timeline-store";
+import { useTrackStore } from "../../stores/track-store";
+import { useToastStore } from "../../stores/toast-store";
+import { useHistoryStore, reloadTimeline } from "../../stores/history-store";
 import * as cmd from "../../lib/commands";
 import { invoke } from "@tauri-apps/api/core";
 
-type ImportMode = "select" | "csv-map";
+type FileImportFormat = "timeline" | "timelinejs" | "wikidata" | "gedcom" | "markdown";
+
+type ImportMode = "select" | "csv-map" | "csv-result" | "merge-preview" | "ics-track";
+
+const STRATEGY_LABELS: Record<cmd.ConflictStrategy, string> = {
+  keepMine: "Keep mine",
+  takeTheirs: "Take theirs",
+  keepBoth: "Keep both",
+};
 
 interface ColumnMapping {
   title: string;
@@ -16,6 +27,11 @@ interface ColumnMapping {
   endDate: string;
   eventType: string;
   importance: string;
+  tags: string;
+  trackName: string;
+  color: string;
+  source: string;
+  externalLink: string;
 }
 
 async function showOpenDialog(
@@ -42,7 +58,7 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [csvData, setCsvData] = useState<string | null>(null);
-  const [csvColumns, setCsvColumns] = useState<string[]>([]);
+  const [csvColumns, setCsvColumns] = useState<cmd.CsvColumn[]>([]);
   const [columnMapping, setColumnMapping] = useState<ColumnMapping>({
     title: "",
     description: "",
@@ -50,21 +66,60 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
     endDate: "",
     eventType: "",
     importance: "",
+    tags: "",
+    trackName: "",
+    color: "",
+    source: "",
+    externalLink: "",
   });
+  const [csvDelimiter, setCsvDelimiter] = useState("");
+  const [csvDetectedDelimiter, setCsvDetectedDelimiter] = useState(",");
+  const [csvDateFormats, setCsvDateFormats] = useState("");
+  const [csvReport, setCsvReport] = useState<cmd.CsvImportReport | null>(null);
+  const [mergeData, setMergeData] = useState<string | null>(null);
+  const [mergePreview, setMergePreview] = useState<cmd.MergeReport | null>(null);
+  const [resolutions, setResolutions] = useState<Record<string, cmd.ConflictStrategy>>({});
+  const [icsTrackId, setIcsTrackId] = useState("");
 
   const { activeTimelineId, loadTimelines } = useTimelineStore();
+  const tracks = useTrackStore((s) => s.tracks);
 
-  const handleJsonImport = useCallback(async () => {
+  const handleFileImport = useCallback(async (format: FileImportFormat) => {
     try {
-      const filePath = await showOpenDialog("JSON", ["json"]);
+      const filePath =
+        format === "gedcom"
+          ? await showOpenDialog("GEDCOM", ["ged"])
+          : format === "markdown"
+            ? await showOpenDialog("Markdown", ["md", "markdown"])
+            : await showOpenDialog("JSON", ["json"]);
       if (!filePath) return;
 
       setLoading(true);
       setError(null);
 
       const content = await readFileContents(filePath);
-      await cmd.importJson(content);
+      const fileTitle = filePath.split(/[\\/]/).pop()?.replace(/\.(json|ged|md|markdown)$/i, "") ?? "Imported";
+      const report =
+        format === "wikidata"
+          ? await cmd.importWikidataJson(fileTitle, content)
+          : format === "gedcom"
+            ? await cmd.importGedcom(fileTitle, content)
+            : format === "timelinejs"
+              ? await cmd.importTimelineJs(content)
+              : format === "markdown"
+                ? await cmd.importMarkdown(fileTitle, content)
+                : await cmd.importJson(content);
       await loadTimelines();
+      const problems =
+        report.skipped.length + report.unresolvedConnections.length + report.duplicateTitles.length;
+      useToastStore.getState().addToast({
+        type: problems > 0 ? "warning" : "success",
+        title: `Imported ${report.eventsCreated} events`,
+        description:
+          problems > 0
+            ? `${report.skipped.length} skipped, ${report.unresolvedConnections.length} unresolved connections, ${report.duplicateTitles.length} duplicate titles`
+            : undefined,
+      });
       onClose();
     } catch (e) {
       setError(String(e));
@@ -73,6 +128,83 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
     }
   }, [loadTimelines, onClose]);
 
+  const handleMergeSelect = useCallback(async () => {
+    if (!activeTimelineId) return;
+    try {
+      const filePath = await showOpenDialog("JSON", ["json"]);
+      if (!filePath) return;
+
+      setLoading(true);
+      setError(null);
+
+      const content = await readFileContents(filePath);
+      const preview = await cmd.mergeImportJson(activeTimelineId, content, { dryRun: true });
+      setMergeData(content);
+      setMergePreview(preview);
+      setResolutions(
+        Object.fromEntries(preview.conflicts.map((c) => [c.eventId, "keepMine" as const]))
+      );
+      setMode("merge-preview");
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setLoading(false);
+    }
+  }, [activeTimelineId]);
+
+  const handleMergeApply = useCallback(async () => {
+    if (!mergeData || !activeTimelineId) return;
+
+    setLoading(true);
+    setError(null);
+
+    try {
+      const report = await cmd.mergeImportJson(activeTimelineId, mergeData, { resolutions });
+      await reloadTimeline(activeTimelineId);
+      void useHistoryStore.getState().refresh();
+      useToastStore.getState().addToast({
+        type: "success",
+        title: "Merge complete",
+        description: `${report.eventsAdded.length} added, ${report.eventsUpdated.length} updated, ${report.conflicts.length} conflicts resolved`,
+      });
+      onClose();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setLoading(false);
+    }
+  }, [mergeData, activeTimelineId, resolutions, onClose]);
+
+  const csvOptions = useCallback(
+    (delimiter: string): cmd.CsvImportOptions => {
+      const dateFormats = csvDateFormats
+        .split("\n")
+        .map((f) => f.trim())
+        .filter(Boolean);
+      return {
+        delimiter: delimiter || undefined,
+        dateFormats: dateFormats.length > 0 ? dateFormats : undefined,
+      };
+    },
+    [csvDateFormats]
+  );
+
+  const loadCsvPreview = useCallback(
+    async (content: string, delimiter: string) => {
+      const preview = await cmd.previewCsv(content, csvOptions(delimiter));
+      setCsvColumns(preview.columns);
+      setCsvDetectedDelimiter(preview.delimiter);
+      setColumnMapping((prev) => {
+        const mapping = { ...prev };
+        for (const field of Object.keys(mapping) as Array<keyof ColumnMapping>) {
+          mapping[field] = preview.suggestedMapping[field] ?? "";
+        }
+        return mapping;
+      });
+    },
+    [csvOptions]
+  );
+
   const handleCsvSelect = useCallback(async () => {
     try {
       const filePath = await showOpenDialog("CSV", ["csv"]);
@@ -83,30 +215,7 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
 
       const content = await readFileContents(filePath);
       setCsvData(content);
-
-      // Parse header row to get column names
-      const firstLine = content.split("\n")[0];
-      if (firstLine) {
-        const columns = firstLine
-          .split(",")
-          .map((col: string) => col.trim().replace(/^"|"$/g, ""));
-        setCsvColumns(columns);
-
-        // Auto-map columns by name match
-        const autoMap: ColumnMapping = {
-          title: columns.find((col: string) => /title|name/i.test(col)) ?? "",
-          description:
-            columns.find((col: string) => /desc/i.test(col)) ?? "",
-          startDate:
-            columns.find((col: string) => /start|date|begin/i.test(col)) ?? "",
-          endDate: columns.find((col: string) => /end/i.test(col)) ?? "",
-          eventType: columns.find((col: string) => /type/i.test(col)) ?? "",
-          importance:
-            columns.find((col: string) => /importance|priority/i.test(col)) ??
-            "",
-        };
-        setColumnMapping(autoMap);
-      }
+      await loadCsvPreview(content, csvDelimiter);
 
       setMode("csv-map");
     } catch (e) {
@@ -114,7 +223,21 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
     } finally {
       setLoading(false);
     }
-  }, []);
+  }, [loadCsvPreview, csvDelimiter]);
+
+  const handleCsvDelimiterChange = useCallback(
+    async (delimiter: string) => {
+      setCsvDelimiter(delimiter);
+      if (!csvData) return;
+      try {
+        setError(null);
+        await loadCsvPreview(csvData, delimiter);
+      } catch (e) {
+        setError(String(e));
+      }
+    },
+    [csvData, loadCsvPreview]
+  );
 
   const handleCsvImport = useCallback(async () => {
     if (!csvData || !activeTimelineId) return;
@@ -128,21 +251,66 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
         if (value) mapping[key] = value;
       }
 
-      const count = await cmd.importCsv(activeTimelineId, csvData, mapping);
-      console.info(`Imported ${count} events`);
+      const report = await cmd.importCsv(activeTimelineId, csvData, mapping, csvOptions(csvDelimiter));
+      await reloadTimeline(activeTimelineId);
+      void useHistoryStore.getState().refresh();
+      if (report.rows.every((r) => r.status === "imported")) {
+        useToastStore.getState().addToast({
+          type: "success",
+          title: `Imported ${report.eventsCreated} events`,
+        });
+        onClose();
+      } else {
+        setCsvReport(report);
+        setMode("csv-result");
+      }
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setLoading(false);
+    }
+  }, [csvData, activeTimelineId, columnMapping, csvOptions, csvDelimiter, onClose]);
+
+  const handleIcsImport = useCallback(async () => {
+    if (!activeTimelineId || !icsTrackId) return;
+    try {
+      const filePath = await showOpenDialog("iCalendar", ["ics", "ical"]);
+      if (!filePath) return;
+
+      setLoading(true);
+      setError(null);
+
+      const content = await readFileContents(filePath);
+      const report = await cmd.importIcs(activeTimelineId, icsTrackId, content);
+      await reloadTimeline(activeTimelineId);
+      void useHistoryStore.getState().refresh();
+      const problems = report.skipped.length + report.warnings.length;
+      useToastStore.getState().addToast({
+        type: problems > 0 ? "warning" : "success",
+        title: `Imported ${report.eventsCreated} events`,
+        description:
+          problems > 0
+            ? [`${report.skipped.length} skipped`, ...report.warnings].join("; ")
+            : undefined,
+      });
       onClose();
     } catch (e) {
       setError(String(e));
     } finally {
       setLoading(false);
     }
-  }, [csvData, activeTimelineId, columnMapping, onClose]);
+  }, [activeTimelineId, icsTrackId, onClose]);
 
   const handleClose = () => {
     setMode("select");
     setError(null);
     setCsvData(null);
     setCsvColumns([]);
+    setCsvReport(null);
+    setMergeData(null);
+    setMergePreview(null);
+    setResolutions({});
+    setIcsTrackId("");
     onClose();
   };
 
@@ -163,7 +331,7 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
           </p>
 
           <button
-            onClick={handleJsonImport}
+            onClick={() => handleFileImport("timeline")}
             className="w-full flex items-center gap-3 p-3 rounded-lg border border-border hover:bg-bg-tertiary transition-colors cursor-pointer"
           >
             <FileJson size={20} className="text-accent shrink-0" />
@@ -176,6 +344,62 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
             <ArrowRight size={14} className="text-text-muted" />
           </button>
 
+          <button
+            onClick={() => handleFileImport("timelinejs")}
+            className="w-full flex items-center gap-3 p-3 rounded-lg border border-border hover:bg-bg-tertiary transition-colors cursor-pointer"
+          >
+            <Newspaper size={20} className="text-accent shrink-0" />
+            <div className="text-left flex-1">
+              <p className="text-xs font-medium text-text">TimelineJS Import</p>
+              <p className="text-[10px] text-text-muted">
+                Import a Knight Lab TimelineJS JSON file; groups become tracks
+              </p>
+            </div>
+            <ArrowRight size={14} className="text-text-muted" />
+          </button>
+
+          <button
+            onClick={() => handleFileImport("wikidata")}
+            className="w-full flex items-center gap-3 p-3 rounded-lg border border-border hover:bg-bg-tertiary transition-colors cursor-pointer"
+          >
+            <Globe size={20} className="text-accent shrink-0" />
+            <div className="text-left flex-1">
+              <p className="text-xs font-medium text-text">Wikidata Import</p>
+              <p className="text-[10px] text-text-muted">
+                Import SPARQL query results saved as JSON (item, itemLabel, date, endDate)
+              </p>
+            </div>
+            <ArrowRight size={14} className="text-text-muted" />
+          </button>
+
+          <button
+            onClick={() => handleFileImport("markdown")}
+            className="w-full flex items-center gap-3 p-3 rounded-lg border border-border hover:bg-bg-tertiary transition-colors cursor-pointer"
+          >
+            <FileType size={20} className="text-accent shrink-0" />
+            <div className="text-left flex-1">
+              <p className="text-xs font-medium text-text">Markdown Import</p>
+              <p className="text-[10px] text-text-muted">
+                Import a Markdown export or notes with a date: line under each heading
+              </p>
+            </div>
+            <ArrowRight size={14} className="text-text-muted" />
+          </button>
+
+          <button
+            onClick={() => handleFileImport("gedcom")}
+            className="w-full flex items-center gap-3 p-3 rounded-lg border border-border hover:bg-bg-tertiary transition-colors cursor-pointer"
+          >
+            <Users size={20} className="text-accent shrink-0" />
+            <div className="text-left flex-1">
+              <p className="text-xs font-medium text-text">GEDCOM Import</p>
+              <p className="text-[10px] text-text-muted">
+                Import births, deaths, marriages and more from a family tree, one track per person
+              </p>
+            </div>
+            <ArrowRight size={14} className="text-text-muted" />
+          </button>
+
           <button
             onClick={handleCsvSelect}
             disabled={!activeTimelineId}
@@ -191,9 +415,42 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
             <ArrowRight size={14} className="text-text-muted" />
           </button>
 
+          <button
+            onClick={handleMergeSelect}
+            disabled={!activeTimelineId}
+            className="w-full flex items-center gap-3 p-3 rounded-lg border border-border hover:bg-bg-tertiary transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            <GitMerge size={20} className="text-accent shrink-0" />
+            <div className="text-left flex-1">
+              <p className="text-xs font-medium text-text">Merge JSON</p>
+              <p className="text-[10px] text-text-muted">
+                Merge a JSON export into the current timeline
+              </p>
+            </div>
+            <ArrowRight size={14} className="text-text-muted" />
+          </button>
+
+          <button
+            onClick={() => {
+              setIcsTrackId(tracks[0]?.id ?? "");
+              setMode("ics-track");
+            }}
+            disabled={!activeTimelineId || tracks.length === 0}
+            className="w-full flex items-center gap-3 p-3 rounded-lg border border-border hover:bg-bg-tertiary transition-colors cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            <CalendarDays size={20} className="text-accent shrink-0" />
+            <div className="text-left flex-1">
+              <p className="text-xs font-medium text-text">iCalendar Import</p>
+              <p className="text-[10px] text-text-muted">
+                Import events from an .ics file into a track, expanding recurring events
+              </p>
+            </div>
+            <ArrowRight size={14} className="text-text-muted" />
+          </button>
+
           {!activeTimelineId && (
             <p className="text-[10px] text-text-muted text-center">
-              Select a timeline first to use CSV import
+              Select a timeline first to use CSV, iCalendar import or merge
             </p>
           )}
         </div>
@@ -205,6 +462,29 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
             Map your CSV columns to event fields.
           </p>
 
+          <div className="flex items-center gap-2">
+            <label className="text-xs text-text-secondary w-24">Delimiter</label>
+            <input
+              type="text"
+              maxLength={1}
+              value={csvDelimiter}
+              placeholder={`Detected: ${csvDetectedDelimiter === "\t" ? "tab" : csvDetectedDelimiter}`}
+              onChange={(e) => void handleCsvDelimiterChange(e.target.value)}
+              className="flex-1 rounded-md border border-border bg-bg px-2 py-1 text-xs text-text focus:outline-none focus:ring-2 focus:ring-accent/50"
+            />
+          </div>
+
+          <div className="flex items-start gap-2">
+            <label className="text-xs text-text-secondary w-24 pt-1">Date formats</label>
+            <textarea
+              rows={2}
+              value={csvDateFormats}
+              placeholder={"One per line, e.g. %d/%m/%Y\nLeave empty for the built-in list"}
+              onChange={(e) => setCsvDateFormats(e.target.value)}
+              className="flex-1 rounded-md border border-border bg-bg px-2 py-1 text-xs text-text font-mono focus:outline-none focus:ring-2 focus:ring-accent/50"
+            />
+          </div>
+
           {(Object.keys(columnMapping) as Array<keyof ColumnMapping>).map(
             (field) => (
               <div key={field} className="flex items-center gap-2">
@@ -223,8 +503,9 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
                 >
                   <option value="">-- Skip --</option>
                   {csvColumns.map((col) => (
-                    <option key={col} value={col}>
-                      {col}
+                    <option key={col.name} value={col.name}>
+                      {col.name}
+                      {col.samples.length > 0 && ` (${col.kind}, e.g. ${col.samples[0]})`}
                     </option>
                   ))}
                 </select>
@@ -252,6 +533,122 @@ export function ImportDialog({ open, onClose }: ImportDialogProps) {
           </div>
         </div>
       )}
+
+      {mode === "csv-result" && csvReport && !loading && (
+        <div className="space-y-3">
+          <p className="text-xs text-text-secondary">
+            Imported {csvReport.eventsCreated} events
+            {csvReport.tracksCreated.length > 0 &&
+              ` and created tracks ${csvReport.tracksCreated.join(", ")}`}
+            .
+          </p>
+          <div className="max-h-64 overflow-y-auto space-y-1">
+            {csvReport.rows
+              .filter((r) => r.status !== "imported")
+              .map((r) => (
+                <p
+                  key={r.row}
+                  className={`text-[10px] ${r.status === "skipped" ? "text-danger" : "text-text-muted"}`}
+                >
+                  Row {r.row}
+                  {r.title && ` (${r.title})`}: {r.status === "skipped" ? "skipped" : "imported"}
+                  {r.reason && ` — ${r.reason}`}
+                </p>
+              ))}
+          </div>
+          <div className="flex justify-end mt-4">
+            <Button variant="primary" size="sm" onClick={handleClose}>
+              Done
+            </Button>
+          </div>
+        </div>
+      )}
+
+      {mode === "ics-track" && !loading && (
+        <div className="space-y-3">
+          <p className="text-xs text-text-secondary mb-2">
+            Choose the track that will receive the calendar events.
+          </p>
+          <select
+            value={icsTrackId}
+            onChange={(e) => setIcsTrackId(e.target.value)}
+            className="w-full rounded-md border border-border bg-bg px-2 py-1 text-xs text-text focus:outline-none focus:ring-2 focus:ring-accent/50"
+          >
+            {tracks.map((track) => (
+              <option key={track.id} value={track.id}>
+                {track.name}
+              </option>
+            ))}
+          </select>
+          <div className="flex justify-end gap-2 mt-4">
+            <Button variant="ghost" size="sm" onClick={() => setMode("select")}>
+              Back
+            </Button>
+            <Button variant="primary" size="sm" onClick={handleIcsImport} disabled={!icsTrackId}>
+              <Upload size={12} className="mr-1" />
+              Choose file
+            </Button>
+          </div>
+        </div>
+      )}
+
+      {mode === "merge-preview" && mergePreview && !loading && (
+        <div className="space-y-3">
+          <p className="text-xs text-text-secondary">
+            {mergePreview.eventsAdded.length} new events, {mergePreview.eventsUpdated.length}{" "}
+            updates, {mergePreview.eventsUnchanged} unchanged,{" "}
+            {mergePreview.tracksAdded.length} new tracks, {mergePreview.connectionsAdded} new
+            connections.
+            {mergePreview.skipped.length > 0 && ` ${mergePreview.skipped.length} skipped.`}
+          </p>
+
+          {mergePreview.conflicts.length > 0 && (
+            <div className="space-y-2 max-h-64 overflow-y-auto">
+              <p className="text-xs font-medium text-text">
+                {mergePreview.conflicts.length} conflicts
+              </p>
+              {mergePreview.conflicts.map((conflict) => (
+                <div key={conflict.eventId} className="p-2 rounded border border-border">
+                  <div className="flex items-center gap-2">
+                    <p className="text-xs text-text flex-1 truncate">{conflict.title}</p>
+                    <select
+                      value={resolutions[conflict.eventId] ?? "keepMine"}
+                      onChange={(e) =>
+                        setResolutions((prev) => ({
+                          ...prev,
+                          [conflict.eventId]: e.target.value as cmd.ConflictStrategy,
+                        }))
+                      }
+                      className="rounded-md border border-border bg-bg px-2 py-1 text-xs text-text focus:outline-none focus:ring-2 focus:ring-accent/50"
+                    >
+                      {(Object.keys(STRATEGY_LABELS) as cmd.ConflictStrategy[]).map((s) => (
+                        <option key={s} value={s}>
+                          {STRATEGY_LABELS[s]}
+                        </option>
+                      ))}
+                    </select>
+                  </div>
+                  {conflict.diffs.map((diff) => (
+                    <p key={diff.field} className="text-[10px] text-text-muted truncate">
+                      {diff.field}: {String(diff.before ?? "")} → {String(diff.after ?? "")}
+                    </p>
+                  ))}
+                </div>
+              ))}
+            </div>
+          )}
+
+          <div className="flex justify-end gap-2 mt-4">
+            <Button variant="ghost" size="sm" onClick={() => setMode("select")}>
+              Back
+            </Button>
+            <Button variant="primary" size="sm" onClick={handleMergeApply}>
+              <GitMerge size={12} className="mr-1" />
+              Merge
+            </Button>
+          </div>
+        </div>
+      )}
     </Modal>
   );
 }
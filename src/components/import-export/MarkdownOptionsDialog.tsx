@@ -0,0 +1,118 @@
+import { useState } from "react";
+import { Download } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { Modal } from "../common/Modal";
+import { Button } from "../common/Button";
+import { useTimelineStore } from "../../stores/timeline-store";
+import { useToastStore } from "../../stores/toast-store";
+import * as cmd from "../../lib/commands";
+
+const GROUPING_LABELS: Record<cmd.MarkdownGrouping, string> = {
+  year: "Year",
+  decade: "Decade",
+  century: "Century",
+  track: "Track",
+};
+
+interface MarkdownOptionsDialogProps {
+  open: boolean;
+  onClose: () => void;
+}
+
+export function MarkdownOptionsDialog({ open, onClose }: MarkdownOptionsDialogProps) {
+  const [exporting, setExporting] = useState(false);
+  const [grouping, setGrouping] = useState<cmd.MarkdownGrouping>("year");
+  const [frontMatter, setFrontMatter] = useState(true);
+  const [includeConnections, setIncludeConnections] = useState(true);
+  const activeTimelineId = useTimelineStore((s) => s.activeTimelineId);
+  const addToast = useToastStore((s) => s.addToast);
+
+  const handleExport = async () => {
+    if (!activeTimelineId) return;
+
+    setExporting(true);
+    try {
+      const content = await cmd.exportMarkdown(activeTimelineId, {
+        grouping,
+        frontMatter,
+        includeConnections,
+      });
+      const filePath = await invoke<string | null>("show_save_dialog", {
+        defaultPath: "timeline.md",
+        filterName: "Markdown",
+        filterExtensions: ["md"],
+      });
+
+      if (filePath) {
+        await cmd.saveFile(filePath, content);
+        addToast({
+          type: "success",
+          title: "Export complete",
+          description: "Saved as Markdown.",
+        });
+        onClose();
+      }
+    } catch (e) {
+      addToast({
+        type: "error",
+        title: "Markdown export failed",
+        description: String(e),
+      });
+    } finally {
+      setExporting(false);
+    }
+  };
+
+  return (
+    <Modal open={open} onClose={onClose} title="Export as Markdown">
+      <div className="space-y-4">
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Group by</label>
+          <select
+            value={grouping}
+            onChange={(e) => setGrouping(e.target.value as cmd.MarkdownGrouping)}
+            className="flex-1 rounded-md border border-border bg-bg px-2 py-1 text-xs text-text focus:outline-none focus:ring-2 focus:ring-accent/50"
+          >
+            {(Object.keys(GROUPING_LABELS) as cmd.MarkdownGrouping[]).map((g) => (
+              <option key={g} value={g}>
+                {GROUPING_LABELS[g]}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        <label className="flex items-center gap-2 text-xs text-text">
+          <input
+            type="checkbox"
+            checked={frontMatter}
+            onChange={(e) => setFrontMatter(e.target.checked)}
+          />
+          YAML front matter
+        </label>
+        <label className="flex items-center gap-2 text-xs text-text">
+          <input
+            type="checkbox"
+            checked={includeConnections}
+            onChange={(e) => setIncludeConnections(e.target.checked)}
+          />
+          Connections section
+        </label>
+
+        <div className="flex justify-end gap-2">
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            Cancel
+          </Button>
+          <Button
+            variant="primary"
+            size="sm"
+            onClick={handleExport}
+            disabled={exporting || !activeTimelineId}
+          >
+            <Download size={12} className="mr-1" />
+            {exporting ? "Exporting..." : "Export Markdown"}
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
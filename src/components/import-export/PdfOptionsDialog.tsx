@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { Download, FileText } from "lucide-react";
 import { Modal } from "../common/Modal";
 import { Button } from "../common/Button";
@@ -13,15 +13,40 @@ interface PdfOptionsDialogProps {
 
 export function PdfOptionsDialog({ open, onClose }: PdfOptionsDialogProps) {
   const [exporting, setExporting] = useState(false);
+  const [appendix, setAppendix] = useState(true);
+  const [outline, setOutline] = useState<cmd.PdfOutlineGrouping>("year");
+  const [pageSize, setPageSize] = useState<cmd.PdfPageSize>("letter");
+  const [orientation, setOrientation] = useState<cmd.PdfOrientation>("landscape");
+  const [margin, setMargin] = useState(20);
+  const [fitToPage, setFitToPage] = useState(false);
+  const [fontPath, setFontPath] = useState("");
   const activeTimelineId = useTimelineStore((s) => s.activeTimelineId);
   const addToast = useToastStore((s) => s.addToast);
 
+  useEffect(() => {
+    if (!open) return;
+    cmd
+      .getSetting("pdf_font_path")
+      .then((setting) => setFontPath(setting.value))
+      .catch(() => {
+        // Setting may not exist yet, use the bundled font
+      });
+  }, [open]);
+
   const handleExport = async () => {
     if (!activeTimelineId) return;
 
     setExporting(true);
     try {
-      const bytes = await cmd.exportPdf(activeTimelineId);
+      await cmd.updateSetting("pdf_font_path", fontPath.trim());
+      const bytes = await cmd.exportPdf(activeTimelineId, {
+        appendix,
+        outline,
+        pageSize,
+        orientation,
+        margin,
+        fitToPage,
+      });
       const uint8Array = new Uint8Array(bytes);
       const blob = new Blob([uint8Array], { type: "application/pdf" });
       const url = URL.createObjectURL(blob);
@@ -51,6 +76,9 @@ export function PdfOptionsDialog({ open, onClose }: PdfOptionsDialogProps) {
     }
   };
 
+  const inputClass =
+    "flex-1 rounded-md border border-border bg-bg px-2 py-1 text-xs text-text focus:outline-none focus:ring-2 focus:ring-accent/50";
+
   return (
     <Modal open={open} onClose={onClose} title="Export as PDF">
       <div className="space-y-4">
@@ -67,6 +95,80 @@ export function PdfOptionsDialog({ open, onClose }: PdfOptionsDialogProps) {
           </div>
         </div>
 
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Page</label>
+          <select
+            value={pageSize}
+            onChange={(e) => setPageSize(e.target.value as cmd.PdfPageSize)}
+            className={inputClass}
+          >
+            <option value="a4">A4</option>
+            <option value="a3">A3</option>
+            <option value="letter">Letter</option>
+          </select>
+          <select
+            value={orientation}
+            onChange={(e) => setOrientation(e.target.value as cmd.PdfOrientation)}
+            className={inputClass}
+          >
+            <option value="landscape">Landscape</option>
+            <option value="portrait">Portrait</option>
+          </select>
+        </div>
+
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Margin</label>
+          <input
+            type="number"
+            min={0}
+            max={50}
+            value={margin}
+            onChange={(e) => setMargin(Number(e.target.value))}
+            className={inputClass}
+          />
+          <span className="text-xs text-text-muted">mm</span>
+        </div>
+
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Font file</label>
+          <input
+            type="text"
+            value={fontPath}
+            placeholder="Bundled (DejaVu Sans)"
+            onChange={(e) => setFontPath(e.target.value)}
+            className={inputClass}
+          />
+        </div>
+
+        <label className="flex items-center gap-2 text-xs text-text">
+          <input
+            type="checkbox"
+            checked={fitToPage}
+            onChange={(e) => setFitToPage(e.target.checked)}
+          />
+          Fit the entire timeline on one page
+        </label>
+        <label className="flex items-center gap-2 text-xs text-text">
+          <input
+            type="checkbox"
+            checked={appendix}
+            onChange={(e) => setAppendix(e.target.checked)}
+          />
+          Append event detail cards
+        </label>
+
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Bookmarks by</label>
+          <select
+            value={outline}
+            onChange={(e) => setOutline(e.target.value as cmd.PdfOutlineGrouping)}
+            className={inputClass}
+          >
+            <option value="year">Year</option>
+            <option value="track">Track</option>
+          </select>
+        </div>
+
         <div className="flex justify-end gap-2">
           <Button variant="ghost" size="sm" onClick={onClose}>
             Cancel
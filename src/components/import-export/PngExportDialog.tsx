@@ -2,9 +2,15 @@ import { useState } from "react";
 import { Download, Image } from "lucide-react";
 import { Modal } from "../common/Modal";
 import { Button } from "../common/Button";
+import { useTimelineStore } from "../../stores/timeline-store";
 import { useToastStore } from "../../stores/toast-store";
+import * as cmd from "../../lib/commands";
 
-type Resolution = 1 | 2 | 3;
+type Resolution = 96 | 192 | 300;
+
+function currentTheme(): cmd.SvgTheme {
+  return document.documentElement.getAttribute("data-theme") === "dark" ? "dark" : "light";
+}
 
 interface PngExportDialogProps {
   open: boolean;
@@ -12,61 +18,43 @@ interface PngExportDialogProps {
 }
 
 export function PngExportDialog({ open, onClose }: PngExportDialogProps) {
-  const [resolution, setResolution] = useState<Resolution>(2);
+  const [resolution, setResolution] = useState<Resolution>(192);
+  const [fixedWidth, setFixedWidth] = useState(false);
+  const [pixelWidth, setPixelWidth] = useState(1920);
+  const [transparent, setTransparent] = useState(false);
   const [exporting, setExporting] = useState(false);
+  const activeTimelineId = useTimelineStore((s) => s.activeTimelineId);
   const addToast = useToastStore((s) => s.addToast);
 
-  const handleExport = () => {
-    setExporting(true);
+  const handleExport = async () => {
+    if (!activeTimelineId) return;
 
+    setExporting(true);
     try {
-      const sourceCanvas = document.querySelector("canvas");
-      if (!sourceCanvas) {
-        addToast({
-          type: "error",
-          title: "No canvas found",
-          description: "There is no timeline canvas to export.",
-        });
-        return;
-      }
-
-      const scale = resolution;
-      const scaledCanvas = document.createElement("canvas");
-      scaledCanvas.width = sourceCanvas.width * scale;
-      scaledCanvas.height = sourceCanvas.height * scale;
-
-      const ctx = scaledCanvas.getContext("2d");
-      if (!ctx) {
-        addToast({
-          type: "error",
-          title: "Export failed",
-          description: "Could not create canvas context.",
-        });
-        return;
-      }
-
-      ctx.imageSmoothingEnabled = true;
-      ctx.imageSmoothingQuality = "high";
-      ctx.drawImage(
-        sourceCanvas,
-        0,
-        0,
-        scaledCanvas.width,
-        scaledCanvas.height
-      );
+      const bytes = await cmd.exportPng(activeTimelineId, {
+        theme: currentTheme(),
+        visibleTracksOnly: true,
+        transparentBackground: transparent,
+        dpi: fixedWidth ? null : resolution,
+        pixelWidth: fixedWidth ? pixelWidth : null,
+      });
+      const blob = new Blob([new Uint8Array(bytes)], { type: "image/png" });
+      const url = URL.createObjectURL(blob);
 
-      const dataUrl = scaledCanvas.toDataURL("image/png");
       const link = document.createElement("a");
       link.download = `timeline-${Date.now()}.png`;
-      link.href = dataUrl;
+      link.href = url;
       document.body.appendChild(link);
       link.click();
       document.body.removeChild(link);
+      URL.revokeObjectURL(url);
 
       addToast({
         type: "success",
         title: "PNG exported",
-        description: `Exported at ${scale}x resolution.`,
+        description: fixedWidth
+          ? `Exported ${pixelWidth} px wide.`
+          : `Exported at ${resolution} DPI.`,
       });
       onClose();
     } catch (e) {
@@ -90,11 +78,11 @@ export function PngExportDialog({ open, onClose }: PngExportDialogProps) {
           </p>
 
           <div className="flex gap-3">
-            {([1, 2, 3] as const).map((scale) => (
+            {([96, 192, 300] as const).map((dpi) => (
               <label
-                key={scale}
+                key={dpi}
                 className={`flex-1 flex flex-col items-center gap-1 p-3 rounded-lg border cursor-pointer transition-colors ${
-                  resolution === scale
+                  !fixedWidth && resolution === dpi
                     ? "border-accent bg-accent/10"
                     : "border-border hover:bg-bg-tertiary"
                 }`}
@@ -102,28 +90,61 @@ export function PngExportDialog({ open, onClose }: PngExportDialogProps) {
                 <input
                   type="radio"
                   name="resolution"
-                  value={scale}
-                  checked={resolution === scale}
-                  onChange={() => setResolution(scale)}
+                  value={dpi}
+                  checked={!fixedWidth && resolution === dpi}
+                  onChange={() => {
+                    setResolution(dpi);
+                    setFixedWidth(false);
+                  }}
                   className="sr-only"
                 />
                 <Image
                   size={20}
                   className={
-                    resolution === scale ? "text-accent" : "text-text-muted"
+                    !fixedWidth && resolution === dpi ? "text-accent" : "text-text-muted"
                   }
                 />
-                <span className="text-xs font-medium">{scale}x</span>
+                <span className="text-xs font-medium">{dpi} DPI</span>
                 <span className="text-[10px] text-text-muted">
-                  {scale === 1 && "Standard"}
-                  {scale === 2 && "Retina"}
-                  {scale === 3 && "Ultra"}
+                  {dpi === 96 && "Screen"}
+                  {dpi === 192 && "Slides"}
+                  {dpi === 300 && "Print"}
                 </span>
               </label>
             ))}
           </div>
         </div>
 
+        <div className="flex items-center gap-2">
+          <label className="flex items-center gap-1 text-xs text-text w-24">
+            <input
+              type="checkbox"
+              checked={fixedWidth}
+              onChange={(e) => setFixedWidth(e.target.checked)}
+            />
+            Fixed width
+          </label>
+          <input
+            type="number"
+            min={100}
+            step={100}
+            value={pixelWidth}
+            disabled={!fixedWidth}
+            onChange={(e) => setPixelWidth(Number(e.target.value))}
+            className="flex-1 rounded-md border border-border bg-bg px-2 py-1 text-xs text-text focus:outline-none focus:ring-2 focus:ring-accent/50"
+          />
+          <span className="text-xs text-text-muted">px</span>
+        </div>
+
+        <label className="flex items-center gap-2 text-xs text-text">
+          <input
+            type="checkbox"
+            checked={transparent}
+            onChange={(e) => setTransparent(e.target.checked)}
+          />
+          Transparent background
+        </label>
+
         <div className="flex justify-end gap-2">
           <Button variant="ghost" size="sm" onClick={onClose}>
             Cancel
@@ -132,7 +153,7 @@ export function PngExportDialog({ open, onClose }: PngExportDialogProps) {
             variant="primary"
             size="sm"
             onClick={handleExport}
-            disabled={exporting}
+            disabled={exporting || !activeTimelineId}
           >
             <Download size={12} className="mr-1" />
             {exporting ? "Exporting..." : "Export PNG"}
@@ -0,0 +1,206 @@
+import { useState } from "react";
+import { Download } from "lucide-react";
+import { invoke } from "@tauri-apps/api/core";
+import { Modal } from "../common/Modal";
+import { Button } from "../common/Button";
+import { useTimelineStore } from "../../stores/timeline-store";
+import { useCanvasStore } from "../../stores/canvas-store";
+import { useToastStore } from "../../stores/toast-store";
+import { getVisibleDateRange } from "../../lib/canvas-math";
+import { IMPORTANCE_LEVELS } from "../../lib/constants";
+import * as cmd from "../../lib/commands";
+
+/** Format a canvas date the way the backend stores dates. */
+function toStoredDate(date: Date): string {
+  const year = date.getUTCFullYear();
+  if (year <= 0) return `${1 - year} BCE`;
+  if (year > 9999) return String(year);
+  return date.toISOString().slice(0, 10);
+}
+
+function currentTheme(): cmd.SvgTheme {
+  return document.documentElement.getAttribute("data-theme") === "dark" ? "dark" : "light";
+}
+
+interface SvgOptionsDialogProps {
+  open: boolean;
+  onClose: () => void;
+}
+
+export function SvgOptionsDialog({ open, onClose }: SvgOptionsDialogProps) {
+  const [exporting, setExporting] = useState(false);
+  const [autoWidth, setAutoWidth] = useState(true);
+  const [width, setWidth] = useState(1920);
+  const [theme, setTheme] = useState<cmd.SvgTheme>(currentTheme);
+  const [fontFamily, setFontFamily] = useState("sans-serif");
+  const [visibleTracksOnly, setVisibleTracksOnly] = useState(true);
+  const [visibleRangeOnly, setVisibleRangeOnly] = useState(false);
+  const [minImportance, setMinImportance] = useState(1);
+  const [tags, setTags] = useState("");
+  const activeTimelineId = useTimelineStore((s) => s.activeTimelineId);
+  const addToast = useToastStore((s) => s.addToast);
+
+  const handleExport = async () => {
+    if (!activeTimelineId) return;
+
+    setExporting(true);
+    try {
+      let start: string | null = null;
+      let end: string | null = null;
+      if (visibleRangeOnly) {
+        const { viewportWidth, zoomLevel, panOffset } = useCanvasStore.getState();
+        const range = getVisibleDateRange(viewportWidth, zoomLevel, panOffset.x);
+        start = toStoredDate(range.start);
+        end = toStoredDate(range.end);
+      }
+
+      const content = await cmd.exportSvg(activeTimelineId, {
+        width: autoWidth ? null : width,
+        start,
+        end,
+        theme,
+        fontFamily,
+        visibleTracksOnly,
+        minImportance,
+        tags: tags
+          .split(",")
+          .map((t) => t.trim())
+          .filter(Boolean),
+      });
+      const filePath = await invoke<string | null>("show_save_dialog", {
+        defaultPath: "timeline.svg",
+        filterName: "SVG",
+        filterExtensions: ["svg"],
+      });
+
+      if (filePath) {
+        await cmd.saveFile(filePath, content);
+        addToast({
+          type: "success",
+          title: "SVG exported",
+          description: "Timeline saved as SVG.",
+        });
+        onClose();
+      }
+    } catch (e) {
+      addToast({
+        type: "error",
+        title: "SVG export failed",
+        description: String(e),
+      });
+    } finally {
+      setExporting(false);
+    }
+  };
+
+  const inputClass =
+    "flex-1 rounded-md border border-border bg-bg px-2 py-1 text-xs text-text focus:outline-none focus:ring-2 focus:ring-accent/50";
+
+  return (
+    <Modal open={open} onClose={onClose} title="Export as SVG">
+      <div className="space-y-4">
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Width</label>
+          <label className="flex items-center gap-1 text-xs text-text">
+            <input
+              type="checkbox"
+              checked={autoWidth}
+              onChange={(e) => setAutoWidth(e.target.checked)}
+            />
+            Auto
+          </label>
+          <input
+            type="number"
+            min={200}
+            step={100}
+            value={width}
+            disabled={autoWidth}
+            onChange={(e) => setWidth(Number(e.target.value))}
+            className={inputClass}
+          />
+          <span className="text-xs text-text-muted">px</span>
+        </div>
+
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Theme</label>
+          <select
+            value={theme}
+            onChange={(e) => setTheme(e.target.value as cmd.SvgTheme)}
+            className={inputClass}
+          >
+            <option value="light">Light</option>
+            <option value="dark">Dark</option>
+          </select>
+        </div>
+
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Font</label>
+          <input
+            type="text"
+            value={fontFamily}
+            onChange={(e) => setFontFamily(e.target.value)}
+            className={inputClass}
+          />
+        </div>
+
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Min. importance</label>
+          <select
+            value={minImportance}
+            onChange={(e) => setMinImportance(Number(e.target.value))}
+            className={inputClass}
+          >
+            {IMPORTANCE_LEVELS.map((level) => (
+              <option key={level} value={level}>
+                {level}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        <div className="flex items-center gap-2">
+          <label className="text-xs text-text-secondary w-24">Tags</label>
+          <input
+            type="text"
+            value={tags}
+            placeholder="Any tag (comma separated)"
+            onChange={(e) => setTags(e.target.value)}
+            className={inputClass}
+          />
+        </div>
+
+        <label className="flex items-center gap-2 text-xs text-text">
+          <input
+            type="checkbox"
+            checked={visibleTracksOnly}
+            onChange={(e) => setVisibleTracksOnly(e.target.checked)}
+          />
+          Only visible tracks
+        </label>
+        <label className="flex items-center gap-2 text-xs text-text">
+          <input
+            type="checkbox"
+            checked={visibleRangeOnly}
+            onChange={(e) => setVisibleRangeOnly(e.target.checked)}
+          />
+          Only the time range on screen
+        </label>
+
+        <div className="flex justify-end gap-2">
+          <Button variant="ghost" size="sm" onClick={onClose}>
+            Cancel
+          </Button>
+          <Button
+            variant="primary"
+            size="sm"
+            onClick={handleExport}
+            disabled={exporting || !activeTimelineId}
+          >
+            <Download size={12} className="mr-1" />
+            {exporting ? "Exporting..." : "Export SVG"}
+          </Button>
+        </div>
+      </div>
+    </Modal>
+  );
+}
@@ -5,6 +5,7 @@ import { useTimelineStore } from "../../stores/timeline-store";
 import { ResizeHandle } from "./ResizeHandle";
 import { TrackManager } from "../tracks/TrackManager";
 import { FilterPanel } from "../search/FilterPanel";
+import { UndatedEvents } from "../events/UndatedEvents";
 import { IconButton } from "../common/IconButton";
 import { EmptyState } from "../common/EmptyState";
 
@@ -34,6 +35,7 @@ export function Sidebar() {
               {activeTimelineId ? (
                 <>
                   <TrackManager />
+                  <UndatedEvents />
                   <div className="border-t border-border">
                     <button
                       onClick={() => setShowFilters(!showFilters)}
@@ -38,29 +38,23 @@ export function TitleBar({ onToggleAi }: TitleBarProps) {
   const { createTimeline } = useTimelineStore();
   const { theme, setTheme } = useThemeStore();
   const openModal = useUiStore((s) => s.openModal);
-  const undoStack = useHistoryStore((s) => s.undoStack);
-  const redoStack = useHistoryStore((s) => s.redoStack);
+  const undoLabel = useHistoryStore((s) => s.undoLabel);
+  const redoLabel = useHistoryStore((s) => s.redoLabel);
 
   const handleUndo = async () => {
     const history = useHistoryStore.getState();
     if (!history.canUndo()) return;
-    await history.undo();
-    if (activeTimelineId) {
-      await useEventStore.getState().loadEvents(activeTimelineId);
-    }
+    const label = await history.undo();
     useCanvasStore.getState().markDirty();
-    useToastStore.getState().addToast({ type: "info", title: "Undone" });
+    useToastStore.getState().addToast({ type: "info", title: label ? `Undone: ${label}` : "Undone" });
   };
 
   const handleRedo = async () => {
     const history = useHistoryStore.getState();
     if (!history.canRedo()) return;
-    await history.redo();
-    if (activeTimelineId) {
-      await useEventStore.getState().loadEvents(activeTimelineId);
-    }
+    const label = await history.redo();
     useCanvasStore.getState().markDirty();
-    useToastStore.getState().addToast({ type: "info", title: "Redone" });
+    useToastStore.getState().addToast({ type: "info", title: label ? `Redone: ${label}` : "Redone" });
   };
   const [showNewDialog, setShowNewDialog] = useState(false);
   const [showImport, setShowImport] = useState(false);
@@ -142,16 +136,16 @@ export function TitleBar({ onToggleAi }: TitleBarProps) {
 
         <div className="w-px h-5 bg-border" />
         <IconButton
-          tooltip="Undo (⌘Z)"
+          tooltip={undoLabel ? `Undo ${undoLabel} (⌘Z)` : "Undo (⌘Z)"}
           onClick={handleUndo}
-          disabled={undoStack.length === 0}
+          disabled={undoLabel === null}
         >
           <Undo2 size={16} />
         </IconButton>
         <IconButton
-          tooltip="Redo (⌘⇧Z)"
+          tooltip={redoLabel ? `Redo ${redoLabel} (⌘⇧Z)` : "Redo (⌘⇧Z)"}
           onClick={handleRedo}
-          disabled={redoStack.length === 0}
+          disabled={redoLabel === null}
         >
           <Redo2 size={16} />
         </IconButton>
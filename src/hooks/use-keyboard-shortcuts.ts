@@ -4,7 +4,6 @@ import { useCanvasStore } from "../stores/canvas-store";
 import { useUiStore } from "../stores/ui-store";
 import { useHistoryStore } from "../stores/history-store";
 import { useToastStore } from "../stores/toast-store";
-import { useTimelineStore } from "../stores/timeline-store";
 import { fitAllEvents } from "../lib/canvas-math";
 
 interface ShortcutOptions {
@@ -27,13 +26,9 @@ export function useKeyboardShortcuts(options: ShortcutOptions = {}) {
         e.preventDefault();
         const history = useHistoryStore.getState();
         if (history.canUndo()) {
-          history.undo().then(() => {
-            const { activeTimelineId } = useTimelineStore.getState();
-            if (activeTimelineId) {
-              useEventStore.getState().loadEvents(activeTimelineId);
-            }
+          history.undo().then((label) => {
             useCanvasStore.getState().markDirty();
-            useToastStore.getState().addToast({ type: "info", title: "Undone" });
+            useToastStore.getState().addToast({ type: "info", title: label ? `Undone: ${label}` : "Undone" });
           });
         }
         return;
@@ -44,13 +39,9 @@ export function useKeyboardShortcuts(options: ShortcutOptions = {}) {
         e.preventDefault();
         const history = useHistoryStore.getState();
         if (history.canRedo()) {
-          history.redo().then(() => {
-            const { activeTimelineId } = useTimelineStore.getState();
-            if (activeTimelineId) {
-              useEventStore.getState().loadEvents(activeTimelineId);
-            }
+          history.redo().then((label) => {
             useCanvasStore.getState().markDirty();
-            useToastStore.getState().addToast({ type: "info", title: "Redone" });
+            useToastStore.getState().addToast({ type: "info", title: label ? `Redone: ${label}` : "Redone" });
           });
         }
         return;
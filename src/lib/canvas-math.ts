@@ -33,22 +33,56 @@ export function pixelToDate(
 export function parseDateToTimestamp(dateStr: string): number {
   if (!dateStr) return Date.now();
 
+  // Canonical backend forms: optional "c. " prefix and " BCE" suffix
+  let text = dateStr.trim().replace(/^c\. /, "");
+  const bce = text.endsWith(" BCE");
+  if (bce) text = text.slice(0, -4);
+  const toYear = (n: number) => (bce ? 1 - n : n);
+
+  // Century: "19th century" → middle of the century
+  const century = /^(\d+)(?:st|nd|rd|th) century$/.exec(text);
+  if (century) {
+    const n = Number(century[1]);
+    const first = bce ? toYear(n * 100) : (n - 1) * 100 + 1;
+    return utcTimestamp(first + 50, 0, 1);
+  }
+
+  // Decade: "1850s" → middle of the decade
+  const decade = /^(\d+)0s$/.exec(text);
+  if (decade) {
+    const first = bce ? toYear(Number(decade[1]) * 10 + 9) : Number(decade[1]) * 10;
+    return utcTimestamp(first + 5, 0, 1);
+  }
+
   // Year only: "2024" → Jul 1 of that year
-  if (/^\d{1,4}$/.test(dateStr)) {
-    return new Date(Number(dateStr), 6, 1).getTime();
+  if (/^\d{1,4}$/.test(text)) {
+    return utcTimestamp(toYear(Number(text)), 6, 1);
   }
 
   // Year-month: "2024-06" → 15th of that month
-  if (/^\d{4}-\d{2}$/.test(dateStr)) {
-    const [y, m] = dateStr.split("-").map(Number);
-    return new Date(y, m - 1, 15).getTime();
+  if (/^\d{4}-\d{2}$/.test(text)) {
+    const [y, m] = text.split("-").map(Number);
+    return utcTimestamp(toYear(y), m - 1, 15);
   }
 
   // Full date or datetime
-  const d = new Date(dateStr);
+  const full = /^(\d{4})-(\d{2})-(\d{2})(?:T(\d{2}):(\d{2})(?::(\d{2}))?)?$/.exec(text);
+  if (full) {
+    const [, y, m, d, hh = "0", mm = "0", ss = "0"] = full;
+    return utcTimestamp(toYear(Number(y)), Number(m) - 1, Number(d), Number(hh), Number(mm), Number(ss));
+  }
+
+  const d = new Date(text);
   return isNaN(d.getTime()) ? Date.now() : d.getTime();
 }
 
+/** Date.UTC that does not remap years 0–99 onto 1900–1999. */
+function utcTimestamp(year: number, month: number, day: number, h = 0, m = 0, s = 0): number {
+  const d = new Date(Date.UTC(2000, month, day, h, m, s));
+  d.setUTCFullYear(year);
+  return d.getTime();
+}
+
 export function getVisibleDateRange(
   viewportWidth: number,
   zoom: number,
@@ -3,6 +3,7 @@ import type {
   Timeline,
   Track,
   TimelineEvent,
+  UndatedEvent,
   Setting,
   Connection,
   Template,
@@ -13,6 +14,8 @@ import type {
   CreateEventInput,
   UpdateEventInput,
   BulkUpdateInput,
+  EventRevision,
+  FieldDiff,
 } from "./types";
 
 // Timelines
@@ -45,6 +48,8 @@ export const getEvent = (id: string) =>
   invoke<TimelineEvent>("get_event", { id });
 export const listEvents = (timelineId: string) =>
   invoke<TimelineEvent[]>("list_events", { timelineId });
+export const listUndatedEvents = (timelineId: string) =>
+  invoke<UndatedEvent[]>("list_undated_events", { timelineId });
 export const updateEvent = (input: UpdateEventInput) =>
   invoke<TimelineEvent>("update_event", { input });
 export const deleteEvent = (id: string) =>
@@ -54,6 +59,14 @@ export const bulkDeleteEvents = (ids: string[]) =>
 export const bulkUpdateEvents = (input: BulkUpdateInput) =>
   invoke<number>("bulk_update_events", { input });
 
+// Revisions
+export const listEventRevisions = (eventId: string) =>
+  invoke<EventRevision[]>("list_event_revisions", { eventId });
+export const diffEventRevisions = (fromId: string, toId?: string) =>
+  invoke<FieldDiff[]>("diff_event_revisions", { fromId, toId });
+export const restoreEventRevision = (revisionId: string) =>
+  invoke<TimelineEvent>("restore_event_revision", { revisionId });
+
 // Connections
 export interface CreateConnectionInput {
   timelineId: string;
@@ -78,6 +91,18 @@ export const updateConnection = (input: UpdateConnectionInput) =>
 export const deleteConnection = (id: string) =>
   invoke<void>("delete_connection", { id });
 
+// History
+export interface HistoryStatus {
+  undoLabel: string | null;
+  redoLabel: string | null;
+}
+export const undo = (timelineId: string) =>
+  invoke<string | null>("undo", { timelineId });
+export const redo = (timelineId: string) =>
+  invoke<string | null>("redo", { timelineId });
+export const getHistoryStatus = (timelineId: string) =>
+  invoke<HistoryStatus>("get_history_status", { timelineId });
+
 // Search
 export interface SearchResult {
   eventId: string;
@@ -90,25 +115,189 @@ export const searchEvents = (timelineId: string, query: string) =>
   invoke<SearchResult[]>("search_events", { timelineId, query });
 
 // Import
-export const importJson = (data: string) =>
-  invoke<string>("import_json", { data });
+export interface SkippedItem {
+  kind: string;
+  index: number;
+  title: string;
+  reason: string;
+}
+export interface UnresolvedConnection {
+  index: number;
+  sourceTitle: string | null;
+  targetTitle: string | null;
+  missing: string[];
+}
+export interface ImportReport {
+  timelineId: string | null;
+  dryRun: boolean;
+  tracksCreated: number;
+  eventsCreated: number;
+  connectionsCreated: number;
+  skipped: SkippedItem[];
+  unresolvedConnections: UnresolvedConnection[];
+  duplicateTitles: string[];
+  idsReassigned: number;
+}
+export const importJson = (data: string, dryRun?: boolean) =>
+  invoke<ImportReport>("import_json", { data, dryRun });
+export interface SparqlMapping {
+  item?: string;
+  title?: string;
+  startDate?: string;
+  endDate?: string | null;
+  description?: string | null;
+  trackName?: string | null;
+  datePrecision?: string | null;
+  endDatePrecision?: string | null;
+}
+export const importWikidataJson = (
+  title: string,
+  data: string,
+  mapping?: SparqlMapping,
+  dryRun?: boolean
+) => invoke<ImportReport>("import_wikidata_json", { title, data, mapping, dryRun });
+export const importTimelineJs = (data: string, dryRun?: boolean) =>
+  invoke<ImportReport>("import_timelinejs", { data, dryRun });
+export const importMarkdown = (title: string, data: string, dryRun?: boolean) =>
+  invoke<ImportReport>("import_markdown", { title, data, dryRun });
+export type GedcomGrouping = "person" | "category";
+export const importGedcom = (
+  title: string,
+  data: string,
+  grouping?: GedcomGrouping,
+  dryRun?: boolean
+) => invoke<ImportReport>("import_gedcom", { title, data, grouping, dryRun });
+export type ConflictStrategy = "keepMine" | "takeTheirs" | "keepBoth";
+export interface MergeMatch {
+  index: number;
+  eventId: string;
+  title: string;
+  matchedBy: "id" | "titleAndDate";
+  diffs: FieldDiff[];
+  resolution: ConflictStrategy | null;
+}
+export interface MergeReport {
+  dryRun: boolean;
+  tracksAdded: string[];
+  eventsAdded: string[];
+  eventsUpdated: MergeMatch[];
+  conflicts: MergeMatch[];
+  eventsUnchanged: number;
+  connectionsAdded: number;
+  skipped: SkippedItem[];
+  unresolvedConnections: UnresolvedConnection[];
+}
+export const mergeImportJson = (
+  timelineId: string,
+  data: string,
+  options: {
+    strategy?: ConflictStrategy;
+    resolutions?: Record<string, ConflictStrategy>;
+    dryRun?: boolean;
+  } = {}
+) => invoke<MergeReport>("merge_import_json", { timelineId, data, ...options });
+export interface CsvRowResult {
+  row: number;
+  status: "imported" | "warning" | "skipped";
+  title: string;
+  reason: string | null;
+}
+export interface CsvImportReport {
+  eventsCreated: number;
+  tracksCreated: string[];
+  rows: CsvRowResult[];
+}
+export interface CsvImportOptions {
+  /** Sniffed from the file when omitted. */
+  delimiter?: string;
+  /** chrono format strings, e.g. "%d/%m/%Y"; replaces the built-in list. */
+  dateFormats?: string[];
+}
+export interface CsvColumn {
+  name: string;
+  kind: "date" | "number" | "text" | "empty";
+  samples: string[];
+}
+export interface CsvPreview {
+  delimiter: string;
+  columns: CsvColumn[];
+  rows: string[][];
+  suggestedMapping: Record<string, string>;
+}
+export const previewCsv = (csvData: string, options?: CsvImportOptions, maxRows?: number) =>
+  invoke<CsvPreview>("preview_csv", { csvData, options, maxRows });
 export const importCsv = (
   timelineId: string,
   csvData: string,
-  columnMapping: Record<string, string>
-) => invoke<number>("import_csv", { timelineId, csvData, columnMapping });
+  columnMapping: Record<string, string>,
+  options?: CsvImportOptions
+) => invoke<CsvImportReport>("import_csv", { timelineId, csvData, columnMapping, options });
+export interface IcsImportReport {
+  eventsCreated: number;
+  skipped: SkippedItem[];
+  warnings: string[];
+}
+export const importIcs = (timelineId: string, trackId: string, data: string) =>
+  invoke<IcsImportReport>("import_ics", { timelineId, trackId, data });
 
 // Export
 export const exportJson = (timelineId: string) =>
   invoke<string>("export_json", { timelineId });
+export const exportTimelineJs = (timelineId: string) =>
+  invoke<string>("export_timelinejs", { timelineId });
 export const exportCsv = (timelineId: string) =>
   invoke<string>("export_csv", { timelineId });
-export const exportMarkdown = (timelineId: string) =>
-  invoke<string>("export_markdown", { timelineId });
-export const exportSvg = (timelineId: string) =>
-  invoke<string>("export_svg", { timelineId });
-export const exportPdf = (timelineId: string) =>
-  invoke<number[]>("export_pdf", { timelineId });
+export const exportIcs = (timelineId: string) =>
+  invoke<string>("export_ics", { timelineId });
+export type MarkdownGrouping = "year" | "decade" | "century" | "track";
+export interface MarkdownExportOptions {
+  grouping?: MarkdownGrouping;
+  frontMatter?: boolean;
+  includeConnections?: boolean;
+}
+export const exportMarkdown = (timelineId: string, options?: MarkdownExportOptions) =>
+  invoke<string>("export_markdown", { timelineId, options });
+export type SvgTheme = "light" | "dark";
+export interface SvgExportOptions {
+  width?: number | null;
+  start?: string | null;
+  end?: string | null;
+  theme?: SvgTheme;
+  fontFamily?: string;
+  visibleTracksOnly?: boolean;
+  minImportance?: number;
+  tags?: string[];
+  transparentBackground?: boolean;
+}
+export const exportSvg = (timelineId: string, options?: SvgExportOptions) =>
+  invoke<string>("export_svg", { timelineId, options });
+export interface PngExportOptions extends SvgExportOptions {
+  dpi?: number | null;
+  pixelWidth?: number | null;
+}
+export const exportPng = (timelineId: string, options?: PngExportOptions) =>
+  invoke<number[]>("export_png", { timelineId, options });
+export const exportHtml = (timelineId: string) =>
+  invoke<string>("export_html", { timelineId });
+export type PdfOutlineGrouping = "year" | "track";
+export type PdfPageSize = "a4" | "a3" | "letter";
+export type PdfOrientation = "portrait" | "landscape";
+export interface PdfExportOptions {
+  appendix?: boolean;
+  outline?: PdfOutlineGrouping;
+  pageSize?: PdfPageSize;
+  orientation?: PdfOrientation;
+  margin?: number;
+  fitToPage?: boolean;
+}
+export const exportPdf = (timelineId: string, options?: PdfExportOptions) =>
+  invoke<number[]>("export_pdf", { timelineId, options });
+export interface VaultExportReport {
+  directory: string;
+  notesWritten: number;
+}
+export const exportObsidianVault = (timelineId: string, directory: string) =>
+  invoke<VaultExportReport>("export_obsidian_vault", { timelineId, directory });
 export const saveFile = (path: string, content: string) =>
   invoke<void>("save_file", { path, content });
 
@@ -38,8 +38,43 @@ export interface TimelineEvent {
   updatedAt: string;
 }
 
+export interface UndatedEvent {
+  id: string;
+  trackId: string;
+  title: string;
+  startDate: string;
+}
+
 export type EventType = "point" | "range" | "milestone" | "era";
 
+/** State of an event before an update; changedBy/changedAt describe that update. */
+export interface EventRevision {
+  id: string;
+  eventId: string;
+  revision: number;
+  trackId: string;
+  title: string;
+  description: string;
+  startDate: string;
+  endDate: string | null;
+  eventType: EventType;
+  importance: number;
+  color: string | null;
+  icon: string | null;
+  imagePath: string | null;
+  externalLink: string | null;
+  tags: string;
+  source: string | null;
+  changedBy: string | null;
+  changedAt: string;
+}
+
+export interface FieldDiff {
+  field: string;
+  before: unknown;
+  after: unknown;
+}
+
 export interface Connection {
   id: string;
   timelineId: string;
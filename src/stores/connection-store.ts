@@ -42,7 +42,7 @@ export const useConnectionStore = create<ConnectionStore>((set, get) => ({
     try {
       const connection = await cmd.createConnection(input);
       set((s) => ({ connections: [...s.connections, connection] }));
-      useHistoryStore.getState().push({ type: "connection:create", before: null, after: connection });
+      void useHistoryStore.getState().refresh();
       useToastStore.getState().addToast({ type: "success", title: "Connection created" });
       return connection;
     } catch (e) {
@@ -55,7 +55,6 @@ export const useConnectionStore = create<ConnectionStore>((set, get) => ({
 
   updateConnection: async (input) => {
     set({ error: null });
-    const before = get().connections.find((c) => c.id === input.id);
     try {
       const updated = await cmd.updateConnection(input);
       set((s) => ({
@@ -63,9 +62,7 @@ export const useConnectionStore = create<ConnectionStore>((set, get) => ({
           c.id === updated.id ? updated : c
         ),
       }));
-      if (before) {
-        useHistoryStore.getState().push({ type: "connection:update", before, after: updated });
-      }
+      void useHistoryStore.getState().refresh();
       return updated;
     } catch (e) {
       const msg = String(e);
@@ -84,8 +81,8 @@ export const useConnectionStore = create<ConnectionStore>((set, get) => ({
         connections: s.connections.filter((c) => c.id !== id),
         selectedConnectionId: s.selectedConnectionId === id ? null : s.selectedConnectionId,
       }));
+      void useHistoryStore.getState().refresh();
       if (before) {
-        useHistoryStore.getState().push({ type: "connection:delete", before, after: null });
         useToastStore.getState().addToast({ type: "success", title: "Connection deleted" });
       }
     } catch (e) {
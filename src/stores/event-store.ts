@@ -1,11 +1,12 @@
 import { create } from "zustand";
-import type { TimelineEvent, CreateEventInput, UpdateEventInput, BulkUpdateInput } from "../lib/types";
+import type { TimelineEvent, UndatedEvent, CreateEventInput, UpdateEventInput, BulkUpdateInput } from "../lib/types";
 import * as cmd from "../lib/commands";
 import { useHistoryStore } from "./history-store";
 import { useToastStore } from "./toast-store";
 
 interface EventStore {
   events: TimelineEvent[];
+  undatedEvents: UndatedEvent[];
   selectedEventId: string | null;
   selectedEventIds: Set<string>;
   loading: boolean;
@@ -14,6 +15,7 @@ interface EventStore {
   loadEvents: (timelineId: string) => Promise<void>;
   createEvent: (input: CreateEventInput) => Promise<TimelineEvent>;
   updateEvent: (input: UpdateEventInput) => Promise<void>;
+  fixUndatedEvent: (id: string, startDate: string) => Promise<void>;
   deleteEvent: (id: string) => Promise<void>;
   selectEvent: (id: string | null) => void;
   toggleEventSelection: (id: string) => void;
@@ -26,6 +28,7 @@ interface EventStore {
 
 export const useEventStore = create<EventStore>((set, get) => ({
   events: [],
+  undatedEvents: [],
   selectedEventId: null,
   selectedEventIds: new Set<string>(),
   loading: false,
@@ -34,8 +37,11 @@ export const useEventStore = create<EventStore>((set, get) => ({
   loadEvents: async (timelineId) => {
     set({ loading: true, error: null });
     try {
-      const events = await cmd.listEvents(timelineId);
-      set({ events, loading: false });
+      const [events, undatedEvents] = await Promise.all([
+        cmd.listEvents(timelineId),
+        cmd.listUndatedEvents(timelineId),
+      ]);
+      set({ events, undatedEvents, loading: false });
     } catch (e) {
       const msg = String(e);
       set({ error: msg, loading: false });
@@ -48,7 +54,7 @@ export const useEventStore = create<EventStore>((set, get) => ({
     try {
       const event = await cmd.createEvent(input);
       set((s) => ({ events: [...s.events, event] }));
-      useHistoryStore.getState().push({ type: "event:create", before: null, after: event });
+      void useHistoryStore.getState().refresh();
       useToastStore.getState().addToast({ type: "success", title: `Created "${event.title}"` });
       return event;
     } catch (e) {
@@ -61,15 +67,12 @@ export const useEventStore = create<EventStore>((set, get) => ({
 
   updateEvent: async (input) => {
     set({ error: null });
-    const before = get().events.find((ev) => ev.id === input.id);
     try {
       const updated = await cmd.updateEvent(input);
       set((s) => ({
         events: s.events.map((ev) => (ev.id === updated.id ? updated : ev)),
       }));
-      if (before) {
-        useHistoryStore.getState().push({ type: "event:update", before, after: updated });
-      }
+      void useHistoryStore.getState().refresh();
     } catch (e) {
       const msg = String(e);
       set({ error: msg });
@@ -78,6 +81,24 @@ export const useEventStore = create<EventStore>((set, get) => ({
     }
   },
 
+  fixUndatedEvent: async (id, startDate) => {
+    set({ error: null });
+    try {
+      const fixed = await cmd.updateEvent({ id, startDate });
+      set((s) => ({
+        events: [...s.events, fixed],
+        undatedEvents: s.undatedEvents.filter((ev) => ev.id !== id),
+      }));
+      void useHistoryStore.getState().refresh();
+      useToastStore.getState().addToast({ type: "success", title: `Dated "${fixed.title}"` });
+    } catch (e) {
+      const msg = String(e);
+      set({ error: msg });
+      useToastStore.getState().addToast({ type: "error", title: "Failed to set start date", description: msg });
+      throw e;
+    }
+  },
+
   deleteEvent: async (id) => {
     set({ error: null });
     const before = get().events.find((ev) => ev.id === id);
@@ -92,8 +113,8 @@ export const useEventStore = create<EventStore>((set, get) => ({
           return next;
         })(),
       }));
+      void useHistoryStore.getState().refresh();
       if (before) {
-        useHistoryStore.getState().push({ type: "event:delete", before, after: null });
         useToastStore.getState().addToast({ type: "success", title: `Deleted "${before.title}"` });
       }
     } catch (e) {
@@ -133,10 +154,7 @@ export const useEventStore = create<EventStore>((set, get) => ({
         selectedEventIds: new Set<string>(),
         selectedEventId: null,
       }));
-      // Push a single history entry for the bulk delete
-      for (const ev of beforeEvents) {
-        useHistoryStore.getState().push({ type: "event:delete", before: ev, after: null });
-      }
+      void useHistoryStore.getState().refresh();
       useToastStore.getState().addToast({ type: "success", title: `Deleted ${beforeEvents.length} events` });
     } catch (e) {
       const msg = String(e);
@@ -156,6 +174,7 @@ export const useEventStore = create<EventStore>((set, get) => ({
         const events = await cmd.listEvents(timelineId);
         set({ events });
       }
+      void useHistoryStore.getState().refresh();
       useToastStore.getState().addToast({ type: "success", title: `Updated ${input.ids.length} events` });
     } catch (e) {
       const msg = String(e);
@@ -165,5 +184,5 @@ export const useEventStore = create<EventStore>((set, get) => ({
     }
   },
 
-  clearEvents: () => set({ events: [], selectedEventId: null, selectedEventIds: new Set<string>(), error: null }),
+  clearEvents: () => set({ events: [], undatedEvents: [], selectedEventId: null, selectedEventIds: new Set<string>(), error: null }),
 }));
@@ -39,7 +39,7 @@ export const useTrackStore = create<TrackStore>((set, get) => ({
     try {
       const track = await cmd.createTrack(input);
       set((s) => ({ tracks: [...s.tracks, track] }));
-      useHistoryStore.getState().push({ type: "track:create", before: null, after: track });
+      void useHistoryStore.getState().refresh();
       useToastStore.getState().addToast({ type: "success", title: `Created track "${track.name}"` });
       return track;
     } catch (e) {
@@ -52,15 +52,12 @@ export const useTrackStore = create<TrackStore>((set, get) => ({
 
   updateTrack: async (input) => {
     set({ error: null });
-    const before = get().tracks.find((t) => t.id === input.id);
     try {
       const updated = await cmd.updateTrack(input);
       set((s) => ({
         tracks: s.tracks.map((t) => (t.id === updated.id ? updated : t)),
       }));
-      if (before) {
-        useHistoryStore.getState().push({ type: "track:update", before, after: updated });
-      }
+      void useHistoryStore.getState().refresh();
     } catch (e) {
       const msg = String(e);
       set({ error: msg });
@@ -75,8 +72,8 @@ export const useTrackStore = create<TrackStore>((set, get) => ({
     try {
       await cmd.deleteTrack(id);
       set((s) => ({ tracks: s.tracks.filter((t) => t.id !== id) }));
+      void useHistoryStore.getState().refresh();
       if (before) {
-        useHistoryStore.getState().push({ type: "track:delete", before, after: null });
         useToastStore.getState().addToast({ type: "success", title: `Deleted track "${before.name}"` });
       }
     } catch (e) {
@@ -89,7 +86,6 @@ export const useTrackStore = create<TrackStore>((set, get) => ({
 
   reorderTracks: async (trackIds) => {
     set({ error: null });
-    const beforeOrder = get().tracks.map((t) => t.id);
     try {
       await cmd.reorderTracks(trackIds);
       set((s) => {
@@ -102,7 +98,7 @@ export const useTrackStore = create<TrackStore>((set, get) => ({
           .filter((t): t is Track => t !== null);
         return { tracks: reordered };
       });
-      useHistoryStore.getState().push({ type: "track:reorder", before: beforeOrder, after: trackIds });
+      void useHistoryStore.getState().refresh();
     } catch (e) {
       const msg = String(e);
       set({ error: msg });